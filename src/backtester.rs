@@ -1,18 +1,35 @@
 extern crate time;
 extern crate chrono;
 
+use self::chrono::prelude::{DateTime, Utc};
 use model::Model;
-use ohlcv::Ohlcv;
-use market_simulation::MarketSimulation;
+use ohlcv::{Ohlcv, TimeSlice, TimeSliceAligner};
+use market_simulation::{MarketSimulation, SimulateExecution};
 use portfolio::Portfolio;
 use strategy::{StrategyManager, StrategyError, StrategyCollection};
-use order::{GenerateOrderId, UUIDOrderIdGenerator};
+use order::{GenerateOrderId, UUIDOrderIdGenerator, Order, OrderStatus, OrderEvent, OrderEventKind, OrderValidator};
+use price::Money;
 
 
 pub struct Backtester {
-    market_simulation: MarketSimulation,
+    market_simulation: Box<SimulateExecution>,
     strategy_manager: StrategyManager,
-    order_id_generator: Box<GenerateOrderId>
+    order_id_generator: Box<GenerateOrderId>,
+    order_validator: Option<OrderValidator>,
+    initial_cash: Money,
+    run_incomplete_slices: bool
+}
+
+/// Record the creation of an order, and its rejection by the pre-trade checks
+fn record_creation(mut order: Order, datetime: &DateTime<Utc>) -> Order {
+    let mut events = vec![OrderEvent::new(*datetime, &order, OrderEventKind::Created)];
+    if let OrderStatus::Rejected(ref reason) = *order.status() {
+        events.push(OrderEvent::new(*datetime, &order, OrderEventKind::Rejected(reason.clone())));
+    }
+    for event in events {
+        order.add_event(event);
+    }
+    order
 }
 
 #[derive(Debug, Clone)]
@@ -25,57 +42,109 @@ impl Backtester {
 
     pub fn new() -> Self {
         Backtester {
-            market_simulation: MarketSimulation::new(),
+            market_simulation: Box::new(MarketSimulation::new()),
             strategy_manager: StrategyManager::new(),
-            order_id_generator: Box::new(UUIDOrderIdGenerator::new())
+            order_id_generator: Box::new(UUIDOrderIdGenerator::new()),
+            order_validator: None,
+            initial_cash: Money::zero(),
+            run_incomplete_slices: false
         }
     }
 
     pub fn run<'a, I>(&self, models: &'a Vec<Box<Model>>, ohlcv: I) -> Result<(Portfolio, StrategyCollection<'a>), BacktesterError>
         where I: Iterator<Item=Ohlcv>
     {
-        let mut portfolio = Portfolio::new();
+        self.run_time_slices(models, TimeSliceAligner::new(ohlcv))
+    }
+
+    /// Run the backtest on aligned time slices, strategies are run once per complete time slice,
+    /// after all of its bars have been given to the market simulation
+    pub fn run_time_slices<'a, I>(&self, models: &'a Vec<Box<Model>>, time_slices: I)
+        -> Result<(Portfolio, StrategyCollection<'a>), BacktesterError>
+        where I: Iterator<Item=TimeSlice>
+    {
+        let mut portfolio = Portfolio::new().set_cash(self.initial_cash);
+        let mut market_simulation = self.market_simulation.box_clone();
         let mut strategy_collection = self.strategy_manager.initialize_strategy_collection(models);
-        let mut previous_datetime = None;
+        self.strategy_manager.on_start(&mut strategy_collection);
 
-        for o in ohlcv {
-            let updates = self.market_simulation.update_orders(portfolio.active_orders().values(), &o);
+        for time_slice in time_slices {
+            let requests = market_simulation.process_requests(
+                &portfolio.active_orders().values().collect::<Vec<&Order>>(), time_slice.datetime()
+            );
+            portfolio.apply_requests(&requests);
 
-            portfolio.update_orders(&updates);
+            // forward filled bars did not trade, they can't execute orders
+            for o in time_slice.traded_bars() {
+                let updates = market_simulation.update_orders(&portfolio.active_orders().values().collect::<Vec<&Order>>(), o);
+                portfolio.update_orders(&updates);
+            }
+
+            // strategies learn about the updates of their orders once they are reported
+            let reports = market_simulation.reports(time_slice.datetime());
             self.strategy_manager.update_strategies(
                 &mut strategy_collection,
-                &updates.iter().map(
-                    |update| (portfolio.closed_orders().get(update.0).unwrap(), update.1.clone())
+                &reports.iter().map(
+                    |report| (portfolio.order(&report.0).unwrap(), report.1.clone())
                 ).collect()
             );
 
-            // run strategies only once per date, multiple ohlcv can have the same datetime
-            // when there is more than one symbol
-            match previous_datetime {
-                Some(ref datetime) if datetime == o.datetime() => (),
-                _ => {
-                    portfolio.add_orders(
-                        self.strategy_manager.run_strategies(
-                            &mut strategy_collection, o.datetime(), &self.order_id_generator
-                        )
-                            .map_err(|e| BacktesterError::StrategyError(e))?
-                            .into_iter().map(|order_builder| order_builder.build().unwrap())
-                            .collect()
-                    );
-                }
-            }
+            self.strategy_manager.on_bar(&mut strategy_collection, &time_slice);
+
+            let order_builders = if time_slice.is_complete() || self.run_incomplete_slices {
+                self.strategy_manager.run_strategies(
+                    &mut strategy_collection, time_slice.datetime(), &self.order_id_generator
+                ).map_err(BacktesterError::StrategyError)?
+            } else {
+                vec![]
+            };
+            let orders: Vec<Order> = order_builders.into_iter()
+                .map(|order_builder| order_builder.build().unwrap())
+                .collect();
+            let orders: Vec<Order> = match self.order_validator {
+                Some(ref order_validator) => order_validator.validate_orders(orders, &portfolio, &time_slice),
+                None => orders
+            }.into_iter().map(|order| record_creation(order, time_slice.datetime())).collect();
+            let new_orders: Vec<(Order, OrderStatus)> = orders.iter()
+                .filter(|order| matches!(*order.status(), OrderStatus::NotSent | OrderStatus::Rejected(_)))
+                .map(|order| (order.clone(), order.status().clone()))
+                .collect();
+
+            market_simulation.send_orders(
+                &orders.iter().filter(|order| *order.status() == OrderStatus::NotSent).collect::<Vec<&Order>>(),
+                time_slice.datetime()
+            );
+            portfolio.add_orders(orders);
+            self.strategy_manager.update_strategies(
+                &mut strategy_collection,
+                &new_orders.iter().map(|new_order| (&new_order.0, new_order.1.clone())).collect()
+            );
 
-            previous_datetime = Some(*o.datetime());
+            let requests = self.strategy_manager.order_requests(&mut strategy_collection, time_slice.datetime());
+            let (requests, rejected_requests) = match self.order_validator {
+                Some(ref order_validator) => order_validator.validate_requests(requests, &portfolio, &time_slice),
+                None => (requests, vec![])
+            };
+            market_simulation.add_requests(requests, time_slice.datetime());
+            portfolio.add_events(market_simulation.drain_events());
+            let rejection_events = rejected_requests.into_iter()
+                .filter_map(|(request, reason)| portfolio.order(request.order_id()).map(|order| OrderEvent::new(
+                    *time_slice.datetime(), order, OrderEventKind::ModifyRejected(reason)
+                )))
+                .collect();
+            portfolio.add_events(rejection_events);
         }
 
+        self.strategy_manager.on_end(&mut strategy_collection);
         Ok((portfolio, strategy_collection))
     }
 
-    pub fn market_simulation(&self) -> &MarketSimulation {
-        &self.market_simulation
+    pub fn market_simulation(&self) -> &SimulateExecution {
+        &*self.market_simulation
     }
 
-    pub fn set_market_simulation(mut self, market_simulation: MarketSimulation) -> Self {
+    /// Simulation of the execution of the orders, a `MarketSimulation` by default
+    pub fn set_market_simulation(mut self, market_simulation: Box<SimulateExecution>) -> Self {
         self.market_simulation = market_simulation;
         self
     }
@@ -89,37 +158,77 @@ impl Backtester {
         self
     }
 
-    pub fn order_id_generator(&self) -> &Box<GenerateOrderId> {
-        &self.order_id_generator
+    pub fn order_id_generator(&self) -> &GenerateOrderId {
+        &*self.order_id_generator
     }
 
     pub fn set_order_id_generator(mut self, order_id_generator: Box<GenerateOrderId>) -> Self {
         self.order_id_generator = order_id_generator;
         self
     }
+
+    /// Pre-trade checks of the orders created by strategies and of their modify requests, no order
+    /// or request is rejected without it
+    pub fn order_validator(&self) -> &Option<OrderValidator> {
+        &self.order_validator
+    }
+
+    pub fn set_order_validator(mut self, order_validator: Option<OrderValidator>) -> Self {
+        self.order_validator = order_validator;
+        self
+    }
+
+    pub fn initial_cash(&self) -> Money {
+        self.initial_cash
+    }
+
+    pub fn set_initial_cash(mut self, initial_cash: Money) -> Self {
+        self.initial_cash = initial_cash;
+        self
+    }
+
+    /// Whether strategies are also run on time slices with missing bars, they are only run on
+    /// complete time slices by default
+    pub fn run_incomplete_slices(&self) -> bool {
+        self.run_incomplete_slices
+    }
+
+    pub fn set_run_incomplete_slices(mut self, value: bool) -> Self {
+        self.run_incomplete_slices = value;
+        self
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
     extern crate chrono;
-    use std::cell::Cell;
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
     use self::chrono::prelude::{DateTime, Utc, TimeZone};
     use backtester::Backtester;
     use model::{Model, ModelId};
     use strategy::Strategy;
     use signal::detector::{DetectSignal, DetectSignalError};
     use direction::Direction;
-    use order::{Order, OrderId, OrderBuilder, OrderKind, OrderStatus};
-    use order::policy::MarketOrderPolicy;
+    use order::{Order, OrderId, OrderBuilder, OrderKind, OrderStatus, OrderRequest, OrderEvent, OrderEventKind, RejectionReason,
+                CancellationReason, Bracket, BracketDistance, SymbolRules};
+    use order::policy::{MarketOrderPolicy, SimpleOrderPolicy};
     use strategy::StrategyId;
     use execution::Execution;
     use symbol::SymbolId;
     use signal::Signal;
+    use quantity::Quantity;
+    use price::Price;
+    use ohlcv::MissingBarPolicy;
+    use ohlcv::transform::{Renko, RenkoBrickSize};
+    use market_simulation::{MarketSimulation, SimulateExecution, Latency, LatencyModel};
+    use std::collections::HashMap;
+    use self::chrono::Duration;
 
     pub struct AlwaysDetectSignal { direction: Direction }
     impl DetectSignal for AlwaysDetectSignal {
-        fn detect_signal(&self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
             let signal = Signal::new(
                 SymbolId::from("eur/usd"), self.direction,
                 datetime.clone(), String::from("always detect signal")
@@ -157,7 +266,7 @@ mod test {
 
     impl GenerateOrderId for IncrementalOrderIdGenerator {
         fn get_id(&self, _strategy_id: StrategyId, _signal: &Signal,
-                  _order_builder: &OrderBuilder) -> OrderId
+                  _order_builder: &OrderBuilder, _index: usize) -> OrderId
         {
             let id = self.counter.get();
             self.counter.set(id + 1);
@@ -166,6 +275,238 @@ mod test {
     }
 
 
+    pub struct OrderOnCompleteSlice;
+    impl Model for OrderOnCompleteSlice {
+
+        fn id(&self) -> ModelId { ModelId::from("order on complete slice") }
+
+        fn entry_strategy(&self) -> Strategy {
+            Strategy::new(
+                Box::new(AlwaysDetectSignal { direction: Direction::Long }),
+                Box::new(MarketOrderPolicy::new())
+            )
+        }
+
+        fn exit_strategies(&self, _order: &Order) -> Vec<Strategy> {
+            vec![]
+        }
+
+    }
+
+    /// Record the lifecycle events of the entry detector
+    pub struct RecordingDetectSignal { events: Rc<RefCell<Vec<String>>> }
+    impl DetectSignal for RecordingDetectSignal {
+        fn detect_signal(&mut self, _datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+            self.events.borrow_mut().push(String::from("detect_signal"));
+            Ok(None)
+        }
+
+        fn on_start(&mut self) {
+            self.events.borrow_mut().push(String::from("on_start"));
+        }
+
+        fn on_bar(&mut self, _time_slice: &TimeSlice) {
+            self.events.borrow_mut().push(String::from("on_bar"));
+        }
+
+        fn on_end(&mut self) {
+            self.events.borrow_mut().push(String::from("on_end"));
+        }
+    }
+
+    pub struct Recording { events: Rc<RefCell<Vec<String>>> }
+    impl Model for Recording {
+
+        fn id(&self) -> ModelId { ModelId::from("recording") }
+
+        fn entry_strategy(&self) -> Strategy {
+            Strategy::new(
+                Box::new(RecordingDetectSignal { events: self.events.clone() }),
+                Box::new(MarketOrderPolicy::new())
+            )
+        }
+
+        fn exit_strategies(&self, _order: &Order) -> Vec<Strategy> {
+            vec![]
+        }
+
+    }
+
+    /// Record the eur/usd close of every time slice
+    pub struct RecordCloses { closes: Rc<RefCell<Vec<f64>>> }
+    impl DetectSignal for RecordCloses {
+        fn detect_signal(&mut self, _datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+            Ok(None)
+        }
+
+        fn on_bar(&mut self, time_slice: &TimeSlice) {
+            if let Some(ohlcv) = time_slice.ohlcv(&SymbolId::from("eur/usd")) {
+                self.closes.borrow_mut().push(ohlcv.close());
+            }
+        }
+    }
+
+    pub struct RecordingCloses { closes: Rc<RefCell<Vec<f64>>> }
+    impl Model for RecordingCloses {
+
+        fn id(&self) -> ModelId { ModelId::from("recording closes") }
+
+        fn entry_strategy(&self) -> Strategy {
+            Strategy::new(
+                Box::new(RecordCloses { closes: self.closes.clone() }),
+                Box::new(MarketOrderPolicy::new())
+            )
+        }
+
+        fn exit_strategies(&self, _order: &Order) -> Vec<Strategy> {
+            vec![]
+        }
+
+    }
+
+    /// Simulation filling market orders at the close of their bar, ignoring requests
+    #[derive(Clone)]
+    pub struct FillAtClose { reports: Vec<(OrderId, OrderStatus)> }
+    impl SimulateExecution for FillAtClose {
+        fn update_orders(&mut self, orders: &[&Order], ohlcv: &Ohlcv) -> HashMap<OrderId, OrderStatus> {
+            let updates: HashMap<OrderId, OrderStatus> = orders.iter()
+                .filter(|order| order.symbol_id() == ohlcv.symbol_id())
+                .map(|order| (order.id().clone(), OrderStatus::Filled(Execution::new(
+                    order.symbol_id().clone(), order.quantity(), Price::new(ohlcv.close()), *ohlcv.datetime()
+                ))))
+                .collect();
+            self.reports.extend(updates.clone());
+            updates
+        }
+
+        fn add_requests(&mut self, _requests: Vec<OrderRequest>, _datetime: &DateTime<Utc>) {}
+
+        fn process_requests(&mut self, _orders: &[&Order], _datetime: &DateTime<Utc>) -> Vec<OrderRequest> {
+            vec![]
+        }
+
+        fn reports(&mut self, _datetime: &DateTime<Utc>) -> Vec<(OrderId, OrderStatus)> {
+            self.reports.drain(..).collect()
+        }
+
+        fn box_clone(&self) -> Box<SimulateExecution> {
+            Box::new(self.clone())
+        }
+    }
+
+    /// Send a single limit order and cancel it once it is resting
+    pub struct CancelRestingOrder { sent: bool, resting: Vec<OrderId> }
+    impl DetectSignal for CancelRestingOrder {
+        fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+            if self.sent {
+                return Ok(None);
+            }
+            self.sent = true;
+            Ok(Some(Signal::new(SymbolId::from("eur/usd"), Direction::Long, *datetime, String::from("resting"))))
+        }
+
+        fn on_order_update(&mut self, order: &Order, status: &OrderStatus) {
+            if let OrderStatus::NotSent = *status {
+                self.resting.push(order.id().clone());
+            }
+        }
+
+        fn order_requests(&mut self, _datetime: &DateTime<Utc>) -> Vec<OrderRequest> {
+            self.resting.drain(..).map(OrderRequest::Cancel).collect()
+        }
+    }
+
+    pub struct CancelOrder;
+    impl Model for CancelOrder {
+
+        fn id(&self) -> ModelId { ModelId::from("cancel order") }
+
+        fn entry_strategy(&self) -> Strategy {
+            Strategy::new(
+                Box::new(CancelRestingOrder { sent: false, resting: vec![] }),
+                Box::new(SimpleOrderPolicy::new(OrderKind::LimitOrder(Price::new(0.5))).set_quantity(Quantity::new(1.)))
+            )
+        }
+
+        fn exit_strategies(&self, _order: &Order) -> Vec<Strategy> {
+            vec![]
+        }
+
+    }
+
+    /// Enter once and move the stop of the bracket to breakeven on the bar after the fill
+    pub struct BreakevenAfterFill { bracket: Bracket, sent: bool, filled: bool }
+    impl DetectSignal for BreakevenAfterFill {
+        fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+            if self.sent {
+                return Ok(None);
+            }
+            self.sent = true;
+            Ok(Some(Signal::new(SymbolId::from("eur/usd"), Direction::Long, *datetime, String::from("entry"))))
+        }
+
+        fn on_bar(&mut self, _time_slice: &TimeSlice) {
+            if self.filled {
+                self.bracket.modify_stop(Some(BracketDistance::Amount(Price::zero())));
+            }
+        }
+
+        fn on_order_update(&mut self, _order: &Order, status: &OrderStatus) {
+            if let OrderStatus::Filled(_) = *status {
+                self.filled = true;
+            }
+        }
+    }
+
+    pub struct BracketModel { bracket: Bracket }
+    impl Model for BracketModel {
+
+        fn id(&self) -> ModelId { ModelId::from("bracket") }
+
+        fn entry_strategy(&self) -> Strategy {
+            Strategy::new(
+                Box::new(BreakevenAfterFill { bracket: self.bracket.clone(), sent: false, filled: false }),
+                Box::new(SimpleOrderPolicy::new(OrderKind::MarketOrder).set_quantity(Quantity::new(1.)))
+            )
+        }
+
+        fn exit_strategies(&self, order: &Order) -> Vec<Strategy> {
+            self.bracket.exit_strategies(order)
+        }
+
+    }
+
+    #[test]
+    fn test_run_lifecycle() {
+        let events = Rc::new(RefCell::new(vec![]));
+        let models: Vec<Box<Model>> = vec![Box::new(Recording { events: events.clone() })];
+        Backtester::new().run(
+            &models,
+            vec![
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), 1., 1., 1., 1., Quantity::new(1.)),
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 1., 1., 1., 1., Quantity::new(1.))
+            ].into_iter()
+        ).unwrap();
+        assert_eq!(
+            *events.borrow(),
+            vec!["on_start", "on_bar", "detect_signal", "on_bar", "detect_signal", "on_end"]
+        );
+    }
+
+    #[test]
+    fn test_run_renko() {
+        let closes = Rc::new(RefCell::new(vec![]));
+        let models: Vec<Box<Model>> = vec![Box::new(RecordingCloses { closes: closes.clone() })];
+        let bricks = Renko::new(vec![
+            Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), 10., 10., 10., 10., Quantity::new(1.)),
+            // three bricks from a single bar
+            Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 10., 13., 10., 13., Quantity::new(1.)),
+            Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 10), 13., 14., 13., 14., Quantity::new(1.))
+        ].into_iter(), RenkoBrickSize::Fixed(1.));
+        Backtester::new().run(&models, bricks).unwrap();
+        assert_eq!(*closes.borrow(), vec![11., 12., 13., 14.]);
+    }
+
     #[test]
     fn test_run() {
         let backtester = Backtester::new().set_order_id_generator(
@@ -177,10 +518,10 @@ mod test {
         let (portfolio, strategy_collection) = backtester.run(
             &models,
             vec![
-                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), 1., 1., 1., 1., 0),
-                Ohlcv::new(SymbolId::from("usd/jpy"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), 1., 1., 1., 1., 0),
-                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 1., 1.5, 1., 1.5, 3),
-                Ohlcv::new(SymbolId::from("usd/jpy"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 1., 1.5, 1., 1.5, 3)
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), 1., 1., 1., 1., Quantity::new(0.)),
+                Ohlcv::new(SymbolId::from("usd/jpy"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), 1., 1., 1., 1., Quantity::new(0.)),
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 1., 1.5, 1., 1.5, Quantity::new(3.)),
+                Ohlcv::new(SymbolId::from("usd/jpy"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 1., 1.5, 1., 1.5, Quantity::new(3.))
             ].into_iter()
         ).unwrap();
 
@@ -189,32 +530,55 @@ mod test {
         let closed_orders = portfolio.closed_orders().values().collect::<Vec<&Order>>();
         assert_eq!(closed_orders.len(), 1);
 
+        let signal = Some(Signal::new(
+            SymbolId::from("eur/usd"), Direction::Long,
+            Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), String::from("always detect signal")
+        ).metadata());
+        let entry_strategy_id = *strategy_collection.entry_strategies[0].id();
+        let exit_strategy_id = *strategy_collection.exit_strategies.keys().next().unwrap();
+        let first_datetime = Utc.ymd(2017, 12, 29).and_hms(12, 0, 0);
+        let second_datetime = Utc.ymd(2017, 12, 29).and_hms(12, 0, 5);
+        let execution = Execution::new(SymbolId::from("eur/usd"), Quantity::zero(), Price::new(1.), second_datetime);
+        let with_events = |mut order: Order, events: Vec<(DateTime<Utc>, OrderEventKind)>| {
+            for (datetime, kind) in events {
+                let event = OrderEvent::new(datetime, &order, kind);
+                order.add_event(event);
+            }
+            order
+        };
         let expected_active_orders: Vec<Order> = vec![
             // Long order from the second detection made by the entry strategy
-            OrderBuilder::unallocated(
-                OrderKind::MarketOrder, SymbolId::from("eur/usd"), Direction::Long
-            ).set_id(String::from("1")).build().unwrap(),
+            with_events(
+                OrderBuilder::unallocated(
+                    OrderKind::MarketOrder, SymbolId::from("eur/usd"), Direction::Long
+                ).set_id(String::from("1")).set_signal(signal.clone()).set_strategy_id(Some(entry_strategy_id)).build().unwrap(),
+                vec![(second_datetime, OrderEventKind::Created), (second_datetime, OrderEventKind::Sent)]
+            ),
             // Short order from the exit strategy linked to the first entry order
-            OrderBuilder::unallocated(
-                OrderKind::MarketOrder, SymbolId::from("eur/usd"), Direction::Short
-            ).set_id(String::from("2")).build().unwrap()
+            with_events(
+                OrderBuilder::unallocated(
+                    OrderKind::MarketOrder, SymbolId::from("eur/usd"), Direction::Short
+                ).set_id(String::from("2")).set_signal(signal.clone()).set_strategy_id(Some(exit_strategy_id)).build().unwrap(),
+                vec![(second_datetime, OrderEventKind::Created), (second_datetime, OrderEventKind::Sent)]
+            )
         ];
         let expected_closed_orders: Vec<Order> = vec![
             // First entry order has been filled
-            OrderBuilder::unallocated(
-                OrderKind::MarketOrder, SymbolId::from("eur/usd"), Direction::Long
+            with_events(
+                OrderBuilder::unallocated(
+                    OrderKind::MarketOrder, SymbolId::from("eur/usd"), Direction::Long
+                )
+                    .set_id(String::from("0"))
+                    .set_signal(signal.clone())
+                    .set_strategy_id(Some(entry_strategy_id))
+                    .set_status(OrderStatus::Filled(execution.clone()))
+                    .build().unwrap(),
+                vec![
+                    (first_datetime, OrderEventKind::Created),
+                    (first_datetime, OrderEventKind::Sent),
+                    (second_datetime, OrderEventKind::Filled(execution.clone()))
+                ]
             )
-                .set_id(String::from("0"))
-                .set_status(
-                    OrderStatus::Filled(
-                        Execution::new(
-                            SymbolId::from("eur/usd"),
-                            0,
-                            1.,
-                            Utc.ymd(2017, 12, 29).and_hms(12, 0, 5)
-                        )
-                    )
-                ).build().unwrap()
         ];
 
         active_orders.sort_by_key(|order| order.id());
@@ -228,4 +592,202 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_run_time_slices() {
+        let backtester = Backtester::new().set_order_id_generator(
+            Box::new(IncrementalOrderIdGenerator { counter: Cell::new(0) })
+        );
+        let ohlcv = vec![
+            Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), 1., 1., 1., 1., Quantity::new(0.)),
+            Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 1., 1.5, 1., 1.5, Quantity::new(3.)),
+            Ohlcv::new(SymbolId::from("usd/jpy"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 1., 1.5, 1., 1.5, Quantity::new(3.)),
+            Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 10), 1., 1.5, 1., 1.5, Quantity::new(3.))
+        ];
+        let models: Vec<Box<Model>> = vec![Box::new(OrderOnCompleteSlice {})];
+
+        // usd/jpy is missing from the first and the last time slices
+        let (portfolio, _) = backtester.run_time_slices(
+            &models,
+            TimeSliceAligner::new(ohlcv.clone().into_iter())
+                .set_symbols(vec![SymbolId::from("eur/usd"), SymbolId::from("usd/jpy")])
+        ).unwrap();
+        assert_eq!(portfolio.active_orders().len() + portfolio.closed_orders().len(), 1);
+
+        // usd/jpy is forward filled in the last time slice
+        let (portfolio, _) = backtester.run_time_slices(
+            &models,
+            TimeSliceAligner::new(ohlcv.clone().into_iter())
+                .set_symbols(vec![SymbolId::from("eur/usd"), SymbolId::from("usd/jpy")])
+                .set_missing_bar_policy(MissingBarPolicy::ForwardFill)
+        ).unwrap();
+        assert_eq!(portfolio.active_orders().len() + portfolio.closed_orders().len(), 2);
+
+        let backtester = backtester.set_run_incomplete_slices(true);
+        let (portfolio, _) = backtester.run_time_slices(
+            &models,
+            TimeSliceAligner::new(ohlcv.into_iter())
+                .set_symbols(vec![SymbolId::from("eur/usd"), SymbolId::from("usd/jpy")])
+        ).unwrap();
+        assert_eq!(portfolio.active_orders().len() + portfolio.closed_orders().len(), 3);
+    }
+
+    /// Test that orders failing the pre-trade checks are rejected instead of being sent
+    #[test]
+    fn test_run_order_validator() {
+        let backtester = Backtester::new()
+            .set_order_id_generator(Box::new(IncrementalOrderIdGenerator { counter: Cell::new(0) }))
+            .set_order_validator(Some(OrderValidator::new()));
+        let models: Vec<Box<Model>> = vec![Box::new(OrderEveryCandle {})];
+        let (portfolio, strategy_collection) = backtester.run(
+            &models,
+            vec![
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), 1., 1., 1., 1., Quantity::new(0.)),
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 1., 1.5, 1., 1.5, Quantity::new(3.))
+            ].into_iter()
+        ).unwrap();
+
+        // the orders of the model have no quantity
+        assert!(portfolio.active_orders().is_empty());
+        assert_eq!(portfolio.closed_orders().len(), 2);
+        for order in portfolio.closed_orders().values() {
+            assert_eq!(order.status(), &OrderStatus::Rejected(RejectionReason::InvalidQuantity));
+        }
+        assert!(strategy_collection.exit_strategies.is_empty());
+    }
+
+    #[test]
+    fn test_run_cancel_request() {
+        let backtester = Backtester::new()
+            .set_order_id_generator(Box::new(IncrementalOrderIdGenerator { counter: Cell::new(0) }));
+        let models: Vec<Box<Model>> = vec![Box::new(CancelOrder {})];
+        let (portfolio, _strategy_collection) = backtester.run(
+            &models,
+            vec![
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), 1., 1., 1., 1., Quantity::new(1.)),
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 1., 1., 1., 1., Quantity::new(1.))
+            ].into_iter()
+        ).unwrap();
+
+        assert!(portfolio.active_orders().is_empty());
+        let order = portfolio.closed_orders().get("0").unwrap();
+        assert_eq!(order.status(), &OrderStatus::Cancelled(CancellationReason::Requested));
+        let events = order.events().iter()
+            .map(|event| (*event.datetime(), event.kind().clone()))
+            .collect::<Vec<(DateTime<Utc>, OrderEventKind)>>();
+        assert_eq!(events, vec![
+            (Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), OrderEventKind::Created),
+            (Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), OrderEventKind::Sent),
+            (Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), OrderEventKind::Cancelled(CancellationReason::Requested))
+        ]);
+        for event in order.events() {
+            assert_eq!(event.strategy_id(), order.strategy_id());
+        }
+        assert!(order.strategy_id().is_some());
+    }
+
+    #[test]
+    fn test_run_modify_bracket() {
+        let backtester = Backtester::new()
+            .set_order_id_generator(Box::new(IncrementalOrderIdGenerator { counter: Cell::new(0) }));
+        let bracket = Bracket::new()
+            .set_stop(Some(BracketDistance::Amount(Price::new(0.5))))
+            .set_target(Some(BracketDistance::Amount(Price::new(0.5))));
+        let models: Vec<Box<Model>> = vec![Box::new(BracketModel { bracket })];
+        let (portfolio, _strategy_collection) = backtester.run(
+            &models,
+            vec![
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), 1., 1., 1., 1., Quantity::new(1.)),
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 1., 1., 1., 1., Quantity::new(1.)),
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 10), 1., 1., 1., 1., Quantity::new(1.))
+            ].into_iter()
+        ).unwrap();
+
+        // the stop leg is sent at 0.5 below the fill, then modified through the market simulation
+        let stop = portfolio.active_orders().get("1").unwrap();
+        assert_eq!(stop.kind(), &OrderKind::StopOrder(Price::new(1.)));
+        assert_eq!(portfolio.active_orders().get("2").unwrap().kind(), &OrderKind::LimitOrder(Price::new(1.5)));
+        let events = stop.events().iter()
+            .map(|event| (*event.datetime(), event.kind().clone()))
+            .collect::<Vec<(DateTime<Utc>, OrderEventKind)>>();
+        assert_eq!(events, vec![
+            (Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), OrderEventKind::Created),
+            (Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), OrderEventKind::Sent),
+            (Utc.ymd(2017, 12, 29).and_hms(12, 0, 10), OrderEventKind::Modified(OrderKind::StopOrder(Price::new(1.)), Quantity::new(1.)))
+        ]);
+    }
+
+    #[test]
+    fn test_run_reject_modification() {
+        let symbols = [(SymbolId::from("eur/usd"), SymbolRules::new().set_tick_size(Some(0.3)))].iter().cloned().collect();
+        let backtester = Backtester::new()
+            .set_order_id_generator(Box::new(IncrementalOrderIdGenerator { counter: Cell::new(0) }))
+            .set_order_validator(Some(OrderValidator::new().set_symbols(Some(symbols))));
+        let bracket = Bracket::new()
+            .set_stop(Some(BracketDistance::Amount(Price::new(0.4))))
+            .set_target(Some(BracketDistance::Amount(Price::new(0.5))));
+        let models: Vec<Box<Model>> = vec![Box::new(BracketModel { bracket })];
+        let (portfolio, _strategy_collection) = backtester.run(
+            &models,
+            vec![
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), 1., 1., 1., 1., Quantity::new(1.)),
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 1., 1., 1., 1., Quantity::new(1.)),
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 10), 1., 1., 1., 1., Quantity::new(1.))
+            ].into_iter()
+        ).unwrap();
+
+        // the breakeven stop is off the tick size, the stop leg keeps its price
+        let stop = portfolio.active_orders().get("1").unwrap();
+        assert_eq!(stop.kind(), &OrderKind::StopOrder(Price::new(0.6)));
+        assert_eq!(
+            stop.events().last().map(|event| (*event.datetime(), event.kind().clone())),
+            Some((Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), OrderEventKind::ModifyRejected(RejectionReason::OffTickPrice)))
+        );
+    }
+
+    #[test]
+    fn test_run_latency() {
+        let latency_model = LatencyModel::new()
+            .set_order_latency(Latency::Fixed(Duration::seconds(7)))
+            .set_report_latency(Latency::Fixed(Duration::seconds(10)));
+        let backtester = Backtester::new()
+            .set_order_id_generator(Box::new(IncrementalOrderIdGenerator { counter: Cell::new(0) }))
+            .set_market_simulation(Box::new(MarketSimulation::new().set_latency_model(Some(latency_model))));
+        let models: Vec<Box<Model>> = vec![Box::new(OrderEveryCandle {})];
+        let (portfolio, strategy_collection) = backtester.run(
+            &models,
+            vec![
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), 1., 1., 1., 1., Quantity::new(1.)),
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 2., 2., 2., 2., Quantity::new(1.)),
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 10), 3., 3., 3., 3., Quantity::new(1.))
+            ].into_iter()
+        ).unwrap();
+
+        // the first order reaches the market after the second bar
+        let first_order = portfolio.closed_orders().get("0").unwrap();
+        assert_eq!(first_order.execution().unwrap().price(), &Price::new(3.));
+        assert_eq!(first_order.execution().unwrap().datetime(), &Utc.ymd(2017, 12, 29).and_hms(12, 0, 10));
+        assert_eq!(portfolio.active_orders().len(), 2);
+        // its fill is not reported yet, so there is no exit strategy
+        assert!(strategy_collection.exit_strategies.is_empty());
+    }
+
+    #[test]
+    fn test_run_custom_market_simulation() {
+        let backtester = Backtester::new()
+            .set_order_id_generator(Box::new(IncrementalOrderIdGenerator { counter: Cell::new(0) }))
+            .set_market_simulation(Box::new(FillAtClose { reports: vec![] }));
+        let models: Vec<Box<Model>> = vec![Box::new(OrderEveryCandle {})];
+        let (portfolio, strategy_collection) = backtester.run(
+            &models,
+            vec![
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), 1., 1., 1., 1., Quantity::new(1.)),
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 1., 2., 1., 1.5, Quantity::new(1.))
+            ].into_iter()
+        ).unwrap();
+
+        let first_order = portfolio.closed_orders().get("0").unwrap();
+        assert_eq!(first_order.execution().unwrap().price(), &Price::new(1.5));
+        assert_eq!(strategy_collection.exit_strategies.len(), 1);
+    }
+
 }
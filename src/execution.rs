@@ -1,17 +1,19 @@
 extern crate chrono;
 use self::chrono::prelude::{DateTime, Utc};
 use symbol::SymbolId;
+use quantity::Quantity;
+use price::Price;
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub struct Execution {
     symbol_id: SymbolId,
-    quantity: u32,
-    price: f64,
+    quantity: Quantity,
+    price: Price,
     datetime: DateTime<Utc>
 }
 
 impl Execution {
-    pub fn new(symbol_id: SymbolId, quantity: u32, price: f64, datetime: DateTime<Utc>) -> Self {
+    pub fn new(symbol_id: SymbolId, quantity: Quantity, price: Price, datetime: DateTime<Utc>) -> Self {
         Execution {
             symbol_id,
             quantity,
@@ -24,15 +26,49 @@ impl Execution {
         &self.symbol_id
     }
 
-    pub fn quantity(&self) -> &u32 {
+    pub fn quantity(&self) -> &Quantity {
         &self.quantity
     }
 
-    pub fn price(&self) -> &f64 {
+    pub fn price(&self) -> &Price {
         &self.price
     }
 
     pub fn datetime(&self) -> &DateTime<Utc> {
         &self.datetime
     }
-}
\ No newline at end of file
+
+    /// Execution of the total quantity at the volume weighted average price, dated at the last
+    /// execution
+    pub fn average(executions: &[Execution]) -> Option<Execution> {
+        let last = executions.last()?;
+        let quantity = executions.iter().map(|execution| execution.quantity).sum::<Quantity>();
+        let price = if quantity.is_zero() {
+            last.price
+        }
+        else {
+            executions.iter().map(|execution| execution.price * execution.quantity).sum::<Price>() / quantity
+        };
+        Some(Execution::new(last.symbol_id.clone(), quantity, price, last.datetime))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use self::chrono::prelude::TimeZone;
+
+    #[test]
+    fn average() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let executions = vec![
+            Execution::new(symbol_id.clone(), Quantity::new(1.), Price::new(10.), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0)),
+            Execution::new(symbol_id.clone(), Quantity::new(3.), Price::new(14.), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0))
+        ];
+        assert_eq!(
+            Execution::average(&executions),
+            Some(Execution::new(symbol_id.clone(), Quantity::new(4.), Price::new(13.), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0)))
+        );
+        assert_eq!(Execution::average(&[]), None);
+    }
+}
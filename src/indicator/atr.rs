@@ -0,0 +1,76 @@
+use ohlcv::Ohlcv;
+
+/// Average true range, smoothed with Wilder's moving average
+#[derive(Clone, PartialEq, Debug)]
+pub struct Atr {
+    period: usize,
+    previous_close: Option<f64>,
+    true_ranges: Vec<f64>,
+    value: Option<f64>
+}
+
+impl Atr {
+
+    pub fn new(period: usize) -> Atr {
+        Atr {
+            period,
+            previous_close: None,
+            true_ranges: vec![],
+            value: None
+        }
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Current value, available once `period` bars have been given
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+
+    pub fn update(&mut self, ohlcv: &Ohlcv) -> Option<f64> {
+        let true_range = match self.previous_close {
+            Some(close) => (ohlcv.high() - ohlcv.low())
+                .max((ohlcv.high() - close).abs())
+                .max((ohlcv.low() - close).abs()),
+            None => ohlcv.high() - ohlcv.low()
+        };
+        self.previous_close = Some(ohlcv.close());
+
+        self.value = match self.value {
+            Some(atr) => Some((atr * (self.period - 1) as f64 + true_range) / self.period as f64),
+            None => {
+                self.true_ranges.push(true_range);
+                if self.true_ranges.len() == self.period {
+                    Some(self.true_ranges.iter().sum::<f64>() / self.period as f64)
+                }
+                else {
+                    None
+                }
+            }
+        };
+        self.value
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+    extern crate chrono;
+    use super::*;
+    use self::chrono::prelude::{Utc, TimeZone};
+    use symbol::SymbolId;
+    use quantity::Quantity;
+
+    #[test]
+    fn update() {
+        let mut atr = Atr::new(2);
+        let symbol_id = SymbolId::from("eur/usd");
+        assert_eq!(atr.update(&Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 1., 2., 1., 2., Quantity::new(1.))), None);
+        // true range uses the previous close: max(1, |4 - 2|, |3 - 2|) = 2
+        assert_eq!(atr.update(&Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0), 3., 4., 3., 3., Quantity::new(1.))), Some(1.5));
+        assert_eq!(atr.update(&Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 2, 0), 3., 3.5, 3., 3., Quantity::new(1.))), Some(1.));
+        assert_eq!(atr.value(), Some(1.));
+    }
+}
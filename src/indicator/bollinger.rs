@@ -0,0 +1,66 @@
+use indicator::{Indicator, StdDev};
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Band {
+    Upper,
+    Middle,
+    Lower
+}
+
+/// One band of the Bollinger bands: a simple moving average shifted by a number of standard
+/// deviations
+#[derive(Clone, PartialEq, Debug)]
+pub struct BollingerBand {
+    band: Band,
+    deviations: f64,
+    std_dev: StdDev
+}
+
+impl BollingerBand {
+
+    pub fn new(band: Band, period: usize, deviations: f64) -> BollingerBand {
+        BollingerBand {
+            band,
+            deviations,
+            std_dev: StdDev::new(period)
+        }
+    }
+
+    pub fn band(&self) -> &Band {
+        &self.band
+    }
+
+}
+
+impl Indicator for BollingerBand {
+
+    fn update(&mut self, value: f64) -> Option<f64> {
+        self.std_dev.update(value);
+        self.value()
+    }
+
+    fn value(&self) -> Option<f64> {
+        match (self.std_dev.mean(), self.std_dev.value()) {
+            (Some(mean), Some(std_dev)) => Some(match self.band {
+                Band::Upper => mean + self.deviations * std_dev,
+                Band::Middle => mean,
+                Band::Lower => mean - self.deviations * std_dev
+            }),
+            _ => None
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn update() {
+        let mut upper = BollingerBand::new(Band::Upper, 2, 2.);
+        let mut lower = BollingerBand::new(Band::Lower, 2, 2.);
+        assert_eq!((upper.update(1.), lower.update(1.)), (None, None));
+        assert_eq!((upper.update(3.), lower.update(3.)), (Some(4.), Some(0.)));
+    }
+}
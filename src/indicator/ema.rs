@@ -0,0 +1,66 @@
+use indicator::Indicator;
+
+/// Exponential moving average, seeded with the simple average of the first `period` values
+#[derive(Clone, PartialEq, Debug)]
+pub struct Ema {
+    period: usize,
+    seed: Vec<f64>,
+    value: Option<f64>
+}
+
+impl Ema {
+
+    pub fn new(period: usize) -> Ema {
+        Ema {
+            period,
+            seed: vec![],
+            value: None
+        }
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+}
+
+impl Indicator for Ema {
+
+    fn update(&mut self, value: f64) -> Option<f64> {
+        self.value = match self.value {
+            Some(ema) => {
+                let alpha = 2. / (self.period as f64 + 1.);
+                Some(ema + alpha * (value - ema))
+            },
+            None => {
+                self.seed.push(value);
+                if self.seed.len() == self.period {
+                    Some(self.seed.iter().sum::<f64>() / self.period as f64)
+                }
+                else {
+                    None
+                }
+            }
+        };
+        self.value
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.value
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn update() {
+        let mut ema = Ema::new(3);
+        assert_eq!(ema.update(1.), None);
+        assert_eq!(ema.update(2.), None);
+        assert_eq!(ema.update(3.), Some(2.));
+        assert_eq!(ema.update(6.), Some(4.));
+    }
+}
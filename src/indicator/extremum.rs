@@ -0,0 +1,96 @@
+use std::collections::VecDeque;
+use indicator::Indicator;
+
+/// Highest value over a rolling window
+#[derive(Clone, PartialEq, Debug)]
+pub struct Highest {
+    period: usize,
+    values: VecDeque<f64>
+}
+
+impl Highest {
+
+    pub fn new(period: usize) -> Highest {
+        Highest { period, values: VecDeque::with_capacity(period + 1) }
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+}
+
+impl Indicator for Highest {
+
+    fn update(&mut self, value: f64) -> Option<f64> {
+        push(&mut self.values, self.period, value);
+        self.value()
+    }
+
+    fn value(&self) -> Option<f64> {
+        extremum(&self.values, self.period, f64::max)
+    }
+
+}
+
+/// Lowest value over a rolling window
+#[derive(Clone, PartialEq, Debug)]
+pub struct Lowest {
+    period: usize,
+    values: VecDeque<f64>
+}
+
+impl Lowest {
+
+    pub fn new(period: usize) -> Lowest {
+        Lowest { period, values: VecDeque::with_capacity(period + 1) }
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+}
+
+impl Indicator for Lowest {
+
+    fn update(&mut self, value: f64) -> Option<f64> {
+        push(&mut self.values, self.period, value);
+        self.value()
+    }
+
+    fn value(&self) -> Option<f64> {
+        extremum(&self.values, self.period, f64::min)
+    }
+
+}
+
+fn push(values: &mut VecDeque<f64>, period: usize, value: f64) {
+    values.push_back(value);
+    if values.len() > period {
+        values.pop_front();
+    }
+}
+
+fn extremum(values: &VecDeque<f64>, period: usize, select: fn(f64, f64) -> f64) -> Option<f64> {
+    if period > 0 && values.len() == period {
+        values.iter().cloned().fold(None, |result, value| Some(result.map_or(value, |r| select(r, value))))
+    }
+    else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn update() {
+        let mut highest = Highest::new(2);
+        let mut lowest = Lowest::new(2);
+        assert_eq!((highest.update(1.), lowest.update(1.)), (None, None));
+        assert_eq!((highest.update(3.), lowest.update(3.)), (Some(3.), Some(1.)));
+        assert_eq!((highest.update(2.), lowest.update(2.)), (Some(3.), Some(2.)));
+    }
+}
@@ -0,0 +1,23 @@
+mod atr;
+pub use indicator::atr::Atr;
+mod sma;
+pub use indicator::sma::Sma;
+mod ema;
+pub use indicator::ema::Ema;
+mod rsi;
+pub use indicator::rsi::Rsi;
+mod std_dev;
+pub use indicator::std_dev::StdDev;
+mod extremum;
+pub use indicator::extremum::{Highest, Lowest};
+mod bollinger;
+pub use indicator::bollinger::{BollingerBand, Band};
+
+/// Indicator computed on a series of values, such as close prices
+pub trait Indicator {
+    /// Add the next value of the series and return the updated indicator value
+    fn update(&mut self, value: f64) -> Option<f64>;
+
+    /// Current value, `None` until enough values have been given
+    fn value(&self) -> Option<f64>;
+}
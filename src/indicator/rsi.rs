@@ -0,0 +1,81 @@
+use indicator::Indicator;
+
+/// Relative strength index, smoothed with Wilder's moving average
+#[derive(Clone, PartialEq, Debug)]
+pub struct Rsi {
+    period: usize,
+    previous: Option<f64>,
+    changes: usize,
+    average_gain: f64,
+    average_loss: f64
+}
+
+impl Rsi {
+
+    pub fn new(period: usize) -> Rsi {
+        Rsi {
+            period,
+            previous: None,
+            changes: 0,
+            average_gain: 0.,
+            average_loss: 0.
+        }
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+}
+
+impl Indicator for Rsi {
+
+    fn update(&mut self, value: f64) -> Option<f64> {
+        if let Some(previous) = self.previous {
+            let change = value - previous;
+            let (gain, loss) = if change > 0. { (change, 0.) } else { (0., -change) };
+            self.changes += 1;
+            if self.changes <= self.period {
+                // simple average of the first changes
+                self.average_gain += gain / self.period as f64;
+                self.average_loss += loss / self.period as f64;
+            }
+            else {
+                let period = self.period as f64;
+                self.average_gain = (self.average_gain * (period - 1.) + gain) / period;
+                self.average_loss = (self.average_loss * (period - 1.) + loss) / period;
+            }
+        }
+        self.previous = Some(value);
+        self.value()
+    }
+
+    fn value(&self) -> Option<f64> {
+        if self.period == 0 || self.changes < self.period {
+            None
+        }
+        else if self.average_loss == 0. {
+            Some(100.)
+        }
+        else {
+            Some(100. - 100. / (1. + self.average_gain / self.average_loss))
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn update() {
+        let mut rsi = Rsi::new(2);
+        assert_eq!(rsi.update(1.), None);
+        assert_eq!(rsi.update(2.), None);
+        // average gain of 0.5 and average loss of 0.5
+        assert_eq!(rsi.update(1.), Some(50.));
+        // average gain of 0.25 and average loss of 0.75
+        assert_eq!(rsi.update(0.), Some(25.));
+    }
+}
@@ -0,0 +1,61 @@
+use std::collections::VecDeque;
+use indicator::Indicator;
+
+/// Simple moving average
+#[derive(Clone, PartialEq, Debug)]
+pub struct Sma {
+    period: usize,
+    values: VecDeque<f64>,
+    sum: f64
+}
+
+impl Sma {
+
+    pub fn new(period: usize) -> Sma {
+        Sma {
+            period,
+            values: VecDeque::with_capacity(period + 1),
+            sum: 0.
+        }
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+}
+
+impl Indicator for Sma {
+
+    fn update(&mut self, value: f64) -> Option<f64> {
+        self.values.push_back(value);
+        self.sum += value;
+        if self.values.len() > self.period {
+            self.sum -= self.values.pop_front().unwrap();
+        }
+        self.value()
+    }
+
+    fn value(&self) -> Option<f64> {
+        if self.period > 0 && self.values.len() == self.period {
+            Some(self.sum / self.period as f64)
+        }
+        else {
+            None
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn update() {
+        let mut sma = Sma::new(2);
+        assert_eq!(sma.update(1.), None);
+        assert_eq!(sma.update(2.), Some(1.5));
+        assert_eq!(sma.update(4.), Some(3.));
+    }
+}
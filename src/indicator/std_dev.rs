@@ -0,0 +1,67 @@
+use std::collections::VecDeque;
+use indicator::Indicator;
+
+/// Population standard deviation over a rolling window
+#[derive(Clone, PartialEq, Debug)]
+pub struct StdDev {
+    period: usize,
+    values: VecDeque<f64>
+}
+
+impl StdDev {
+
+    pub fn new(period: usize) -> StdDev {
+        StdDev {
+            period,
+            values: VecDeque::with_capacity(period + 1)
+        }
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        if self.period > 0 && self.values.len() == self.period {
+            Some(self.values.iter().sum::<f64>() / self.period as f64)
+        }
+        else {
+            None
+        }
+    }
+
+}
+
+impl Indicator for StdDev {
+
+    fn update(&mut self, value: f64) -> Option<f64> {
+        self.values.push_back(value);
+        if self.values.len() > self.period {
+            self.values.pop_front();
+        }
+        self.value()
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.mean().map(|mean| {
+            let variance = self.values.iter().map(|value| (value - mean).powi(2)).sum::<f64>()
+                / self.period as f64;
+            variance.sqrt()
+        })
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn update() {
+        let mut std_dev = StdDev::new(2);
+        assert_eq!(std_dev.update(1.), None);
+        assert_eq!(std_dev.update(3.), Some(1.));
+        assert_eq!(std_dev.mean(), Some(2.));
+        assert_eq!(std_dev.update(3.), Some(0.));
+    }
+}
@@ -2,7 +2,10 @@
 extern crate serde_derive;
 
 pub mod ohlcv;
+pub mod indicator;
 pub mod symbol;
+pub mod quantity;
+pub mod price;
 pub mod signal;
 pub mod direction;
 pub mod order;
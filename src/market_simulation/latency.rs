@@ -0,0 +1,141 @@
+extern crate chrono;
+use std::collections::HashMap;
+use self::chrono::Duration;
+use symbol::SymbolId;
+
+/// Delay of a message between the strategies and the market
+#[derive(Clone, PartialEq, Debug)]
+pub enum Latency {
+    Fixed(Duration),
+    /// Uniformly distributed between a minimum and a maximum
+    Random(Duration, Duration),
+    /// Latency of the venue each symbol is traded on, the last latency for the other symbols
+    PerVenue(HashMap<SymbolId, Latency>, Box<Latency>)
+}
+
+/// Delays of the orders sent to the market and of the execution reports sent back to the
+/// strategies
+///
+/// Random latencies are drawn from a generator seeded for reproducible backtests, each run of a
+/// backtest starts from the seed.
+#[derive(Clone, Debug)]
+pub struct LatencyModel {
+    order_latency: Latency,
+    report_latency: Latency,
+    seed: u64,
+    state: u64
+}
+
+impl LatencyModel {
+
+    pub fn new() -> LatencyModel {
+        LatencyModel {
+            order_latency: Latency::Fixed(Duration::zero()),
+            report_latency: Latency::Fixed(Duration::zero()),
+            seed: 0,
+            state: 0
+        }
+    }
+
+    /// Latency between the creation of an order, or of a request about it, and its arrival on
+    /// the market
+    pub fn order_latency(&self) -> &Latency {
+        &self.order_latency
+    }
+
+    pub fn set_order_latency(mut self, value: Latency) -> Self {
+        self.order_latency = value;
+        self
+    }
+
+    /// Latency between an execution or a cancellation and its report to the strategy
+    pub fn report_latency(&self) -> &Latency {
+        &self.report_latency
+    }
+
+    pub fn set_report_latency(mut self, value: Latency) -> Self {
+        self.report_latency = value;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn set_seed(mut self, value: u64) -> Self {
+        self.seed = value;
+        self.state = value;
+        self
+    }
+
+    pub fn order_delay(&mut self, symbol_id: &SymbolId) -> Duration {
+        sample(&self.order_latency, symbol_id, &mut self.state)
+    }
+
+    pub fn report_delay(&mut self, symbol_id: &SymbolId) -> Duration {
+        sample(&self.report_latency, symbol_id, &mut self.state)
+    }
+
+}
+
+impl Default for LatencyModel {
+    fn default() -> LatencyModel {
+        LatencyModel::new()
+    }
+}
+
+fn sample(latency: &Latency, symbol_id: &SymbolId, state: &mut u64) -> Duration {
+    match *latency {
+        Latency::Fixed(duration) => duration,
+        Latency::Random(min, max) => {
+            let range = (max - min).num_microseconds().unwrap_or(i64::MAX) as f64;
+            min + Duration::microseconds((range * next_random(state)) as i64)
+        },
+        Latency::PerVenue(ref venues, ref default) => {
+            sample(venues.get(symbol_id).unwrap_or(default), symbol_id, state)
+        }
+    }
+}
+
+/// Next number of a splitmix64 generator, uniformly distributed in [0, 1)
+fn next_random(state: &mut u64) -> f64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn random_latency() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let latency = Latency::Random(Duration::milliseconds(10), Duration::milliseconds(20));
+        let mut latency_model = LatencyModel::new().set_order_latency(latency.clone()).set_seed(42);
+        let delays = (0..100).map(|_| latency_model.order_delay(&symbol_id)).collect::<Vec<Duration>>();
+        for delay in delays.iter() {
+            assert!(*delay >= Duration::milliseconds(10) && *delay < Duration::milliseconds(20));
+        }
+        assert!(delays.iter().any(|delay| *delay != delays[0]));
+
+        // the same seed gives the same delays
+        let mut latency_model = LatencyModel::new().set_order_latency(latency).set_seed(42);
+        assert_eq!((0..100).map(|_| latency_model.order_delay(&symbol_id)).collect::<Vec<Duration>>(), delays);
+    }
+
+    #[test]
+    fn per_venue_latency() {
+        let mut venues = HashMap::new();
+        venues.insert(SymbolId::from("eur/usd"), Latency::Fixed(Duration::milliseconds(5)));
+        let mut latency_model = LatencyModel::new().set_report_latency(
+            Latency::PerVenue(venues, Box::new(Latency::Fixed(Duration::milliseconds(50))))
+        );
+        assert_eq!(latency_model.report_delay(&SymbolId::from("eur/usd")), Duration::milliseconds(5));
+        assert_eq!(latency_model.report_delay(&SymbolId::from("btc/usd")), Duration::milliseconds(50));
+        assert_eq!(latency_model.order_delay(&SymbolId::from("btc/usd")), Duration::zero());
+    }
+}
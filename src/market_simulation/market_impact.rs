@@ -0,0 +1,208 @@
+extern crate chrono;
+use std::collections::{HashMap, VecDeque};
+use self::chrono::prelude::NaiveDate;
+use direction::Direction;
+use ohlcv::Ohlcv;
+use price::Price;
+use quantity::Quantity;
+use symbol::SymbolId;
+
+/// Impact of an execution in units of daily volatility, from its participation, the executed
+/// quantity over the average daily volume
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ImpactFunction {
+    /// Coefficient times the participation
+    Linear(f64),
+    /// Coefficient times the square root of the participation
+    SquareRoot(f64)
+}
+
+impl ImpactFunction {
+    pub fn impact(&self, participation: f64) -> f64 {
+        match *self {
+            ImpactFunction::Linear(coefficient) => coefficient * participation,
+            ImpactFunction::SquareRoot(coefficient) => coefficient * participation.sqrt()
+        }
+    }
+}
+
+/// Daily volumes and returns of a symbol
+#[derive(Clone, Default, Debug)]
+struct DailyHistory {
+    date: Option<NaiveDate>,
+    volume: Quantity,
+    close: f64,
+    previous_close: Option<f64>,
+    volumes: VecDeque<Quantity>,
+    returns: VecDeque<f64>,
+    /// Sum of the permanent impacts of the executions, as a fraction of the price
+    permanent_impact: f64
+}
+
+/// Price impact of the executions of large orders
+///
+/// The impact of an execution is a fraction of its price, the daily volatility times an impact
+/// function of its participation. Temporary impact moves the price of the execution against its
+/// order, permanent impact moves the prices of all the following executions of the symbol.
+///
+/// The average daily volume and the volatility, the root mean square of the close to close
+/// returns, are computed from the bars of the last complete days, executions have no impact
+/// until both are known.
+#[derive(Clone, Debug)]
+pub struct MarketImpactModel {
+    temporary_impact: ImpactFunction,
+    permanent_impact: Option<ImpactFunction>,
+    days: usize,
+    histories: HashMap<SymbolId, DailyHistory>
+}
+
+impl MarketImpactModel {
+
+    pub fn new(temporary_impact: ImpactFunction) -> MarketImpactModel {
+        MarketImpactModel {
+            temporary_impact,
+            permanent_impact: None,
+            days: 20,
+            histories: HashMap::new()
+        }
+    }
+
+    pub fn temporary_impact(&self) -> &ImpactFunction {
+        &self.temporary_impact
+    }
+
+    pub fn permanent_impact(&self) -> &Option<ImpactFunction> {
+        &self.permanent_impact
+    }
+
+    pub fn set_permanent_impact(mut self, value: Option<ImpactFunction>) -> Self {
+        self.permanent_impact = value;
+        self
+    }
+
+    /// Number of days of the average daily volume and of the volatility
+    pub fn days(&self) -> usize {
+        self.days
+    }
+
+    pub fn set_days(mut self, value: usize) -> Self {
+        self.days = value;
+        self
+    }
+
+    /// Add a bar to the history of its symbol, the previous day is complete on the first bar of
+    /// a new day
+    pub fn update(&mut self, ohlcv: &Ohlcv) {
+        let days = self.days;
+        let history = self.histories.entry(ohlcv.symbol_id().clone()).or_default();
+        let date = ohlcv.datetime().date_naive();
+        if history.date != Some(date) {
+            if history.date.is_some() {
+                history.volumes.push_back(history.volume);
+                if let Some(previous_close) = history.previous_close {
+                    history.returns.push_back(history.close / previous_close - 1.);
+                }
+                history.previous_close = Some(history.close);
+                while history.volumes.len() > days {
+                    history.volumes.pop_front();
+                }
+                while history.returns.len() > days {
+                    history.returns.pop_front();
+                }
+            }
+            history.date = Some(date);
+            history.volume = Quantity::zero();
+        }
+        history.volume += ohlcv.volume();
+        history.close = ohlcv.close();
+    }
+
+    pub fn average_daily_volume(&self, symbol_id: &SymbolId) -> Option<Quantity> {
+        let volumes = &self.histories.get(symbol_id)?.volumes;
+        if volumes.is_empty() {
+            return None;
+        }
+        Some(volumes.iter().cloned().sum::<Quantity>() * (1. / volumes.len() as f64))
+    }
+
+    /// Daily volatility as a fraction of the price
+    pub fn volatility(&self, symbol_id: &SymbolId) -> Option<f64> {
+        let returns = &self.histories.get(symbol_id)?.returns;
+        if returns.is_empty() {
+            return None;
+        }
+        Some((returns.iter().map(|r| r * r).sum::<f64>() / returns.len() as f64).sqrt())
+    }
+
+    /// Price of an execution of the quantity at the given price, moved by the permanent impact of
+    /// the previous executions of the symbol and by its temporary impact
+    pub fn execution_price(&mut self, symbol_id: &SymbolId, direction: &Direction, quantity: Quantity, price: Price) -> Price {
+        let (average_daily_volume, volatility) = match (self.average_daily_volume(symbol_id), self.volatility(symbol_id)) {
+            (Some(volume), Some(volatility)) if !volume.is_zero() => (volume, volatility),
+            _ => return price
+        };
+        let participation = quantity.value() / average_daily_volume.value();
+        let sign = match *direction {
+            Direction::Long => 1.,
+            Direction::Short => -1.
+        };
+        let temporary_impact = volatility * self.temporary_impact.impact(participation);
+        let permanent_impact = self.permanent_impact
+            .map_or(0., |function| volatility * function.impact(participation));
+
+        let history = self.histories.get_mut(symbol_id).unwrap();
+        let factor = 1. + history.permanent_impact + sign * temporary_impact;
+        if !factor.is_finite() {
+            return price;
+        }
+        let price = price * factor;
+        history.permanent_impact += sign * permanent_impact;
+        price
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use self::chrono::prelude::{TimeZone, Utc};
+
+    fn model(permanent_impact: Option<ImpactFunction>) -> MarketImpactModel {
+        let symbol_id = SymbolId::from("eur/usd");
+        let mut model = MarketImpactModel::new(ImpactFunction::SquareRoot(0.4))
+            .set_permanent_impact(permanent_impact)
+            .set_days(2);
+        let bars = [(1, 1, 100., 40.), (1, 2, 100., 60.), (2, 1, 125., 300.), (3, 1, 125., 50.)];
+        for &(day, hour, close, volume) in bars.iter() {
+            let datetime = Utc.ymd(2016, 1, day).and_hms(hour, 0, 0);
+            model.update(&Ohlcv::new(symbol_id.clone(), datetime, close, close, close, close, Quantity::new(volume)));
+        }
+        model
+    }
+
+    #[test]
+    fn average_daily_volume_and_volatility() {
+        let model = model(None);
+        let symbol_id = SymbolId::from("eur/usd");
+        assert_eq!(model.average_daily_volume(&symbol_id), Some(Quantity::new(200.)));
+        assert_eq!(model.volatility(&symbol_id), Some(0.25));
+        assert_eq!(model.volatility(&SymbolId::from("btc/usd")), None);
+    }
+
+    #[test]
+    fn execution_price() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let mut model = model(Some(ImpactFunction::Linear(0.8)));
+        // a participation of 0.25 moves the price by 0.25 * 0.4 * 0.5 and then by 0.25 * 0.8 * 0.25
+        let price = model.execution_price(&symbol_id, &Direction::Long, Quantity::new(50.), Price::new(100.));
+        assert!((price.value() - 105.).abs() < 1e-9);
+        let price = model.execution_price(&symbol_id, &Direction::Short, Quantity::new(50.), Price::new(100.));
+        assert!((price.value() - 100.).abs() < 1e-9);
+        let price = model.execution_price(&symbol_id, &Direction::Short, Quantity::new(50.), Price::new(100.));
+        assert!((price.value() - 95.).abs() < 1e-9);
+
+        // no impact without the history of the symbol
+        let price = model.execution_price(&SymbolId::from("btc/usd"), &Direction::Long, Quantity::new(50.), Price::new(100.));
+        assert_eq!(price, Price::new(100.));
+    }
+}
@@ -1,25 +1,227 @@
 extern crate chrono;
+
+mod latency;
+mod market_impact;
+mod queue_model;
+mod simulate_execution;
+
 use std::collections::{HashSet, HashMap};
+use self::chrono::Duration;
+use self::chrono::prelude::{DateTime, Utc, NaiveDate, NaiveTime};
 use ohlcv::Ohlcv;
 use direction::Direction;
-use order::{Order, OrderId, OrderStatus, OrderKind, OcaGroup, CancellationReason};
+use indicator::Atr;
+use order::{Order, OrderId, OrderStatus, OrderKind, OrderRequest, OrderEvent, OrderEventKind, OcaGroup, CancellationReason,
+            TrailingDistance, TimeInForce};
 use execution::Execution;
+use price::Price;
+use quantity::Quantity;
+use symbol::SymbolId;
+pub use self::latency::{Latency, LatencyModel};
+pub use self::market_impact::{ImpactFunction, MarketImpactModel};
+pub use self::queue_model::QueueModel;
+pub use self::simulate_execution::SimulateExecution;
+
+/// State kept between bars for orders whose execution depends on previous bars
+#[derive(Clone, Default, Debug)]
+struct OrderState {
+    /// Whether the stop price of a stop-limit order has been reached
+    triggered: bool,
+    /// Best price of a trailing stop since the order became active
+    best_price: Option<f64>,
+    atr: Option<Atr>,
+    /// Date a day order reaches the market, or of its first bar if it was not sent
+    first_date: Option<NaiveDate>,
+    /// Whether an immediate or cancel order has been partially filled on its first bar
+    partially_filled: bool,
+    /// Datetime the order reaches the market, after the order latency
+    arrival: Option<DateTime<Utc>>,
+    /// Quantity resting ahead of a limit order at its limit price
+    queue_ahead: Option<Quantity>,
+    /// Whether an on-close order has been active on a bar of the current day
+    active_on_day: bool
+}
+
+/// Request sent to the market, it arrives after the order latency of the symbol of its order
+#[derive(Clone, Debug)]
+struct SentRequest {
+    request: OrderRequest,
+    datetime: DateTime<Utc>,
+    arrival: Option<DateTime<Utc>>
+}
 
-pub struct MarketSimulation;
+#[derive(Clone, Debug)]
+pub struct MarketSimulation {
+    max_volume_participation: Option<f64>,
+    latency_model: Option<LatencyModel>,
+    queue_model: Option<QueueModel>,
+    market_impact_model: Option<MarketImpactModel>,
+    session_close: Option<NaiveTime>,
+    order_states: HashMap<OrderId, OrderState>,
+    last_bars: HashMap<SymbolId, Ohlcv>,
+    requests: Vec<SentRequest>,
+    reports: Vec<(DateTime<Utc>, OrderId, OrderStatus)>,
+    events: Vec<OrderEvent>
+}
 
 impl MarketSimulation {
     pub fn new() -> MarketSimulation {
-        MarketSimulation {}
+        MarketSimulation {
+            max_volume_participation: None,
+            latency_model: None,
+            queue_model: None,
+            market_impact_model: None,
+            session_close: None,
+            order_states: HashMap::new(),
+            last_bars: HashMap::new(),
+            requests: vec![],
+            reports: vec![],
+            events: vec![]
+        }
+    }
+
+    /// Maximum percent of the volume of a bar filled for each order, the rest of the order is
+    /// filled on the next bars
+    pub fn max_volume_participation(&self) -> Option<f64> {
+        self.max_volume_participation
+    }
+
+    pub fn set_max_volume_participation(mut self, value: Option<f64>) -> Self {
+        self.max_volume_participation = value;
+        self
+    }
+
+    /// Delays of the orders and of their execution reports, without latency orders can be
+    /// executed on the bar following their creation and executions are reported on their bar
+    pub fn latency_model(&self) -> &Option<LatencyModel> {
+        &self.latency_model
+    }
+
+    pub fn set_latency_model(mut self, value: Option<LatencyModel>) -> Self {
+        self.latency_model = value;
+        self
+    }
+
+    /// Queue ahead of resting limit orders, without it a limit order is filled as soon as price
+    /// trades through its limit and never when price only touches it
+    pub fn queue_model(&self) -> &Option<QueueModel> {
+        &self.queue_model
+    }
+
+    pub fn set_queue_model(mut self, value: Option<QueueModel>) -> Self {
+        self.queue_model = value;
+        self
+    }
+
+    /// Impact of the executions on their prices, limit orders are never filled beyond their
+    /// limit price
+    pub fn market_impact_model(&self) -> &Option<MarketImpactModel> {
+        &self.market_impact_model
+    }
+
+    pub fn set_market_impact_model(mut self, value: Option<MarketImpactModel>) -> Self {
+        self.market_impact_model = value;
+        self
+    }
+
+    /// UTC time of the last bar of each trading session, on which on-close orders are filled
+    ///
+    /// Without it on-close orders are filled at the close of the last bar of the day, which is
+    /// known once the first bar of the next day arrives. Only orders which were active on a
+    /// bar of that day are filled, the others wait for the close of the next day.
+    pub fn session_close(&self) -> Option<NaiveTime> {
+        self.session_close
+    }
+
+    pub fn set_session_close(mut self, value: Option<NaiveTime>) -> Self {
+        self.session_close = value;
+        self
+    }
+
+    /// Record the event of an update of an order and report it to its strategy
+    fn report(&mut self, order: &Order, datetime: &DateTime<Utc>, order_status: OrderStatus) {
+        let event_kind = match order_status {
+            OrderStatus::PartiallyFilled(ref execution) => Some(OrderEventKind::PartiallyFilled(execution.clone())),
+            OrderStatus::Filled(ref execution) => Some(OrderEventKind::Filled(execution.clone())),
+            OrderStatus::Cancelled(ref reason) => Some(OrderEventKind::Cancelled(reason.clone())),
+            _ => None
+        };
+        if let Some(event_kind) = event_kind {
+            self.events.push(OrderEvent::new(*datetime, order, event_kind));
+        }
+
+        let delay = match self.latency_model {
+            Some(ref mut latency_model) => latency_model.report_delay(order.symbol_id()),
+            None => Duration::zero()
+        };
+        self.reports.push((*datetime + delay, order.id().clone(), order_status));
+    }
+
+    fn order_delay(&mut self, symbol_id: &SymbolId) -> Duration {
+        match self.latency_model {
+            Some(ref mut latency_model) => latency_model.order_delay(symbol_id),
+            None => Duration::zero()
+        }
+    }
+
+    /// Get the bar at whose close an on-close order is filled, either the bar of the session
+    /// close or the last bar of the previous day
+    fn closing_bar(&mut self, order: &Order, ohlcv: &Ohlcv, opens_day: bool) -> Option<Ohlcv> {
+        match *order.kind() {
+            OrderKind::MarketOnClose | OrderKind::LimitOnClose(_) if order.symbol_id() == ohlcv.symbol_id() => (),
+            _ => return None
+        }
+        if let Some(session_close) = self.session_close {
+            return if ohlcv.datetime().time() == session_close { Some(ohlcv.clone()) } else { None };
+        }
+        let state = self.order_states.entry(order.id().clone()).or_default();
+        let active_on_previous_day = opens_day && state.active_on_day;
+        state.active_on_day = true;
+        if active_on_previous_day {
+            self.last_bars.get(ohlcv.symbol_id()).cloned()
+        } else {
+            None
+        }
+    }
+
+    /// Get the reason to cancel an order whose time in force has expired
+    fn expiration(&mut self, order: &Order, ohlcv: &Ohlcv) -> Option<CancellationReason> {
+        match *order.time_in_force() {
+            TimeInForce::Day => {
+                let date = ohlcv.datetime().date_naive();
+                let state = self.order_states.entry(order.id().clone()).or_default();
+                if date > *state.first_date.get_or_insert(date) {
+                    Some(CancellationReason::DayEnded)
+                } else {
+                    None
+                }
+            },
+            TimeInForce::GoodTillDate(datetime) => if &datetime <= ohlcv.datetime() {
+                Some(CancellationReason::DateReached)
+            } else {
+                None
+            },
+            _ => None
+        }
     }
+}
 
-    pub fn update_orders<'a, I>(&self, orders: I, ohlcv: &Ohlcv) -> HashMap<OrderId, OrderStatus>
-        where I: Iterator<Item=&'a Order>
-    {
+impl SimulateExecution for MarketSimulation {
+    fn update_orders(&mut self, orders: &[&Order], ohlcv: &Ohlcv) -> HashMap<OrderId, OrderStatus> {
         let mut updates: HashMap<OrderId, OrderStatus> = HashMap::new();
         let mut filled_oca_groups: HashSet<OcaGroup> = HashSet::new();
         let mut oca_orders: HashMap<OcaGroup, Vec<&Order>> = HashMap::new();
+        let opens_day = self.last_bars.get(ohlcv.symbol_id()).map(|bar| bar.datetime().date_naive()) !=
+            Some(ohlcv.datetime().date_naive());
+        if let Some(ref mut market_impact_model) = self.market_impact_model {
+            market_impact_model.update(ohlcv);
+        }
+
+        let mut updated_orders: HashMap<&OrderId, &Order> = HashMap::new();
+
+        for &order in orders {
+            updated_orders.insert(order.id(), order);
 
-        for order in orders {
             if let &Some(ref oca_group) = order.oca() {
                 if filled_oca_groups.contains(oca_group) {
                     updates.insert(
@@ -32,6 +234,11 @@ impl MarketSimulation {
                 oca_orders.entry(oca_group.clone()).or_insert(vec![]).push(&order);
             }
 
+            if self.order_states.get(order.id()).and_then(|state| state.arrival).map_or(false, |arrival| &arrival > ohlcv.datetime()) {
+                // the order has not reached the market yet
+                continue;
+            }
+
             if let &Some(active_until) = order.active_until() {
                 if &active_until <= ohlcv.datetime() {
                     updates.insert(
@@ -42,6 +249,19 @@ impl MarketSimulation {
                 }
             }
 
+            let closing_bar = self.closing_bar(order, ohlcv, opens_day);
+            // an on-close order filled at the close of the previous day was valid on that day
+            if let Some(reason) = self.expiration(order, closing_bar.as_ref().unwrap_or(ohlcv)) {
+                updates.insert(order.id().clone(), OrderStatus::Cancelled(reason));
+                continue;
+            }
+
+            if self.order_states.get(order.id()).map_or(false, |state| state.partially_filled) {
+                // the rest of an immediate or cancel order
+                updates.insert(order.id().clone(), OrderStatus::Cancelled(CancellationReason::NotFilledImmediately));
+                continue;
+            }
+
             if order.symbol_id() != ohlcv.symbol_id() {
                 // the order can't be executed on a candle from a different symbol
                 continue
@@ -53,70 +273,346 @@ impl MarketSimulation {
                 }
             }
 
-            let is_executed = match *order.kind() {
-                OrderKind::MarketOrder => true,
-                OrderKind::LimitOrder(price) => match *order.direction() {
-                    Direction::Long => ohlcv.low() < price,
-                    Direction::Short => ohlcv.high() > price
+            // volume left to the order at its limit price once the queue ahead is exhausted
+            let mut available_volume = None;
+            let execution_price = match *order.kind() {
+                OrderKind::MarketOrder => Some(Price::new(ohlcv.open())),
+                OrderKind::LimitOrder(price) => match self.queue_model {
+                    Some(ref queue_model) if is_limit_touched(order.direction(), price, ohlcv) &&
+                        !is_limit_reached(order.direction(), price, ohlcv) => {
+                        let state = self.order_states.entry(order.id().clone()).or_default();
+                        let last_volume = self.last_bars.get(order.symbol_id()).map_or(ohlcv.volume(), |bar| bar.volume());
+                        let queue_ahead = state.queue_ahead.get_or_insert(queue_model.estimate_queue_ahead(last_volume));
+                        let traded = queue_model.estimate_traded_at_price(ohlcv.volume());
+                        if traded <= *queue_ahead {
+                            *queue_ahead -= traded;
+                            None
+                        } else {
+                            available_volume = Some(traded - *queue_ahead);
+                            *queue_ahead = Quantity::zero();
+                            Some(price)
+                        }
+                    },
+                    _ => if is_limit_reached(order.direction(), price, ohlcv) {
+                        Some(price)
+                    } else {
+                        None
+                    }
                 },
-                OrderKind::StopOrder(price) => match *order.direction() {
-                    Direction::Long => ohlcv.high() > price,
-                    Direction::Short => ohlcv.low() < price
-                }
+                // the rest of a partially filled stop is a market order
+                OrderKind::StopOrder(_) | OrderKind::TrailingStopOrder(_) if !order.executions().is_empty() => {
+                    Some(Price::new(ohlcv.open()))
+                },
+                OrderKind::StopOrder(price) => if is_stop_reached(order.direction(), price, ohlcv) {
+                    Some(price)
+                } else {
+                    None
+                },
+                OrderKind::StopLimitOrder(stop, limit) => {
+                    let state = self.order_states.entry(order.id().clone()).or_default();
+                    if !state.triggered && is_stop_reached(order.direction(), stop, ohlcv) {
+                        state.triggered = true;
+                        self.events.push(OrderEvent::new(*ohlcv.datetime(), order, OrderEventKind::Triggered));
+                    }
+                    if state.triggered && is_limit_reached(order.direction(), limit, ohlcv) {
+                        Some(limit)
+                    } else {
+                        None
+                    }
+                },
+                OrderKind::TrailingStopOrder(ref distance) => {
+                    let state = self.order_states.entry(order.id().clone()).or_default();
+                    update_trailing_stop(state, order.direction(), distance, ohlcv)
+                },
+                OrderKind::MarketOnOpen => if opens_day {
+                    Some(Price::new(ohlcv.open()))
+                } else {
+                    None
+                },
+                OrderKind::MarketOnClose => closing_bar.as_ref().map(|bar| Price::new(bar.close())),
+                OrderKind::LimitOnClose(price) => closing_bar.as_ref().and_then(|bar| {
+                    let close = Price::new(bar.close());
+                    let is_executed = match *order.direction() {
+                        Direction::Long => close <= price,
+                        Direction::Short => close >= price
+                    };
+                    if is_executed { Some(close) } else { None }
+                })
             };
 
-            if is_executed {
-                if let &Some(ref oca_group) = order.oca() {
-                    filled_oca_groups.insert(oca_group.clone());
-                    if let Some(filled_oca_orders) = oca_orders.get(oca_group) {
-                        for cancelled_order in filled_oca_orders {
-                            updates.insert(
-                                cancelled_order.id().clone(),
-                                OrderStatus::Cancelled(CancellationReason::FilledOca)
-                            );
-                        }
+            // on-close orders are filled on their closing bar
+            let bar = closing_bar.as_ref().unwrap_or(ohlcv);
+            let remaining_quantity = order.quantity() - order.filled_quantity();
+            let quantity = match self.max_volume_participation {
+                Some(percentage) => remaining_quantity.min(bar.volume() * (percentage / 100.)),
+                None => remaining_quantity
+            };
+            let quantity = match available_volume {
+                Some(volume) => quantity.min(volume),
+                None => quantity
+            };
+            let is_partial = quantity < remaining_quantity;
+            let is_executed = execution_price.is_some() && !(is_partial && quantity.is_zero());
+            let cancellation = match *order.time_in_force() {
+                TimeInForce::ImmediateOrCancel if !is_executed => {
+                    Some(CancellationReason::NotFilledImmediately)
+                },
+                TimeInForce::FillOrKill if execution_price.is_none() || is_partial => {
+                    Some(CancellationReason::NotFilledEntirely)
+                },
+                _ => None
+            };
+            if let Some(reason) = cancellation {
+                updates.insert(order.id().clone(), OrderStatus::Cancelled(reason));
+                continue;
+            }
+
+            let price = match execution_price {
+                Some(price) if is_executed => price,
+                _ => continue
+            };
+            if let Some(oca_group) = order.oca() {
+                filled_oca_groups.insert(oca_group.clone());
+                if let Some(filled_oca_orders) = oca_orders.get(oca_group) {
+                    for cancelled_order in filled_oca_orders {
+                        updates.insert(
+                            cancelled_order.id().clone(),
+                            OrderStatus::Cancelled(CancellationReason::FilledOca)
+                        );
                     }
                 }
-                updates.insert(
-                    order.id().clone(),
-                    OrderStatus::Filled(
-                        Execution::new(
-                            order.symbol_id().clone(),
-                            order.quantity(),
-                            match order.kind() {
-                                &OrderKind::MarketOrder => ohlcv.open(),
-                                &OrderKind::LimitOrder(price) => price,
-                                &OrderKind::StopOrder(price) => price
-                            },
-                            ohlcv.datetime().clone()
-                        )
-                    )
-                );
             }
+            let price = match self.market_impact_model {
+                Some(ref mut market_impact_model) => within_limit(
+                    order,
+                    market_impact_model.execution_price(order.symbol_id(), order.direction(), quantity, price)
+                ),
+                None => price
+            };
+            let execution = Execution::new(order.symbol_id().clone(), quantity, price, *bar.datetime());
+            if is_partial {
+                if let TimeInForce::ImmediateOrCancel = *order.time_in_force() {
+                    self.order_states.entry(order.id().clone()).or_default().partially_filled = true;
+                }
+                updates.insert(order.id().clone(), OrderStatus::PartiallyFilled(execution));
+            }
+            else {
+                updates.insert(order.id().clone(), OrderStatus::Filled(execution));
+            }
+        }
+
+        for (order_id, status) in updates.iter() {
+            self.report(updated_orders[order_id], ohlcv.datetime(), status.clone());
+            // partially filled orders keep their state for the rest of their quantity
+            if let OrderStatus::PartiallyFilled(_) = *status {
+                continue;
+            }
+            self.order_states.remove(order_id);
         }
+        self.last_bars.insert(ohlcv.symbol_id().clone(), ohlcv.clone());
         updates
     }
+
+
+    /// Send orders created at the given datetime to the market, where they can be executed once
+    /// the order latency has elapsed
+    fn send_orders(&mut self, orders: &[&Order], datetime: &DateTime<Utc>) {
+        for &order in orders {
+            let arrival = *datetime + self.order_delay(order.symbol_id());
+            if self.latency_model.is_some() {
+                self.order_states.entry(order.id().clone()).or_default().arrival = Some(arrival);
+            }
+            // a day order is valid on the day it reaches the market, even without bars after it
+            if let TimeInForce::Day = *order.time_in_force() {
+                self.order_states.entry(order.id().clone()).or_default().first_date = Some(arrival.date_naive());
+            }
+            if let (OrderKind::LimitOrder(_), Some(queue_model)) = (order.kind(), &self.queue_model) {
+                if let Some(bar) = self.last_bars.get(order.symbol_id()) {
+                    let queue_ahead = queue_model.estimate_queue_ahead(bar.volume());
+                    self.order_states.entry(order.id().clone()).or_default().queue_ahead = Some(queue_ahead);
+                }
+            }
+            self.events.push(OrderEvent::new(arrival, order, OrderEventKind::Sent));
+        }
+    }
+
+    /// Send cancel and modify requests made at the given datetime, processed once the order
+    /// latency has elapsed
+    fn add_requests(&mut self, requests: Vec<OrderRequest>, datetime: &DateTime<Utc>) {
+        self.requests.extend(requests.into_iter().map(|request| SentRequest {
+            request,
+            datetime: *datetime,
+            arrival: None
+        }));
+    }
+
+    /// Process the requests which have reached the market at the given datetime, returns the
+    /// requests to apply to the active orders
+    ///
+    /// Requests about orders which are no longer active, or reducing the quantity of an order to
+    /// its filled quantity, are dropped. A modified order is a new order for the simulation, such
+    /// as a stop-limit which has to be triggered again or a limit order at the back of the queue,
+    /// but it keeps the day it was sent.
+    fn process_requests(&mut self, orders: &[&Order], datetime: &DateTime<Utc>) -> Vec<OrderRequest> {
+        let orders: HashMap<&OrderId, &Order> = orders.iter().map(|&order| (order.id(), order)).collect();
+        let mut requests = vec![];
+        let mut pending = vec![];
+        for mut sent_request in ::std::mem::take(&mut self.requests) {
+            let order = match orders.get(sent_request.request.order_id()) {
+                Some(order) => order,
+                None => continue
+            };
+            let arrival = match sent_request.arrival {
+                Some(arrival) => arrival,
+                None => sent_request.datetime + self.order_delay(order.symbol_id())
+            };
+            if arrival > *datetime {
+                sent_request.arrival = Some(arrival);
+                pending.push(sent_request);
+                continue;
+            }
+            match sent_request.request {
+                OrderRequest::Modify(_, _, quantity) if quantity <= order.filled_quantity() => (),
+                request => requests.push((request, *order))
+            }
+        }
+        self.requests = pending;
+
+        for &(ref request, order) in requests.iter() {
+            match *request {
+                OrderRequest::Cancel(ref order_id) => {
+                    let order_status = OrderStatus::Cancelled(CancellationReason::Requested);
+                    self.order_states.remove(order_id);
+                    self.report(order, datetime, order_status);
+                },
+                OrderRequest::Modify(ref order_id, ref kind, quantity) => {
+                    if let Some(state) = self.order_states.get_mut(order_id) {
+                        *state = OrderState {
+                            first_date: state.first_date,
+                            partially_filled: state.partially_filled,
+                            arrival: state.arrival,
+                            ..OrderState::default()
+                        };
+                    }
+                    self.events.push(OrderEvent::new(*datetime, order, OrderEventKind::Modified(kind.clone(), quantity)));
+                }
+            }
+        }
+        requests.into_iter().map(|(request, _)| request).collect()
+    }
+
+    /// Take the execution and cancellation reports which have reached the strategies at the
+    /// given datetime, in the order they arrived
+    fn reports(&mut self, datetime: &DateTime<Utc>) -> Vec<(OrderId, OrderStatus)> {
+        let (mut arrived, pending): (Vec<_>, Vec<_>) = self.reports.drain(..)
+            .partition(|report| report.0 <= *datetime);
+        self.reports = pending;
+        arrived.sort_by_key(|report| report.0);
+        arrived.into_iter().map(|(_, order_id, order_status)| (order_id, order_status)).collect()
+    }
+
+    /// Take the events of the orders since the last call
+    fn drain_events(&mut self) -> Vec<OrderEvent> {
+        self.events.drain(..).collect()
+    }
+
+    fn box_clone(&self) -> Box<SimulateExecution> {
+        Box::new(self.clone())
+    }
+}
+
+fn is_limit_reached(direction: &Direction, price: Price, ohlcv: &Ohlcv) -> bool {
+    match *direction {
+        Direction::Long => ohlcv.low() < price.value(),
+        Direction::Short => ohlcv.high() > price.value()
+    }
+}
+
+/// Bring an execution price moved by the market impact back to the limit price of its order
+fn within_limit(order: &Order, price: Price) -> Price {
+    let limit = match *order.kind() {
+        OrderKind::LimitOrder(limit) | OrderKind::LimitOnClose(limit) | OrderKind::StopLimitOrder(_, limit) => limit,
+        _ => return price
+    };
+    match *order.direction() {
+        Direction::Long if price > limit => limit,
+        Direction::Short if price < limit => limit,
+        _ => price
+    }
+}
+
+fn is_limit_touched(direction: &Direction, price: Price, ohlcv: &Ohlcv) -> bool {
+    match *direction {
+        Direction::Long => ohlcv.low() <= price.value(),
+        Direction::Short => ohlcv.high() >= price.value()
+    }
+}
+
+fn is_stop_reached(direction: &Direction, price: Price, ohlcv: &Ohlcv) -> bool {
+    match *direction {
+        Direction::Long => ohlcv.high() > price.value(),
+        Direction::Short => ohlcv.low() < price.value()
+    }
+}
+
+/// Get the execution price of a trailing stop if its stop, computed from the previous bars, is
+/// reached, otherwise follow the best price of the bar
+///
+/// The best price is the lowest low for a long order and the highest high for a short order,
+/// starting from the open of the first bar.
+fn update_trailing_stop(state: &mut OrderState, direction: &Direction, distance: &TrailingDistance,
+                        ohlcv: &Ohlcv) -> Option<Price>
+{
+    let best_price = *state.best_price.get_or_insert(ohlcv.open());
+    let distance = match *distance {
+        TrailingDistance::Amount(amount) => Some(amount),
+        TrailingDistance::Percentage(percentage) => Some(best_price * percentage / 100.),
+        TrailingDistance::Atr(period, multiplier) => state.atr
+            .get_or_insert_with(|| Atr::new(period))
+            .value()
+            .map(|atr| atr * multiplier)
+    };
+    if let Some(distance) = distance {
+        let stop = match *direction {
+            Direction::Long => Price::new(best_price + distance),
+            Direction::Short => Price::new(best_price - distance)
+        };
+        if is_stop_reached(direction, stop, ohlcv) {
+            return Some(stop);
+        }
+    }
+
+    state.best_price = Some(match *direction {
+        Direction::Long => best_price.min(ohlcv.low()),
+        Direction::Short => best_price.max(ohlcv.high())
+    });
+    if let Some(ref mut atr) = state.atr {
+        atr.update(ohlcv);
+    }
+    None
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use self::chrono::prelude::{Utc, TimeZone};
-    use order::{OrderBuilder, OrderKind};
+    use self::chrono::prelude::{DateTime, Utc, TimeZone};
+    use order::{OrderBuilder, OrderKind, TrailingDistance, TimeInForce};
     use execution::Execution;
     use symbol::SymbolId;
     use direction::Direction;
+    use quantity::Quantity;
+    use price::Price;
 
     #[test]
     fn update_market_order() {
-        let market_simulation = MarketSimulation::new();
+        let mut market_simulation = MarketSimulation::new();
         let symbol_id = SymbolId::from("eur/usd");
         let order = OrderBuilder::unallocated(OrderKind::MarketOrder, symbol_id.clone(), Direction::Long )
             .set_id(OrderId::from("test order"))
             .build().unwrap();
         let updates = market_simulation.update_orders(
-            vec![&order].into_iter(),
-            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 1., 2., 0., 1.5, 1)
+            &[&order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 1., 2., 0., 1.5, Quantity::new(1.))
         );
         let expected: HashMap<OrderId, OrderStatus> = [
             (
@@ -125,7 +621,7 @@ mod test {
                     Execution::new(
                         symbol_id.clone(),
                         order.quantity(),
-                        1.,
+                        Price::new(1.),
                         Utc.ymd(2016, 1, 3).and_hms(17, 0, 0)
                     )
                 )
@@ -136,17 +632,17 @@ mod test {
 
     #[test]
     fn update_limit_long_order() {
-        let market_simulation = MarketSimulation::new();
+        let mut market_simulation = MarketSimulation::new();
         let symbol_id = SymbolId::from("eur/usd");
         let executed_order = OrderBuilder::unallocated(
-            OrderKind::LimitOrder(100.), symbol_id.clone(), Direction::Long
+            OrderKind::LimitOrder(Price::new(100.)), symbol_id.clone(), Direction::Long
         ).set_id(OrderId::from("executed order")).build().unwrap();
         let not_executed_order = OrderBuilder::unallocated(
-            OrderKind::LimitOrder(99.), symbol_id.clone(), Direction::Long
+            OrderKind::LimitOrder(Price::new(99.)), symbol_id.clone(), Direction::Long
         ).set_id(OrderId::from("not executed order")).build().unwrap();
         let updates = market_simulation.update_orders(
-            vec![&executed_order, &not_executed_order].into_iter(),
-            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 0., 0., 99., 0., 1)
+            &[&executed_order, &not_executed_order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 0., 0., 99., 0., Quantity::new(1.))
         );
         let expected: HashMap<OrderId, OrderStatus> = [
             (
@@ -155,7 +651,7 @@ mod test {
                     Execution::new(
                         symbol_id.clone(),
                         executed_order.quantity(),
-                        100.,
+                        Price::new(100.),
                         Utc.ymd(2016, 1, 3).and_hms(17, 0, 0)
                     )
                 )
@@ -166,17 +662,17 @@ mod test {
 
     #[test]
     fn update_limit_short_order() {
-        let market_simulation = MarketSimulation::new();
+        let mut market_simulation = MarketSimulation::new();
         let symbol_id = SymbolId::from("eur/usd");
         let executed_order = OrderBuilder::unallocated(
-            OrderKind::LimitOrder(100.), symbol_id.clone(), Direction::Short
+            OrderKind::LimitOrder(Price::new(100.)), symbol_id.clone(), Direction::Short
         ).set_id(OrderId::from("executed order")).build().unwrap();
         let not_executed_order = OrderBuilder::unallocated(
-            OrderKind::LimitOrder(101.), symbol_id.clone(), Direction::Short
+            OrderKind::LimitOrder(Price::new(101.)), symbol_id.clone(), Direction::Short
         ).set_id(OrderId::from("not executed order")).build().unwrap();
         let updates = market_simulation.update_orders(
-            vec![&executed_order, &not_executed_order].into_iter(),
-            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 0., 101., 0., 0., 1)
+            &[&executed_order, &not_executed_order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 0., 101., 0., 0., Quantity::new(1.))
         );
         let expected: HashMap<OrderId, OrderStatus> = [
             (
@@ -185,7 +681,7 @@ mod test {
                     Execution::new(
                         symbol_id.clone(),
                         executed_order.quantity(),
-                        100.,
+                        Price::new(100.),
                         Utc.ymd(2016, 1, 3).and_hms(17, 0, 0)
                     )
                 )
@@ -197,17 +693,17 @@ mod test {
 
     #[test]
     fn update_stop_long_order() {
-        let market_simulation = MarketSimulation::new();
+        let mut market_simulation = MarketSimulation::new();
         let symbol_id = SymbolId::from("eur/usd");
         let executed_order = OrderBuilder::unallocated(
-            OrderKind::StopOrder(100.), symbol_id.clone(), Direction::Long
+            OrderKind::StopOrder(Price::new(100.)), symbol_id.clone(), Direction::Long
         ).set_id(OrderId::from("executed order")).build().unwrap();
         let not_executed_order = OrderBuilder::unallocated(
-            OrderKind::StopOrder(101.), symbol_id.clone(), Direction::Long
+            OrderKind::StopOrder(Price::new(101.)), symbol_id.clone(), Direction::Long
         ).set_id(OrderId::from("not executed order")).build().unwrap();
         let updates = market_simulation.update_orders(
-            vec![&executed_order, &not_executed_order].into_iter(),
-            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 0., 101., 0., 0., 1)
+            &[&executed_order, &not_executed_order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 0., 101., 0., 0., Quantity::new(1.))
         );
         let expected: HashMap<OrderId, OrderStatus> = [
             (
@@ -216,7 +712,7 @@ mod test {
                     Execution::new(
                         symbol_id.clone(),
                         executed_order.quantity(),
-                        100.,
+                        Price::new(100.),
                         Utc.ymd(2016, 1, 3).and_hms(17, 0, 0)
                     )
                 )
@@ -227,17 +723,17 @@ mod test {
 
     #[test]
     fn update_stop_short_order() {
-        let market_simulation = MarketSimulation::new();
+        let mut market_simulation = MarketSimulation::new();
         let symbol_id = SymbolId::from("eur/usd");
         let executed_order = OrderBuilder::unallocated(
-            OrderKind::StopOrder(100.), symbol_id.clone(), Direction::Short
+            OrderKind::StopOrder(Price::new(100.)), symbol_id.clone(), Direction::Short
         ).set_id(OrderId::from("executed order")).build().unwrap();
         let not_executed_order = OrderBuilder::unallocated(
-            OrderKind::StopOrder(99.), symbol_id.clone(), Direction::Short
+            OrderKind::StopOrder(Price::new(99.)), symbol_id.clone(), Direction::Short
         ).set_id(OrderId::from("not executed order")).build().unwrap();
         let updates = market_simulation.update_orders(
-            vec![&executed_order, &not_executed_order].into_iter(),
-            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 0., 0., 99., 0., 1)
+            &[&executed_order, &not_executed_order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 0., 0., 99., 0., Quantity::new(1.))
         );
         let expected: HashMap<OrderId, OrderStatus> = [
             (
@@ -246,7 +742,7 @@ mod test {
                     Execution::new(
                         symbol_id.clone(),
                         executed_order.quantity(),
-                        100.,
+                        Price::new(100.),
                         Utc.ymd(2016, 1, 3).and_hms(17, 0, 0)
                     )
                 )
@@ -257,20 +753,20 @@ mod test {
 
     #[test]
     fn update_oca() {
-        let market_simulation = MarketSimulation::new();
+        let mut market_simulation = MarketSimulation::new();
         let symbol_id = SymbolId::from("eur/usd");
         let not_executed_order_1 = OrderBuilder::unallocated(
-            OrderKind::LimitOrder(100.), symbol_id.clone(), Direction::Short
+            OrderKind::LimitOrder(Price::new(100.)), symbol_id.clone(), Direction::Short
         ).set_oca(Some(String::from("0"))).set_id(OrderId::from("not executed order 1")).build().unwrap();
         let executed_order = OrderBuilder::unallocated(
             OrderKind::MarketOrder, symbol_id.clone(), Direction::Short
         ).set_oca(Some(String::from("0"))).set_id(OrderId::from("executed order")).build().unwrap();
         let not_executed_order_2 = OrderBuilder::unallocated(
-            OrderKind::StopOrder(98.), symbol_id.clone(), Direction::Short
+            OrderKind::StopOrder(Price::new(98.)), symbol_id.clone(), Direction::Short
         ).set_oca(Some(String::from("0"))).set_id(OrderId::from("not executed order 2")).build().unwrap();
         let updates = market_simulation.update_orders(
-            vec![&not_executed_order_1, &executed_order, &not_executed_order_2].into_iter(),
-            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 0., 0., 99., 0., 1)
+            &[&not_executed_order_1, &executed_order, &not_executed_order_2],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 0., 0., 99., 0., Quantity::new(1.))
         );
         let expected: HashMap<OrderId, OrderStatus> = [
             (not_executed_order_1.id().clone(), OrderStatus::Cancelled(CancellationReason::FilledOca)),
@@ -280,7 +776,7 @@ mod test {
                     Execution::new(
                         symbol_id.clone(),
                         executed_order.quantity(),
-                        0.,
+                        Price::new(0.),
                         Utc.ymd(2016, 1, 3).and_hms(17, 0, 0)
                     )
                 )
@@ -292,20 +788,20 @@ mod test {
 
     #[test]
     fn update_outdated_order() {
-        let market_simulation = MarketSimulation::new();
+        let mut market_simulation = MarketSimulation::new();
         let symbol_id = SymbolId::from("eur/usd");
         let executed_order = OrderBuilder::unallocated(
-            OrderKind::StopOrder(100.), symbol_id.clone(), Direction::Short
+            OrderKind::StopOrder(Price::new(100.)), symbol_id.clone(), Direction::Short
         ).set_id(OrderId::from("executed order")).build().unwrap();
         let cancelled_order = OrderBuilder::unallocated(
-            OrderKind::StopOrder(100.), symbol_id.clone(), Direction::Short
+            OrderKind::StopOrder(Price::new(100.)), symbol_id.clone(), Direction::Short
         )
             .set_active_until(Some(Utc.ymd(2016, 1, 3).and_hms(16, 59, 59)))
             .set_id(OrderId::from("cancelled order")).build().unwrap();
 
         let updates = market_simulation.update_orders(
-            vec![&executed_order, &cancelled_order].into_iter(),
-            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 0., 0., 99., 0., 1)
+            &[&executed_order, &cancelled_order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 0., 0., 99., 0., Quantity::new(1.))
         );
         let expected: HashMap<OrderId, OrderStatus> = [
             (
@@ -314,7 +810,7 @@ mod test {
                     Execution::new(
                         symbol_id.clone(),
                         executed_order.quantity(),
-                        100.,
+                        Price::new(100.),
                         Utc.ymd(2016, 1, 3).and_hms(17, 0, 0)
                     )
                 )
@@ -326,18 +822,18 @@ mod test {
 
     #[test]
     fn update_inactive_order() {
-        let market_simulation = MarketSimulation::new();
+        let mut market_simulation = MarketSimulation::new();
         let symbol_id = SymbolId::from("eur/usd");
         let executed_order = OrderBuilder::unallocated(
-            OrderKind::StopOrder(100.), symbol_id.clone(), Direction::Short
+            OrderKind::StopOrder(Price::new(100.)), symbol_id.clone(), Direction::Short
         ).set_id(OrderId::from("executed order")).build().unwrap();
-        let not_executed_order = OrderBuilder::unallocated(OrderKind::StopOrder(100.), symbol_id.clone(), Direction::Short)
+        let not_executed_order = OrderBuilder::unallocated(OrderKind::StopOrder(Price::new(100.)), symbol_id.clone(), Direction::Short)
             .set_active_after(Some(Utc.ymd(2016, 1, 3).and_hms(17, 0, 1)))
             .set_id(OrderId::from("not executed order"))
             .build().unwrap();
         let updates = market_simulation.update_orders(
-            vec![&executed_order, &not_executed_order].into_iter(),
-            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 0., 0., 99., 0., 1)
+            &[&executed_order, &not_executed_order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 0., 0., 99., 0., Quantity::new(1.))
         );
         let expected: HashMap<OrderId, OrderStatus> = [
             (
@@ -346,7 +842,7 @@ mod test {
                     Execution::new(
                         symbol_id.clone(),
                         executed_order.quantity(),
-                        100.,
+                        Price::new(100.),
                         Utc.ymd(2016, 1, 3).and_hms(17, 0, 0)
                     )
                 )
@@ -354,4 +850,498 @@ mod test {
         ].iter().cloned().collect();
         assert!(updates == expected);
     }
+
+    /// Filled status of the order at the given price
+    fn filled(order: &Order, price: f64, datetime: DateTime<Utc>) -> HashMap<OrderId, OrderStatus> {
+        [
+            (
+                order.id().clone(),
+                OrderStatus::Filled(
+                    Execution::new(order.symbol_id().clone(), order.quantity(), Price::new(price), datetime)
+                )
+            )
+        ].iter().cloned().collect()
+    }
+
+    #[test]
+    fn update_stop_limit_long_order() {
+        let mut market_simulation = MarketSimulation::new();
+        let symbol_id = SymbolId::from("eur/usd");
+        let executed_order = OrderBuilder::unallocated(
+            OrderKind::StopLimitOrder(Price::new(100.), Price::new(99.)), symbol_id.clone(), Direction::Long
+        ).set_id(OrderId::from("executed order")).build().unwrap();
+        let not_executed_order = OrderBuilder::unallocated(
+            OrderKind::StopLimitOrder(Price::new(102.), Price::new(99.)), symbol_id.clone(), Direction::Long
+        ).set_id(OrderId::from("not executed order")).build().unwrap();
+        // the stop of the first order is reached but not its limit
+        let updates = market_simulation.update_orders(
+            &[&executed_order, &not_executed_order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 100., 101., 99.5, 101., Quantity::new(1.))
+        );
+        assert!(updates.is_empty());
+        assert_eq!(
+            market_simulation.drain_events(),
+            vec![OrderEvent::new(Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), &executed_order, OrderEventKind::Triggered)]
+        );
+        let updates = market_simulation.update_orders(
+            &[&executed_order, &not_executed_order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0), 100., 100.5, 98.5, 99., Quantity::new(1.))
+        );
+        assert_eq!(updates, filled(&executed_order, 99., Utc.ymd(2016, 1, 3).and_hms(17, 1, 0)));
+    }
+
+    #[test]
+    fn update_trailing_stop_amount_order() {
+        let mut market_simulation = MarketSimulation::new();
+        let symbol_id = SymbolId::from("eur/usd");
+        let order = OrderBuilder::unallocated(
+            OrderKind::TrailingStopOrder(TrailingDistance::Amount(2.)), symbol_id.clone(), Direction::Short
+        ).set_id(OrderId::from("test order")).build().unwrap();
+        let bars = [
+            (100., 103., 99., 102.),
+            (102., 104., 101.5, 102.),
+            (103., 103., 101., 101.)
+        ];
+        let updates = bars.iter().enumerate().map(|(minute, &(open, high, low, close))| {
+            market_simulation.update_orders(
+                &[&order],
+                &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, minute as u32, 0), open, high, low, close, Quantity::new(1.))
+            )
+        }).collect::<Vec<HashMap<OrderId, OrderStatus>>>();
+        assert!(updates[0].is_empty());
+        assert!(updates[1].is_empty());
+        // stop at 2 below the highest high of 104
+        assert_eq!(updates[2], filled(&order, 102., Utc.ymd(2016, 1, 3).and_hms(17, 2, 0)));
+    }
+
+    #[test]
+    fn update_trailing_stop_percentage_order() {
+        let mut market_simulation = MarketSimulation::new();
+        let symbol_id = SymbolId::from("eur/usd");
+        let order = OrderBuilder::unallocated(
+            OrderKind::TrailingStopOrder(TrailingDistance::Percentage(10.)), symbol_id.clone(), Direction::Long
+        ).set_id(OrderId::from("test order")).build().unwrap();
+        let updates = market_simulation.update_orders(
+            &[&order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 100., 105., 95., 96., Quantity::new(1.))
+        );
+        assert!(updates.is_empty());
+        // stop at 10% above the lowest low of 95
+        let updates = market_simulation.update_orders(
+            &[&order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0), 96., 105., 96., 105., Quantity::new(1.))
+        );
+        assert_eq!(updates, filled(&order, 104.5, Utc.ymd(2016, 1, 3).and_hms(17, 1, 0)));
+    }
+
+    #[test]
+    fn update_trailing_stop_atr_order() {
+        let mut market_simulation = MarketSimulation::new();
+        let symbol_id = SymbolId::from("eur/usd");
+        let order = OrderBuilder::unallocated(
+            OrderKind::TrailingStopOrder(TrailingDistance::Atr(2, 1.)), symbol_id.clone(), Direction::Short
+        ).set_id(OrderId::from("test order")).build().unwrap();
+        let bars = [
+            (100., 102., 98., 100.),
+            (100., 101., 99., 100.),
+            (100., 100., 98.5, 99.)
+        ];
+        let updates = bars.iter().enumerate().map(|(minute, &(open, high, low, close))| {
+            market_simulation.update_orders(
+                &[&order],
+                &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, minute as u32, 0), open, high, low, close, Quantity::new(1.))
+            )
+        }).collect::<Vec<HashMap<OrderId, OrderStatus>>>();
+        // no stop until the average true range is available
+        assert!(updates[0].is_empty());
+        assert!(updates[1].is_empty());
+        // stop at an average true range of 3 below the highest high of 102
+        assert_eq!(updates[2], filled(&order, 99., Utc.ymd(2016, 1, 3).and_hms(17, 2, 0)));
+    }
+
+    #[test]
+    fn update_market_on_open_order() {
+        let mut market_simulation = MarketSimulation::new();
+        let symbol_id = SymbolId::from("eur/usd");
+        let order = OrderBuilder::unallocated(OrderKind::MarketOnOpen, symbol_id.clone(), Direction::Long)
+            .set_id(OrderId::from("test order")).build().unwrap();
+        market_simulation.update_orders(
+            &[],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 1., 2., 0., 1.5, Quantity::new(1.))
+        );
+        let updates = market_simulation.update_orders(
+            &[&order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0), 1.5, 2., 1., 1.5, Quantity::new(1.))
+        );
+        assert!(updates.is_empty());
+        let updates = market_simulation.update_orders(
+            &[&order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 4).and_hms(0, 0, 0), 2., 3., 1., 2.5, Quantity::new(1.))
+        );
+        assert_eq!(updates, filled(&order, 2., Utc.ymd(2016, 1, 4).and_hms(0, 0, 0)));
+    }
+
+    #[test]
+    fn update_market_on_close_order() {
+        let mut market_simulation = MarketSimulation::new();
+        let symbol_id = SymbolId::from("eur/usd");
+        let order = OrderBuilder::unallocated(OrderKind::MarketOnClose, symbol_id.clone(), Direction::Short)
+            .set_id(OrderId::from("test order")).build().unwrap();
+        let bars = [
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 1., 2., 0., 1.5, Quantity::new(1.)),
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0), 1.5, 2., 1., 1.8, Quantity::new(1.)),
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 4).and_hms(0, 0, 0), 2., 3., 1., 2.5, Quantity::new(1.))
+        ];
+        assert!(market_simulation.update_orders(&[&order], &bars[0]).is_empty());
+        assert!(market_simulation.update_orders(&[&order], &bars[1]).is_empty());
+        // the first bar of the next day ends the day, the order is filled at its last close
+        let updates = market_simulation.update_orders(&[&order], &bars[2]);
+        assert_eq!(updates, filled(&order, 1.8, *bars[1].datetime()));
+
+        // with a session close the order is filled on the bar of the close
+        let mut market_simulation = MarketSimulation::new().set_session_close(Some(NaiveTime::from_hms_opt(17, 1, 0).unwrap()));
+        assert!(market_simulation.update_orders(&[&order], &bars[0]).is_empty());
+        let updates = market_simulation.update_orders(&[&order], &bars[1]);
+        assert_eq!(updates, filled(&order, 1.8, *bars[1].datetime()));
+    }
+
+    #[test]
+    fn update_limit_on_close_order() {
+        let mut market_simulation = MarketSimulation::new();
+        let symbol_id = SymbolId::from("eur/usd");
+        let order = OrderBuilder::unallocated(OrderKind::LimitOnClose(Price::new(100.)), symbol_id.clone(), Direction::Long)
+            .set_id(OrderId::from("test order")).build().unwrap();
+        let bars = [
+            // the low reaches the limit but not the close of the day
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 101., 102., 99., 100.5, Quantity::new(1.)),
+            // an intraday close at the limit is not the close of the day
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 4).and_hms(17, 0, 0), 100.5, 101., 99., 99., Quantity::new(1.)),
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 4).and_hms(17, 1, 0), 99., 101., 99., 99.5, Quantity::new(1.)),
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 5).and_hms(17, 0, 0), 99.5, 101., 99., 100.5, Quantity::new(1.))
+        ];
+        for bar in bars[..3].iter() {
+            assert!(market_simulation.update_orders(&[&order], bar).is_empty());
+        }
+        let updates = market_simulation.update_orders(&[&order], &bars[3]);
+        assert_eq!(updates, filled(&order, 99.5, *bars[2].datetime()));
+    }
+
+    #[test]
+    fn update_day_order_sent_after_last_bar() {
+        let mut market_simulation = MarketSimulation::new();
+        let symbol_id = SymbolId::from("eur/usd");
+        let order = OrderBuilder::unallocated(OrderKind::LimitOrder(Price::new(90.)), symbol_id.clone(), Direction::Long)
+            .set_time_in_force(TimeInForce::Day)
+            .set_id(OrderId::from("test order")).build().unwrap();
+        // sent on the last bar of the day, the first bar it sees is on the next day
+        market_simulation.send_orders(&[&order], &Utc.ymd(2016, 1, 3).and_hms(23, 59, 0));
+        let updates = market_simulation.update_orders(
+            &[&order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 4).and_hms(0, 0, 0), 100., 101., 80., 100., Quantity::new(1.))
+        );
+        let expected: HashMap<OrderId, OrderStatus> = [
+            (order.id().clone(), OrderStatus::Cancelled(CancellationReason::DayEnded))
+        ].iter().cloned().collect();
+        assert_eq!(updates, expected);
+
+        // with latency the order is valid on the day it arrives
+        let latency_model = LatencyModel::new().set_order_latency(Latency::Fixed(chrono::Duration::minutes(2)));
+        let mut market_simulation = MarketSimulation::new().set_latency_model(Some(latency_model));
+        market_simulation.send_orders(&[&order], &Utc.ymd(2016, 1, 3).and_hms(23, 59, 0));
+        let updates = market_simulation.update_orders(
+            &[&order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 4).and_hms(0, 1, 0), 100., 101., 80., 100., Quantity::new(1.))
+        );
+        assert_eq!(updates, filled(&order, 90., Utc.ymd(2016, 1, 4).and_hms(0, 1, 0)));
+    }
+
+    #[test]
+    fn update_day_order() {
+        let mut market_simulation = MarketSimulation::new();
+        let symbol_id = SymbolId::from("eur/usd");
+        let order = OrderBuilder::unallocated(OrderKind::LimitOrder(Price::new(90.)), symbol_id.clone(), Direction::Long)
+            .set_time_in_force(TimeInForce::Day)
+            .set_id(OrderId::from("test order")).build().unwrap();
+        let updates = market_simulation.update_orders(
+            &[&order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 100., 101., 99., 100., Quantity::new(1.))
+        );
+        assert!(updates.is_empty());
+        let updates = market_simulation.update_orders(
+            &[&order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(23, 59, 0), 100., 101., 99., 100., Quantity::new(1.))
+        );
+        assert!(updates.is_empty());
+        let updates = market_simulation.update_orders(
+            &[&order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 4).and_hms(0, 0, 0), 100., 101., 80., 100., Quantity::new(1.))
+        );
+        let expected: HashMap<OrderId, OrderStatus> = [
+            (order.id().clone(), OrderStatus::Cancelled(CancellationReason::DayEnded))
+        ].iter().cloned().collect();
+        assert_eq!(updates, expected);
+    }
+
+    #[test]
+    fn update_good_till_date_order() {
+        let mut market_simulation = MarketSimulation::new();
+        let symbol_id = SymbolId::from("eur/usd");
+        let order = OrderBuilder::unallocated(OrderKind::LimitOrder(Price::new(90.)), symbol_id.clone(), Direction::Long)
+            .set_time_in_force(TimeInForce::GoodTillDate(Utc.ymd(2016, 1, 3).and_hms(17, 1, 0)))
+            .set_id(OrderId::from("test order")).build().unwrap();
+        let updates = market_simulation.update_orders(
+            &[&order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 100., 101., 99., 100., Quantity::new(1.))
+        );
+        assert!(updates.is_empty());
+        let updates = market_simulation.update_orders(
+            &[&order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0), 100., 101., 80., 100., Quantity::new(1.))
+        );
+        let expected: HashMap<OrderId, OrderStatus> = [
+            (order.id().clone(), OrderStatus::Cancelled(CancellationReason::DateReached))
+        ].iter().cloned().collect();
+        assert_eq!(updates, expected);
+    }
+
+    #[test]
+    fn update_immediate_or_cancel_order() {
+        let mut market_simulation = MarketSimulation::new();
+        let symbol_id = SymbolId::from("eur/usd");
+        let executed_order = OrderBuilder::unallocated(OrderKind::LimitOrder(Price::new(100.)), symbol_id.clone(), Direction::Long)
+            .set_time_in_force(TimeInForce::ImmediateOrCancel)
+            .set_id(OrderId::from("executed order")).build().unwrap();
+        let cancelled_order = OrderBuilder::unallocated(OrderKind::LimitOrder(Price::new(90.)), symbol_id.clone(), Direction::Long)
+            .set_time_in_force(TimeInForce::ImmediateOrCancel)
+            .set_id(OrderId::from("cancelled order")).build().unwrap();
+        let not_active_order = OrderBuilder::unallocated(OrderKind::LimitOrder(Price::new(90.)), symbol_id.clone(), Direction::Long)
+            .set_time_in_force(TimeInForce::ImmediateOrCancel)
+            .set_active_after(Some(Utc.ymd(2016, 1, 3).and_hms(17, 1, 0)))
+            .set_id(OrderId::from("not active order")).build().unwrap();
+        let updates = market_simulation.update_orders(
+            &[&executed_order, &cancelled_order, &not_active_order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 100., 101., 99., 100., Quantity::new(1.))
+        );
+        let mut expected = filled(&executed_order, 100., Utc.ymd(2016, 1, 3).and_hms(17, 0, 0));
+        expected.insert(cancelled_order.id().clone(), OrderStatus::Cancelled(CancellationReason::NotFilledImmediately));
+        assert_eq!(updates, expected);
+    }
+
+    #[test]
+    fn update_fill_or_kill_order() {
+        let mut market_simulation = MarketSimulation::new();
+        let symbol_id = SymbolId::from("eur/usd");
+        let order = OrderBuilder::unallocated(OrderKind::StopOrder(Price::new(102.)), symbol_id.clone(), Direction::Long)
+            .set_time_in_force(TimeInForce::FillOrKill)
+            .set_id(OrderId::from("test order")).build().unwrap();
+        let updates = market_simulation.update_orders(
+            &[&order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 100., 101., 99., 100., Quantity::new(1.))
+        );
+        let expected: HashMap<OrderId, OrderStatus> = [
+            (order.id().clone(), OrderStatus::Cancelled(CancellationReason::NotFilledEntirely))
+        ].iter().cloned().collect();
+        assert_eq!(updates, expected);
+    }
+
+    #[test]
+    fn update_partially_filled_order() {
+        let mut market_simulation = MarketSimulation::new().set_max_volume_participation(Some(50.));
+        let symbol_id = SymbolId::from("eur/usd");
+        let mut order = OrderBuilder::unallocated(OrderKind::StopOrder(Price::new(100.)), symbol_id.clone(), Direction::Long)
+            .set_quantity(Quantity::new(3.))
+            .set_id(OrderId::from("test order")).build().unwrap();
+        let bars = [
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 99., 101., 98., 100., Quantity::new(2.)),
+            // the rest of the stop is executed at the open without reaching the stop again
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0), 99., 99.5, 98., 99., Quantity::new(2.)),
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 2, 0), 98., 99., 97., 98., Quantity::new(4.))
+        ];
+        let mut statuses = vec![];
+        for bar in bars.iter() {
+            let status = market_simulation.update_orders(&[&order], bar).remove(order.id()).unwrap();
+            if let OrderStatus::PartiallyFilled(ref execution) = status {
+                order.add_execution(execution.clone());
+            }
+            statuses.push(status);
+        }
+        assert_eq!(statuses, vec![
+            OrderStatus::PartiallyFilled(Execution::new(symbol_id.clone(), Quantity::new(1.), Price::new(100.), *bars[0].datetime())),
+            OrderStatus::PartiallyFilled(Execution::new(symbol_id.clone(), Quantity::new(1.), Price::new(99.), *bars[1].datetime())),
+            OrderStatus::Filled(Execution::new(symbol_id.clone(), Quantity::new(1.), Price::new(98.), *bars[2].datetime()))
+        ]);
+    }
+
+    #[test]
+    fn update_limit_order_queue() {
+        let mut market_simulation = MarketSimulation::new().set_queue_model(Some(QueueModel::new(100., 50.)));
+        let symbol_id = SymbolId::from("eur/usd");
+        let mut order = OrderBuilder::unallocated(OrderKind::LimitOrder(Price::new(100.)), symbol_id.clone(), Direction::Long)
+            .set_quantity(Quantity::new(4.))
+            .set_id(OrderId::from("test order")).build().unwrap();
+        let bar = |minute: u32, low: f64, volume: f64| {
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, minute, 0), 101., 102., low, 101., Quantity::new(volume))
+        };
+        assert!(market_simulation.update_orders(&[], &bar(0, 101., 10.)).is_empty());
+        // 10 ahead of the order when it is placed
+        market_simulation.send_orders(&[&order], &Utc.ymd(2016, 1, 3).and_hms(17, 0, 0));
+
+        // touches deplete the queue by half of their volume
+        assert!(market_simulation.update_orders(&[&order], &bar(1, 100., 10.)).is_empty());
+        let bars = [bar(2, 100., 12.), bar(3, 100., 4.), bar(4, 99., 1.)];
+        let mut statuses = vec![];
+        for bar in bars.iter() {
+            let status = market_simulation.update_orders(&[&order], bar).remove(order.id()).unwrap();
+            if let OrderStatus::PartiallyFilled(ref execution) = status {
+                order.add_execution(execution.clone());
+            }
+            statuses.push(status);
+        }
+        assert_eq!(statuses, vec![
+            OrderStatus::PartiallyFilled(Execution::new(symbol_id.clone(), Quantity::new(1.), Price::new(100.), *bars[0].datetime())),
+            OrderStatus::PartiallyFilled(Execution::new(symbol_id.clone(), Quantity::new(2.), Price::new(100.), *bars[1].datetime())),
+            // trading through the limit fills the rest
+            OrderStatus::Filled(Execution::new(symbol_id.clone(), Quantity::new(1.), Price::new(100.), *bars[2].datetime()))
+        ]);
+    }
+
+    #[test]
+    fn market_impact() {
+        let market_impact_model = MarketImpactModel::new(ImpactFunction::Linear(2.)).set_days(1);
+        let mut market_simulation = MarketSimulation::new().set_market_impact_model(Some(market_impact_model));
+        let symbol_id = SymbolId::from("eur/usd");
+        let bar = |day: u32, close: f64| {
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, day).and_hms(17, 0, 0), 100., 101., 99., close, Quantity::new(100.))
+        };
+        // a volatility of 0.25 and an average daily volume of 100
+        assert!(market_simulation.update_orders(&[], &bar(1, 100.)).is_empty());
+        assert!(market_simulation.update_orders(&[], &bar(2, 125.)).is_empty());
+
+        let market_order = OrderBuilder::unallocated(OrderKind::MarketOrder, symbol_id.clone(), Direction::Short)
+            .set_quantity(Quantity::new(10.))
+            .set_id(OrderId::from("market order")).build().unwrap();
+        let limit_order = OrderBuilder::unallocated(OrderKind::LimitOrder(Price::new(99.5)), symbol_id.clone(), Direction::Long)
+            .set_quantity(Quantity::new(10.))
+            .set_id(OrderId::from("limit order")).build().unwrap();
+        let bar = bar(3, 100.);
+        let updates = market_simulation.update_orders(&[&market_order, &limit_order], &bar);
+        let price = |order: &Order| match updates[order.id()] {
+            OrderStatus::Filled(ref execution) => *execution.price(),
+            ref status => panic!("unexpected status {:?}", status)
+        };
+        // the short is filled 0.25 * 2 * 0.1 below the open, the limit order at its limit
+        assert!((price(&market_order).value() - 95.).abs() < 1e-9);
+        assert_eq!(price(&limit_order), Price::new(99.5));
+    }
+
+    #[test]
+    fn update_partially_filled_time_in_force() {
+        let mut market_simulation = MarketSimulation::new().set_max_volume_participation(Some(10.));
+        let symbol_id = SymbolId::from("eur/usd");
+        let mut immediate_or_cancel_order = OrderBuilder::unallocated(OrderKind::MarketOrder, symbol_id.clone(), Direction::Long)
+            .set_quantity(Quantity::new(2.))
+            .set_time_in_force(TimeInForce::ImmediateOrCancel)
+            .set_id(OrderId::from("immediate or cancel order")).build().unwrap();
+        let fill_or_kill_order = OrderBuilder::unallocated(OrderKind::MarketOrder, symbol_id.clone(), Direction::Long)
+            .set_quantity(Quantity::new(2.))
+            .set_time_in_force(TimeInForce::FillOrKill)
+            .set_id(OrderId::from("fill or kill order")).build().unwrap();
+        let execution = Execution::new(symbol_id.clone(), Quantity::new(1.), Price::new(1.), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0));
+        let updates = market_simulation.update_orders(
+            &[&immediate_or_cancel_order, &fill_or_kill_order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 1., 2., 0., 1.5, Quantity::new(10.))
+        );
+        let expected: HashMap<OrderId, OrderStatus> = [
+            (immediate_or_cancel_order.id().clone(), OrderStatus::PartiallyFilled(execution.clone())),
+            (fill_or_kill_order.id().clone(), OrderStatus::Cancelled(CancellationReason::NotFilledEntirely))
+        ].iter().cloned().collect();
+        assert_eq!(updates, expected);
+
+        // the rest of the immediate or cancel order is cancelled
+        immediate_or_cancel_order.add_execution(execution);
+        let updates = market_simulation.update_orders(
+            &[&immediate_or_cancel_order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0), 1., 2., 0., 1.5, Quantity::new(10.))
+        );
+        let expected: HashMap<OrderId, OrderStatus> = [
+            (immediate_or_cancel_order.id().clone(), OrderStatus::Cancelled(CancellationReason::NotFilledImmediately))
+        ].iter().cloned().collect();
+        assert_eq!(updates, expected);
+    }
+
+    #[test]
+    fn process_requests() {
+        let mut market_simulation = MarketSimulation::new();
+        let symbol_id = SymbolId::from("eur/usd");
+        let mut order = OrderBuilder::unallocated(
+            OrderKind::StopLimitOrder(Price::new(100.), Price::new(99.)), symbol_id.clone(), Direction::Long
+        ).set_id(OrderId::from("test order")).set_quantity(Quantity::new(1.)).build().unwrap();
+        // the stop is reached but not the limit
+        let updates = market_simulation.update_orders(
+            &[&order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 100., 101., 99.5, 101., Quantity::new(1.))
+        );
+        assert!(updates.is_empty());
+
+        let modification = OrderRequest::Modify(
+            order.id().clone(), OrderKind::StopLimitOrder(Price::new(102.), Price::new(99.)), Quantity::new(1.)
+        );
+        let datetime = Utc.ymd(2016, 1, 3).and_hms(17, 0, 0);
+        market_simulation.add_requests(vec![
+            modification.clone(),
+            OrderRequest::Modify(order.id().clone(), OrderKind::MarketOrder, Quantity::zero()),
+            OrderRequest::Cancel(OrderId::from("unknown order"))
+        ], &datetime);
+        assert_eq!(market_simulation.process_requests(&[&order], &datetime), vec![modification]);
+        assert!(market_simulation.process_requests(&[&order], &datetime).is_empty());
+
+        // the modified order has to be triggered again
+        order.set_kind(OrderKind::StopLimitOrder(Price::new(102.), Price::new(99.)));
+        let updates = market_simulation.update_orders(
+            &[&order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0), 100., 100.5, 98.5, 99., Quantity::new(1.))
+        );
+        assert!(updates.is_empty());
+    }
+
+    #[test]
+    fn latency() {
+        let latency_model = LatencyModel::new()
+            .set_order_latency(Latency::Fixed(chrono::Duration::seconds(30)))
+            .set_report_latency(Latency::Fixed(chrono::Duration::minutes(1)));
+        let mut market_simulation = MarketSimulation::new().set_latency_model(Some(latency_model));
+        let symbol_id = SymbolId::from("eur/usd");
+        let order = OrderBuilder::unallocated(OrderKind::MarketOrder, symbol_id.clone(), Direction::Long)
+            .set_id(OrderId::from("test order"))
+            .build().unwrap();
+        market_simulation.send_orders(&[&order], &Utc.ymd(2016, 1, 3).and_hms(17, 0, 0));
+
+        // the order has not reached the market on the first bar
+        let updates = market_simulation.update_orders(
+            &[&order],
+            &Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 20), 1., 2., 0., 1.5, Quantity::new(1.))
+        );
+        assert!(updates.is_empty());
+        let datetime = Utc.ymd(2016, 1, 3).and_hms(17, 0, 40);
+        let updates = market_simulation.update_orders(
+            &[&order],
+            &Ohlcv::new(symbol_id.clone(), datetime, 1., 2., 0., 1.5, Quantity::new(1.))
+        );
+        assert_eq!(updates, filled(&order, 1., datetime));
+
+        // the fill is reported a minute later
+        assert!(market_simulation.reports(&datetime).is_empty());
+        assert_eq!(
+            market_simulation.reports(&Utc.ymd(2016, 1, 3).and_hms(17, 1, 40)),
+            updates.into_iter().collect::<Vec<(OrderId, OrderStatus)>>()
+        );
+        assert!(market_simulation.reports(&Utc.ymd(2016, 1, 3).and_hms(17, 2, 0)).is_empty());
+        let events = market_simulation.drain_events().into_iter()
+            .map(|event| (*event.datetime(), event.kind().clone()))
+            .collect::<Vec<(DateTime<Utc>, OrderEventKind)>>();
+        assert_eq!(events, vec![
+            (Utc.ymd(2016, 1, 3).and_hms(17, 0, 30), OrderEventKind::Sent),
+            (datetime, OrderEventKind::Filled(Execution::new(symbol_id.clone(), order.quantity(), Price::new(1.), datetime)))
+        ]);
+    }
 }
@@ -0,0 +1,52 @@
+use quantity::Quantity;
+
+/// Estimate of the position of resting limit orders in the queue at their limit price
+///
+/// The quantity ahead of an order is estimated from the volume of the last bar of its symbol
+/// when it is placed. Bars touching the limit price without trading through it deplete the
+/// queue by the volume estimated to trade at that price, and the order is filled with what is
+/// left once the queue is exhausted.
+#[derive(Clone, PartialEq, Debug)]
+pub struct QueueModel {
+    queue_ahead: f64,
+    traded_at_price: f64
+}
+
+impl QueueModel {
+    pub fn new(queue_ahead: f64, traded_at_price: f64) -> QueueModel {
+        QueueModel {
+            queue_ahead,
+            traded_at_price
+        }
+    }
+
+    /// Percent of the volume of the last bar resting ahead of an order when it is placed
+    pub fn queue_ahead(&self) -> f64 {
+        self.queue_ahead
+    }
+
+    /// Percent of the volume of a bar touching the limit price traded at that price
+    pub fn traded_at_price(&self) -> f64 {
+        self.traded_at_price
+    }
+
+    pub fn estimate_queue_ahead(&self, volume: Quantity) -> Quantity {
+        volume * (self.queue_ahead / 100.)
+    }
+
+    pub fn estimate_traded_at_price(&self, volume: Quantity) -> Quantity {
+        volume * (self.traded_at_price / 100.)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn estimate() {
+        let queue_model = QueueModel::new(50., 20.);
+        assert_eq!(queue_model.estimate_queue_ahead(Quantity::new(10.)), Quantity::new(5.));
+        assert_eq!(queue_model.estimate_traded_at_price(Quantity::new(10.)), Quantity::new(2.));
+    }
+}
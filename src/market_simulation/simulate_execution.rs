@@ -0,0 +1,35 @@
+extern crate chrono;
+use std::collections::HashMap;
+use self::chrono::prelude::{DateTime, Utc};
+use ohlcv::Ohlcv;
+use order::{Order, OrderId, OrderStatus, OrderRequest, OrderEvent};
+
+/// Simulation of the execution of orders on market data
+///
+/// The backtester sends the orders created by the strategies and their cancel and modify requests
+/// to the simulation, and updates the active orders with each bar. Each run of a backtest starts
+/// from a copy of the simulation given to the backtester.
+pub trait SimulateExecution {
+    /// Update the active orders with a bar, returns the new status of the updated orders
+    fn update_orders(&mut self, orders: &[&Order], ohlcv: &Ohlcv) -> HashMap<OrderId, OrderStatus>;
+
+    /// Called with the orders created at the given datetime, before they are updated
+    fn send_orders(&mut self, _orders: &[&Order], _datetime: &DateTime<Utc>) {}
+
+    /// Called with the cancel and modify requests made by strategies at the given datetime
+    fn add_requests(&mut self, requests: Vec<OrderRequest>, datetime: &DateTime<Utc>);
+
+    /// Get the requests to apply to the active orders at the given datetime
+    fn process_requests(&mut self, orders: &[&Order], datetime: &DateTime<Utc>) -> Vec<OrderRequest>;
+
+    /// Get the order updates reported to the strategies at the given datetime
+    fn reports(&mut self, datetime: &DateTime<Utc>) -> Vec<(OrderId, OrderStatus)>;
+
+    /// Get the events of the orders since the last call
+    fn drain_events(&mut self) -> Vec<OrderEvent> {
+        vec![]
+    }
+
+    /// Copy of the simulation for a new run of the backtest
+    fn box_clone(&self) -> Box<SimulateExecution>;
+}
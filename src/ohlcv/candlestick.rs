@@ -0,0 +1,186 @@
+use direction::Direction;
+use ohlcv::Ohlcv;
+
+/// Classic candlestick pattern, found on the last bars of a window in the bullish variant for a
+/// long direction and in the bearish variant for a short direction
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum CandlestickPattern {
+    /// Body engulfing the opposite body of the previous bar
+    Engulfing,
+    /// Hammer for long, shooting star for short
+    Hammer,
+    /// Body smaller than a tenth of the range, in both directions
+    Doji,
+    /// Morning star for long, evening star for short
+    Star,
+    /// Range inside the range of the previous bar, in both directions
+    InsideBar,
+    /// Range outside the range of the previous bar, closing in the direction
+    OutsideBar,
+    /// Three white soldiers for long, three black crows for short
+    ThreeSoldiers
+}
+
+impl CandlestickPattern {
+
+    /// Number of bars making the pattern
+    pub fn bars(&self) -> usize {
+        match *self {
+            CandlestickPattern::Hammer | CandlestickPattern::Doji => 1,
+            CandlestickPattern::Engulfing | CandlestickPattern::InsideBar | CandlestickPattern::OutsideBar => 2,
+            CandlestickPattern::Star | CandlestickPattern::ThreeSoldiers => 3
+        }
+    }
+
+    pub fn name(&self, direction: Direction) -> &'static str {
+        match (*self, direction) {
+            (CandlestickPattern::Engulfing, Direction::Long) => "bullish engulfing",
+            (CandlestickPattern::Engulfing, Direction::Short) => "bearish engulfing",
+            (CandlestickPattern::Hammer, Direction::Long) => "hammer",
+            (CandlestickPattern::Hammer, Direction::Short) => "shooting star",
+            (CandlestickPattern::Doji, _) => "doji",
+            (CandlestickPattern::Star, Direction::Long) => "morning star",
+            (CandlestickPattern::Star, Direction::Short) => "evening star",
+            (CandlestickPattern::InsideBar, _) => "inside bar",
+            (CandlestickPattern::OutsideBar, Direction::Long) => "bullish outside bar",
+            (CandlestickPattern::OutsideBar, Direction::Short) => "bearish outside bar",
+            (CandlestickPattern::ThreeSoldiers, Direction::Long) => "three white soldiers",
+            (CandlestickPattern::ThreeSoldiers, Direction::Short) => "three black crows"
+        }
+    }
+
+    /// Whether the pattern ends on the last bar of the window, ordered from the oldest bar
+    pub fn is_found(&self, direction: Direction, window: &[Ohlcv]) -> bool {
+        if window.len() < self.bars() {
+            return false;
+        }
+        let bars = &window[window.len() - self.bars()..];
+        let bullish = direction == Direction::Long;
+        match *self {
+            CandlestickPattern::Engulfing => {
+                let (previous, current) = (&bars[0], &bars[1]);
+                is_bullish(previous) != bullish && is_bullish(current) == bullish && body(previous) > 0.
+                    && body_top(current) >= body_top(previous) && body_bottom(current) <= body_bottom(previous)
+            },
+            CandlestickPattern::Hammer => {
+                let bar = &bars[0];
+                let (long_shadow, short_shadow) = if bullish {
+                    (lower_shadow(bar), upper_shadow(bar))
+                }
+                else {
+                    (upper_shadow(bar), lower_shadow(bar))
+                };
+                body(bar) > 0. && long_shadow >= 2. * body(bar) && short_shadow <= body(bar)
+            },
+            CandlestickPattern::Doji => {
+                let bar = &bars[0];
+                range(bar) > 0. && body(bar) <= range(bar) / 10.
+            },
+            CandlestickPattern::Star => {
+                let (first, middle, last) = (&bars[0], &bars[1], &bars[2]);
+                let first_middle = (first.open() + first.close()) / 2.;
+                let last_beyond_middle = if bullish { last.close() > first_middle } else { last.close() < first_middle };
+                is_bullish(first) != bullish && is_bullish(last) == bullish && body(first) > 0.
+                    && body(middle) <= body(first) * 0.3 && last_beyond_middle
+            },
+            CandlestickPattern::InsideBar => {
+                bars[1].high() < bars[0].high() && bars[1].low() > bars[0].low()
+            },
+            CandlestickPattern::OutsideBar => {
+                bars[1].high() > bars[0].high() && bars[1].low() < bars[0].low()
+                    && is_bullish(&bars[1]) == bullish && body(&bars[1]) > 0.
+            },
+            CandlestickPattern::ThreeSoldiers => {
+                bars.iter().all(|bar| is_bullish(bar) == bullish && body(bar) > 0.)
+                    && bars.windows(2).all(|pair| {
+                        let (previous, current) = (&pair[0], &pair[1]);
+                        let opens_inside = current.open() >= body_bottom(previous) && current.open() <= body_top(previous);
+                        let moves_on = if bullish { current.close() > previous.close() } else { current.close() < previous.close() };
+                        opens_inside && moves_on
+                    })
+            }
+        }
+    }
+
+}
+
+fn is_bullish(ohlcv: &Ohlcv) -> bool {
+    ohlcv.close() >= ohlcv.open()
+}
+
+fn body(ohlcv: &Ohlcv) -> f64 {
+    (ohlcv.close() - ohlcv.open()).abs()
+}
+
+fn body_top(ohlcv: &Ohlcv) -> f64 {
+    ohlcv.open().max(ohlcv.close())
+}
+
+fn body_bottom(ohlcv: &Ohlcv) -> f64 {
+    ohlcv.open().min(ohlcv.close())
+}
+
+fn range(ohlcv: &Ohlcv) -> f64 {
+    ohlcv.high() - ohlcv.low()
+}
+
+fn upper_shadow(ohlcv: &Ohlcv) -> f64 {
+    ohlcv.high() - body_top(ohlcv)
+}
+
+fn lower_shadow(ohlcv: &Ohlcv) -> f64 {
+    body_bottom(ohlcv) - ohlcv.low()
+}
+
+#[cfg(test)]
+mod test {
+    extern crate chrono;
+    use super::*;
+    use self::chrono::prelude::{Utc, TimeZone};
+    use quantity::Quantity;
+    use symbol::SymbolId;
+
+    fn bar(minute: u32, open: f64, high: f64, low: f64, close: f64) -> Ohlcv {
+        Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2016, 1, 3).and_hms(17, minute, 0), open, high, low, close, Quantity::new(1.))
+    }
+
+    #[test]
+    fn engulfing() {
+        let window = vec![bar(0, 2., 2.1, 1.4, 1.5), bar(1, 1.4, 2.2, 1.3, 2.1)];
+        assert!(CandlestickPattern::Engulfing.is_found(Direction::Long, &window));
+        assert!(!CandlestickPattern::Engulfing.is_found(Direction::Short, &window));
+    }
+
+    #[test]
+    fn hammer_and_doji() {
+        let hammer = vec![bar(0, 1.8, 2., 1., 2.)];
+        assert!(CandlestickPattern::Hammer.is_found(Direction::Long, &hammer));
+        assert!(!CandlestickPattern::Hammer.is_found(Direction::Short, &hammer));
+        let doji = vec![bar(0, 1.5, 2., 1., 1.55)];
+        assert!(CandlestickPattern::Doji.is_found(Direction::Short, &doji));
+        assert!(!CandlestickPattern::Doji.is_found(Direction::Long, &hammer));
+    }
+
+    #[test]
+    fn star() {
+        let window = vec![bar(0, 2., 2., 1., 1.), bar(1, 0.9, 1., 0.8, 0.95), bar(2, 1., 1.8, 1., 1.8)];
+        assert!(CandlestickPattern::Star.is_found(Direction::Long, &window));
+        assert!(!CandlestickPattern::Star.is_found(Direction::Long, &window[1..]));
+    }
+
+    #[test]
+    fn inside_and_outside_bars() {
+        let window = vec![bar(0, 1., 2., 1., 2.), bar(1, 1.2, 1.8, 1.1, 1.5)];
+        assert!(CandlestickPattern::InsideBar.is_found(Direction::Long, &window));
+        assert!(!CandlestickPattern::OutsideBar.is_found(Direction::Long, &window));
+        let window = vec![bar(0, 1.2, 1.8, 1.1, 1.5), bar(1, 2., 2., 1., 1.)];
+        assert!(CandlestickPattern::OutsideBar.is_found(Direction::Short, &window));
+    }
+
+    #[test]
+    fn three_soldiers() {
+        let window = vec![bar(0, 1., 1.5, 1., 1.5), bar(1, 1.4, 2., 1.4, 2.), bar(2, 1.9, 2.5, 1.9, 2.5)];
+        assert!(CandlestickPattern::ThreeSoldiers.is_found(Direction::Long, &window));
+        assert!(!CandlestickPattern::ThreeSoldiers.is_found(Direction::Short, &window));
+    }
+}
@@ -2,8 +2,14 @@ extern crate chrono;
 use self::chrono::prelude::{DateTime, Utc};
 
 pub mod source;
+pub mod transform;
+mod time_slice;
+mod candlestick;
+pub use self::time_slice::{TimeSlice, SliceBar, MissingBarPolicy, TimeSliceAligner};
+pub use self::candlestick::CandlestickPattern;
 
 use symbol::SymbolId;
+use quantity::Quantity;
 
 #[derive(PartialEq, Clone, Debug)]
 pub struct Ohlcv {
@@ -13,12 +19,12 @@ pub struct Ohlcv {
     high: f64,
     low: f64,
     close: f64,
-    volume: u32
+    volume: Quantity
 }
 
 impl Ohlcv {
 
-    pub fn new(symbol_id: SymbolId, datetime: DateTime<Utc>, open: f64, high: f64, low: f64, close: f64, volume: u32) -> Ohlcv {
+    pub fn new(symbol_id: SymbolId, datetime: DateTime<Utc>, open: f64, high: f64, low: f64, close: f64, volume: Quantity) -> Ohlcv {
         Ohlcv {
             symbol_id,
             datetime,
@@ -54,7 +60,7 @@ impl Ohlcv {
         self.close
     }
 
-    pub fn volume(&self) -> u32 {
+    pub fn volume(&self) -> Quantity {
         self.volume
     }
 }
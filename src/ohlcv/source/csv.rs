@@ -53,6 +53,7 @@ impl<T: Read> CsvOhlcvSource<T> {
 mod tests {
     use super::*;
     use symbol::SymbolId;
+    use quantity::Quantity;
 
     use ohlcv::chrono::TimeZone;
 
@@ -76,7 +77,7 @@ mod tests {
                     high: 1.087130,
                     low: 1.087010,
                     close: 1.087130,
-                    volume: 1
+                    volume: Quantity::new(1.)
                 }
             ]
         )
@@ -39,6 +39,7 @@ mod test {
     use self::chrono::prelude::TimeZone;
     use symbol::SymbolId;
     use ohlcv::source::OhlcvSource;
+    use quantity::Quantity;
 
     pub struct FakeOhlcvSource { symbol_id: SymbolId }
     impl OhlcvSource for FakeOhlcvSource {
@@ -46,8 +47,8 @@ mod test {
                  -> Result<Vec<Ohlcv>, OhlcvSourceError>
         {
             Ok(vec![
-                Ohlcv::new(self.symbol_id.clone(), Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), 1., 1., 1., 1., 0),
-                Ohlcv::new(self.symbol_id.clone(), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 1., 1.5, 1., 1.5, 3)
+                Ohlcv::new(self.symbol_id.clone(), Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), 1., 1., 1., 1., Quantity::new(0.)),
+                Ohlcv::new(self.symbol_id.clone(), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 1., 1.5, 1., 1.5, Quantity::new(3.))
             ])
         }
     }
@@ -66,10 +67,10 @@ mod test {
                 &Utc.ymd(2017, 12, 29).and_hms(12, 0, 5)
             ).unwrap(),
             vec![
-                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), 1., 1., 1., 1., 0),
-                Ohlcv::new(SymbolId::from("usd/jpy"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), 1., 1., 1., 1., 0),
-                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 1., 1.5, 1., 1.5, 3),
-                Ohlcv::new(SymbolId::from("usd/jpy"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 1., 1.5, 1., 1.5, 3)
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), 1., 1., 1., 1., Quantity::new(0.)),
+                Ohlcv::new(SymbolId::from("usd/jpy"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), 1., 1., 1., 1., Quantity::new(0.)),
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 1., 1.5, 1., 1.5, Quantity::new(3.)),
+                Ohlcv::new(SymbolId::from("usd/jpy"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 1., 1.5, 1., 1.5, Quantity::new(3.))
             ]
         );
     }
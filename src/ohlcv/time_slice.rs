@@ -0,0 +1,237 @@
+extern crate chrono;
+
+use std::collections::{BTreeMap, HashMap};
+use std::iter::Peekable;
+use self::chrono::prelude::{DateTime, Utc};
+use ohlcv::Ohlcv;
+use symbol::SymbolId;
+use quantity::Quantity;
+
+/// Bar of a symbol inside a time slice
+#[derive(PartialEq, Clone, Debug)]
+pub enum SliceBar {
+    /// Bar received from the source at the slice datetime
+    Traded(Ohlcv),
+    /// Flat bar built from the last known close of the symbol, without volume
+    ForwardFilled(Ohlcv),
+    /// No bar is available for the symbol at the slice datetime
+    Missing
+}
+
+impl SliceBar {
+
+    pub fn ohlcv(&self) -> Option<&Ohlcv> {
+        match *self {
+            SliceBar::Traded(ref ohlcv) => Some(ohlcv),
+            SliceBar::ForwardFilled(ref ohlcv) => Some(ohlcv),
+            SliceBar::Missing => None
+        }
+    }
+
+}
+
+/// How to fill a time slice for a symbol that has no bar at the slice datetime
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum MissingBarPolicy {
+    ForwardFill,
+    Mark
+}
+
+/// All the bars sharing the same datetime
+#[derive(PartialEq, Clone, Debug)]
+pub struct TimeSlice {
+    datetime: DateTime<Utc>,
+    bars: BTreeMap<SymbolId, SliceBar>
+}
+
+impl TimeSlice {
+
+    pub fn new(datetime: DateTime<Utc>, bars: BTreeMap<SymbolId, SliceBar>) -> TimeSlice {
+        TimeSlice { datetime, bars }
+    }
+
+    pub fn datetime(&self) -> &DateTime<Utc> {
+        &self.datetime
+    }
+
+    pub fn bars(&self) -> &BTreeMap<SymbolId, SliceBar> {
+        &self.bars
+    }
+
+    /// Bar of the symbol, either traded or forward filled
+    pub fn ohlcv(&self, symbol_id: &SymbolId) -> Option<&Ohlcv> {
+        self.bars.get(symbol_id).and_then(|bar| bar.ohlcv())
+    }
+
+    /// Bars received from the source, ordered by symbol
+    pub fn traded_bars(&self) -> Vec<&Ohlcv> {
+        self.bars.values().filter_map(|bar| match *bar {
+            SliceBar::Traded(ref ohlcv) => Some(ohlcv),
+            _ => None
+        }).collect()
+    }
+
+    pub fn missing_symbols(&self) -> Vec<&SymbolId> {
+        self.bars.iter()
+            .filter(|&(_, bar)| *bar == SliceBar::Missing)
+            .map(|(symbol_id, _)| symbol_id)
+            .collect()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.bars.values().all(|bar| *bar != SliceBar::Missing)
+    }
+
+}
+
+/// Group an ohlcv stream sorted by datetime into time slices
+///
+/// Every slice contains an entry for each symbol of the universe, which is made of the symbols
+/// given with `set_symbols` and of every symbol seen so far in the stream.
+pub struct TimeSliceAligner<I: Iterator<Item=Ohlcv>> {
+    ohlcv: Peekable<I>,
+    symbols: Vec<SymbolId>,
+    missing_bar_policy: MissingBarPolicy,
+    last_bars: HashMap<SymbolId, Ohlcv>
+}
+
+impl<I: Iterator<Item=Ohlcv>> TimeSliceAligner<I> {
+
+    pub fn new(ohlcv: I) -> TimeSliceAligner<I> {
+        TimeSliceAligner {
+            ohlcv: ohlcv.peekable(),
+            symbols: vec![],
+            missing_bar_policy: MissingBarPolicy::Mark,
+            last_bars: HashMap::new()
+        }
+    }
+
+    pub fn symbols(&self) -> &Vec<SymbolId> {
+        &self.symbols
+    }
+
+    pub fn set_symbols(mut self, value: Vec<SymbolId>) -> Self {
+        self.symbols = value;
+        self
+    }
+
+    pub fn missing_bar_policy(&self) -> &MissingBarPolicy {
+        &self.missing_bar_policy
+    }
+
+    pub fn set_missing_bar_policy(mut self, value: MissingBarPolicy) -> Self {
+        self.missing_bar_policy = value;
+        self
+    }
+
+    fn missing_bar(&self, symbol_id: &SymbolId, datetime: &DateTime<Utc>) -> SliceBar {
+        match (self.missing_bar_policy, self.last_bars.get(symbol_id)) {
+            (MissingBarPolicy::ForwardFill, Some(last_bar)) => SliceBar::ForwardFilled(
+                Ohlcv::new(
+                    symbol_id.clone(),
+                    *datetime,
+                    last_bar.close(),
+                    last_bar.close(),
+                    last_bar.close(),
+                    last_bar.close(),
+                    Quantity::zero()
+                )
+            ),
+            _ => SliceBar::Missing
+        }
+    }
+}
+
+impl<I: Iterator<Item=Ohlcv>> Iterator for TimeSliceAligner<I> {
+    type Item = TimeSlice;
+
+    fn next(&mut self) -> Option<TimeSlice> {
+        let datetime = match self.ohlcv.peek() {
+            Some(ohlcv) => *ohlcv.datetime(),
+            None => return None
+        };
+
+        let mut bars = BTreeMap::new();
+        while self.ohlcv.peek().map_or(false, |ohlcv| ohlcv.datetime() == &datetime) {
+            let ohlcv = self.ohlcv.next().unwrap();
+            if !self.symbols.contains(ohlcv.symbol_id()) {
+                self.symbols.push(ohlcv.symbol_id().clone());
+            }
+            bars.insert(ohlcv.symbol_id().clone(), SliceBar::Traded(ohlcv));
+        }
+
+        for symbol_id in &self.symbols {
+            if !bars.contains_key(symbol_id) {
+                let missing_bar = self.missing_bar(symbol_id, &datetime);
+                bars.insert(symbol_id.clone(), missing_bar);
+            }
+        }
+
+        for bar in bars.values() {
+            if let SliceBar::Traded(ref ohlcv) = *bar {
+                self.last_bars.insert(ohlcv.symbol_id().clone(), ohlcv.clone());
+            }
+        }
+
+        Some(TimeSlice::new(datetime, bars))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use self::chrono::prelude::TimeZone;
+
+    fn ohlcv() -> Vec<Ohlcv> {
+        vec![
+            Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), 1., 1., 1., 1., Quantity::new(0.)),
+            Ohlcv::new(SymbolId::from("usd/jpy"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 0), 2., 2., 2., 2., Quantity::new(1.)),
+            Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 1., 1.5, 1., 1.5, Quantity::new(3.))
+        ]
+    }
+
+    #[test]
+    fn align_mark_missing_bars() {
+        let time_slices = TimeSliceAligner::new(ohlcv().into_iter())
+            .set_symbols(vec![SymbolId::from("gbp/usd")])
+            .collect::<Vec<TimeSlice>>();
+        assert_eq!(time_slices.len(), 2);
+
+        assert_eq!(time_slices[0].datetime(), &Utc.ymd(2017, 12, 29).and_hms(12, 0, 0));
+        assert_eq!(time_slices[0].traded_bars(), vec![&ohlcv()[0], &ohlcv()[1]]);
+        assert_eq!(time_slices[0].missing_symbols(), vec![&SymbolId::from("gbp/usd")]);
+        assert!(!time_slices[0].is_complete());
+
+        assert_eq!(time_slices[1].traded_bars(), vec![&ohlcv()[2]]);
+        assert_eq!(
+            time_slices[1].missing_symbols(),
+            vec![&SymbolId::from("gbp/usd"), &SymbolId::from("usd/jpy")]
+        );
+    }
+
+    #[test]
+    fn align_forward_fill_missing_bars() {
+        let time_slices = TimeSliceAligner::new(ohlcv().into_iter())
+            .set_missing_bar_policy(MissingBarPolicy::ForwardFill)
+            .collect::<Vec<TimeSlice>>();
+        assert_eq!(time_slices.len(), 2);
+        assert!(time_slices[1].is_complete());
+        assert_eq!(
+            time_slices[1].bars().get(&SymbolId::from("usd/jpy")),
+            Some(&SliceBar::ForwardFilled(
+                Ohlcv::new(SymbolId::from("usd/jpy"), Utc.ymd(2017, 12, 29).and_hms(12, 0, 5), 2., 2., 2., 2., Quantity::new(0.))
+            ))
+        );
+        assert_eq!(time_slices[1].traded_bars(), vec![&ohlcv()[2]]);
+    }
+
+    #[test]
+    fn align_forward_fill_unknown_symbol() {
+        let time_slices = TimeSliceAligner::new(ohlcv().into_iter())
+            .set_symbols(vec![SymbolId::from("gbp/usd")])
+            .set_missing_bar_policy(MissingBarPolicy::ForwardFill)
+            .collect::<Vec<TimeSlice>>();
+        assert_eq!(time_slices[0].ohlcv(&SymbolId::from("gbp/usd")), None);
+        assert_eq!(time_slices[1].missing_symbols(), vec![&SymbolId::from("gbp/usd")]);
+    }
+}
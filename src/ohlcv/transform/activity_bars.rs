@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use ohlcv::Ohlcv;
+use ohlcv::transform::merge;
+use symbol::SymbolId;
+use quantity::Quantity;
+
+/// Activity to accumulate before a bar is complete
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum BarThreshold {
+    /// Number of input bars
+    Ticks(u32),
+    Volume(Quantity),
+    /// Traded value, estimated with the close of each input bar
+    Dollar(f64)
+}
+
+/// Transform a bar stream into tick, volume or dollar bars
+///
+/// Input bars are merged until the threshold is reached. The last bar of each symbol is dropped
+/// if its threshold is not reached at the end of the stream.
+pub struct ActivityBars<I: Iterator<Item=Ohlcv>> {
+    ohlcv: I,
+    threshold: BarThreshold,
+    building: HashMap<SymbolId, (Ohlcv, f64)>
+}
+
+impl<I: Iterator<Item=Ohlcv>> ActivityBars<I> {
+
+    pub fn new(ohlcv: I, threshold: BarThreshold) -> ActivityBars<I> {
+        ActivityBars {
+            ohlcv,
+            threshold,
+            building: HashMap::new()
+        }
+    }
+
+    pub fn threshold(&self) -> &BarThreshold {
+        &self.threshold
+    }
+
+}
+
+impl<I: Iterator<Item=Ohlcv>> Iterator for ActivityBars<I> {
+    type Item = Ohlcv;
+
+    fn next(&mut self) -> Option<Ohlcv> {
+        loop {
+            let ohlcv = self.ohlcv.next()?;
+            let activity = match self.threshold {
+                BarThreshold::Ticks(_) => 1.,
+                BarThreshold::Volume(_) => ohlcv.volume().value(),
+                BarThreshold::Dollar(_) => ohlcv.close() * ohlcv.volume().value()
+            };
+            let (bar, accumulated) = match self.building.remove(ohlcv.symbol_id()) {
+                Some((building, accumulated)) => (merge(&building, &ohlcv), accumulated + activity),
+                None => (ohlcv, activity)
+            };
+
+            let is_complete = match self.threshold {
+                BarThreshold::Ticks(ticks) => accumulated >= ticks as f64,
+                BarThreshold::Volume(volume) => accumulated >= volume.value(),
+                BarThreshold::Dollar(value) => accumulated >= value
+            };
+            if is_complete {
+                return Some(bar);
+            }
+            self.building.insert(bar.symbol_id().clone(), (bar, accumulated));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate chrono;
+    use super::*;
+    use self::chrono::prelude::{Utc, TimeZone};
+
+    fn ohlcv() -> Vec<Ohlcv> {
+        let symbol_id = SymbolId::from("eur/usd");
+        vec![
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 10., 11., 9., 10., Quantity::new(1.)),
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0), 10., 12., 10., 12., Quantity::new(2.)),
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 2, 0), 12., 12., 8., 9., Quantity::new(4.))
+        ]
+    }
+
+    #[test]
+    fn tick_bars() {
+        let bars = ActivityBars::new(ohlcv().into_iter(), BarThreshold::Ticks(2)).collect::<Vec<Ohlcv>>();
+        assert_eq!(
+            bars,
+            vec![Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0), 10., 12., 9., 12., Quantity::new(3.))]
+        );
+    }
+
+    #[test]
+    fn volume_bars() {
+        let bars = ActivityBars::new(ohlcv().into_iter(), BarThreshold::Volume(Quantity::new(4.))).collect::<Vec<Ohlcv>>();
+        assert_eq!(
+            bars,
+            vec![Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2016, 1, 3).and_hms(17, 2, 0), 10., 12., 8., 9., Quantity::new(7.))]
+        );
+    }
+
+    #[test]
+    fn dollar_bars() {
+        let bars = ActivityBars::new(ohlcv().into_iter(), BarThreshold::Dollar(30.)).collect::<Vec<Ohlcv>>();
+        assert_eq!(
+            bars,
+            vec![
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0), 10., 12., 9., 12., Quantity::new(3.)),
+                Ohlcv::new(SymbolId::from("eur/usd"), Utc.ymd(2016, 1, 3).and_hms(17, 2, 0), 12., 12., 8., 9., Quantity::new(4.))
+            ]
+        );
+    }
+}
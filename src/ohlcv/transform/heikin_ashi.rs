@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+use ohlcv::Ohlcv;
+use symbol::SymbolId;
+
+/// Transform a bar stream into Heikin-Ashi bars, one for each input bar
+pub struct HeikinAshi<I: Iterator<Item=Ohlcv>> {
+    ohlcv: I,
+    previous_bars: HashMap<SymbolId, Ohlcv>
+}
+
+impl<I: Iterator<Item=Ohlcv>> HeikinAshi<I> {
+
+    pub fn new(ohlcv: I) -> HeikinAshi<I> {
+        HeikinAshi {
+            ohlcv,
+            previous_bars: HashMap::new()
+        }
+    }
+
+}
+
+impl<I: Iterator<Item=Ohlcv>> Iterator for HeikinAshi<I> {
+    type Item = Ohlcv;
+
+    fn next(&mut self) -> Option<Ohlcv> {
+        let ohlcv = self.ohlcv.next()?;
+
+        let close = (ohlcv.open() + ohlcv.high() + ohlcv.low() + ohlcv.close()) / 4.;
+        let open = match self.previous_bars.get(ohlcv.symbol_id()) {
+            Some(previous) => (previous.open() + previous.close()) / 2.,
+            None => (ohlcv.open() + ohlcv.close()) / 2.
+        };
+        let heikin_ashi = Ohlcv::new(
+            ohlcv.symbol_id().clone(),
+            *ohlcv.datetime(),
+            open,
+            ohlcv.high().max(open).max(close),
+            ohlcv.low().min(open).min(close),
+            close,
+            ohlcv.volume()
+        );
+
+        self.previous_bars.insert(ohlcv.symbol_id().clone(), heikin_ashi.clone());
+        Some(heikin_ashi)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate chrono;
+    use super::*;
+    use self::chrono::prelude::{Utc, TimeZone};
+    use quantity::Quantity;
+
+    #[test]
+    fn heikin_ashi() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let bars = HeikinAshi::new(vec![
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 1., 4., 1., 2., Quantity::new(3.)),
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0), 2., 3., 1., 2., Quantity::new(5.))
+        ].into_iter()).collect::<Vec<Ohlcv>>();
+        assert_eq!(
+            bars,
+            vec![
+                Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 1.5, 4., 1., 2., Quantity::new(3.)),
+                Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0), 1.75, 3., 1., 2., Quantity::new(5.))
+            ]
+        );
+    }
+}
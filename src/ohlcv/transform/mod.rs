@@ -0,0 +1,23 @@
+use ohlcv::Ohlcv;
+
+mod heikin_ashi;
+mod renko;
+mod range_bars;
+mod activity_bars;
+pub use self::heikin_ashi::HeikinAshi;
+pub use self::renko::{Renko, RenkoBrickSize};
+pub use self::range_bars::RangeBars;
+pub use self::activity_bars::{ActivityBars, BarThreshold};
+
+/// Merge a bar into the bar being built, the result is dated at the latest bar
+fn merge(building: &Ohlcv, ohlcv: &Ohlcv) -> Ohlcv {
+    Ohlcv::new(
+        ohlcv.symbol_id().clone(),
+        *ohlcv.datetime(),
+        building.open(),
+        building.high().max(ohlcv.high()),
+        building.low().min(ohlcv.low()),
+        ohlcv.close(),
+        building.volume() + ohlcv.volume()
+    )
+}
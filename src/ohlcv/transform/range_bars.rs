@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use ohlcv::Ohlcv;
+use ohlcv::transform::merge;
+use symbol::SymbolId;
+
+/// Transform a bar stream into bars whose high to low range reaches a constant size
+///
+/// Input bars are merged until their range reaches the target, so the bar completing the range
+/// can make it larger than the target. The last bar of each symbol is dropped if its range is
+/// not reached at the end of the stream.
+pub struct RangeBars<I: Iterator<Item=Ohlcv>> {
+    ohlcv: I,
+    range: f64,
+    building: HashMap<SymbolId, Ohlcv>
+}
+
+impl<I: Iterator<Item=Ohlcv>> RangeBars<I> {
+
+    pub fn new(ohlcv: I, range: f64) -> RangeBars<I> {
+        RangeBars {
+            ohlcv,
+            range,
+            building: HashMap::new()
+        }
+    }
+
+    pub fn range(&self) -> f64 {
+        self.range
+    }
+
+}
+
+impl<I: Iterator<Item=Ohlcv>> Iterator for RangeBars<I> {
+    type Item = Ohlcv;
+
+    fn next(&mut self) -> Option<Ohlcv> {
+        loop {
+            let ohlcv = self.ohlcv.next()?;
+            let bar = match self.building.remove(ohlcv.symbol_id()) {
+                Some(building) => merge(&building, &ohlcv),
+                None => ohlcv
+            };
+
+            if bar.high() - bar.low() >= self.range {
+                return Some(bar);
+            }
+            self.building.insert(bar.symbol_id().clone(), bar);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate chrono;
+    use super::*;
+    use self::chrono::prelude::{Utc, TimeZone};
+    use quantity::Quantity;
+
+    #[test]
+    fn range_bars() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let bars = RangeBars::new(vec![
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 10., 10.5, 10., 10.5, Quantity::new(1.)),
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0), 10.5, 11., 10.5, 11., Quantity::new(2.)),
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 2, 0), 11., 11.5, 11., 11.5, Quantity::new(3.))
+        ].into_iter(), 1.).collect::<Vec<Ohlcv>>();
+        assert_eq!(
+            bars,
+            vec![Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0), 10., 11., 10., 11., Quantity::new(3.))]
+        );
+    }
+}
@@ -0,0 +1,149 @@
+extern crate chrono;
+use std::collections::{HashMap, VecDeque};
+use self::chrono::Duration;
+use ohlcv::Ohlcv;
+use indicator::Atr;
+use quantity::Quantity;
+use symbol::SymbolId;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum RenkoBrickSize {
+    Fixed(f64),
+    /// Average true range over the given number of bars, no brick is built before it is known
+    Atr(usize)
+}
+
+#[derive(Clone, Debug)]
+struct RenkoState {
+    atr: Option<Atr>,
+    bottom: f64,
+    top: f64
+}
+
+/// Transform a bar stream into Renko bricks built on close prices
+///
+/// A brick is added when the close moves one brick size beyond the top or the bottom of the
+/// previous brick, so a reversal needs a move of two brick sizes. A bar can build several bricks,
+/// the first one gets its datetime and its volume, and each of the next ones is dated one
+/// nanosecond after the previous one so that the bricks stay in distinct time slices.
+pub struct Renko<I: Iterator<Item=Ohlcv>> {
+    ohlcv: I,
+    brick_size: RenkoBrickSize,
+    states: HashMap<SymbolId, RenkoState>,
+    bricks: VecDeque<Ohlcv>
+}
+
+impl<I: Iterator<Item=Ohlcv>> Renko<I> {
+
+    pub fn new(ohlcv: I, brick_size: RenkoBrickSize) -> Renko<I> {
+        Renko {
+            ohlcv,
+            brick_size,
+            states: HashMap::new(),
+            bricks: VecDeque::new()
+        }
+    }
+
+    pub fn brick_size(&self) -> &RenkoBrickSize {
+        &self.brick_size
+    }
+
+    fn build_bricks(&mut self, ohlcv: &Ohlcv) {
+        let brick_size = self.brick_size;
+        let state = self.states.entry(ohlcv.symbol_id().clone()).or_insert(RenkoState {
+            atr: match brick_size {
+                RenkoBrickSize::Atr(period) => Some(Atr::new(period)),
+                RenkoBrickSize::Fixed(_) => None
+            },
+            bottom: ohlcv.close(),
+            top: ohlcv.close()
+        });
+
+        let size = match (brick_size, state.atr.as_mut()) {
+            (RenkoBrickSize::Fixed(size), _) => size,
+            (RenkoBrickSize::Atr(_), Some(atr)) => match atr.update(ohlcv) {
+                Some(size) => size,
+                None => return
+            },
+            (RenkoBrickSize::Atr(_), None) => return
+        };
+        if size <= 0. {
+            return;
+        }
+
+        let mut volume = ohlcv.volume();
+        let mut datetime = *ohlcv.datetime();
+        while ohlcv.close() >= state.top + size || ohlcv.close() <= state.bottom - size {
+            let (open, close) = if ohlcv.close() >= state.top + size {
+                (state.top, state.top + size)
+            }
+            else {
+                (state.bottom, state.bottom - size)
+            };
+            self.bricks.push_back(Ohlcv::new(
+                ohlcv.symbol_id().clone(),
+                datetime,
+                open,
+                open.max(close),
+                open.min(close),
+                close,
+                volume
+            ));
+            volume = Quantity::zero();
+            datetime += Duration::nanoseconds(1);
+            state.bottom = open.min(close);
+            state.top = open.max(close);
+        }
+    }
+}
+
+impl<I: Iterator<Item=Ohlcv>> Iterator for Renko<I> {
+    type Item = Ohlcv;
+
+    fn next(&mut self) -> Option<Ohlcv> {
+        while self.bricks.is_empty() {
+            let ohlcv = self.ohlcv.next()?;
+            self.build_bricks(&ohlcv);
+        }
+        self.bricks.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use self::chrono::prelude::{Utc, TimeZone};
+
+    #[test]
+    fn fixed_brick_size() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let bricks = Renko::new(vec![
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 10., 10., 10., 10., Quantity::new(1.)),
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0), 10., 12.5, 10., 12.5, Quantity::new(2.)),
+            // not enough to reverse
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 2, 0), 12.5, 12.5, 11., 11., Quantity::new(3.)),
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 3, 0), 11., 11., 10., 10., Quantity::new(4.))
+        ].into_iter(), RenkoBrickSize::Fixed(1.)).collect::<Vec<Ohlcv>>();
+        assert_eq!(
+            bricks,
+            vec![
+                Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0), 10., 11., 10., 11., Quantity::new(2.)),
+                Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms_nano(17, 1, 0, 1), 11., 12., 11., 12., Quantity::new(0.)),
+                Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 3, 0), 11., 11., 10., 10., Quantity::new(4.))
+            ]
+        );
+    }
+
+    #[test]
+    fn atr_brick_size() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let bricks = Renko::new(vec![
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), 10., 12., 10., 10., Quantity::new(1.)),
+            Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0), 10., 12., 10., 12., Quantity::new(2.))
+        ].into_iter(), RenkoBrickSize::Atr(2)).collect::<Vec<Ohlcv>>();
+        assert_eq!(
+            bricks,
+            vec![Ohlcv::new(symbol_id.clone(), Utc.ymd(2016, 1, 3).and_hms(17, 1, 0), 10., 12., 10., 12., Quantity::new(2.))]
+        );
+    }
+}
@@ -0,0 +1,319 @@
+extern crate chrono;
+use std::cell::RefCell;
+use std::rc::Rc;
+use self::chrono::Duration;
+use self::chrono::prelude::{DateTime, Utc};
+use direction::Direction;
+use order::{Order, OrderKind, OrderStatus, OrderRequest};
+use order::policy::SimpleOrderPolicy;
+use price::Price;
+use signal::Signal;
+use signal::detector::{DetectSignal, DetectSignalError, Once};
+use strategy::Strategy;
+
+/// Distance of a bracket leg from the fill price of the entry order
+#[derive(Clone, PartialEq, Debug)]
+pub enum BracketDistance {
+    Amount(Price),
+    /// Percent of the fill price
+    Percentage(f64)
+}
+
+impl BracketDistance {
+
+    /// Distance from the price, if the percentage gives a valid one
+    fn amount(&self, price: Price) -> Option<Price> {
+        match *self {
+            BracketDistance::Amount(amount) => Some(amount),
+            BracketDistance::Percentage(percentage) => Price::try_new(price.value() * percentage / 100.).ok()
+        }
+    }
+
+}
+
+/// Stop loss, take profit and timeout exits of an entry order
+///
+/// Once the entry order is filled, each leg is an exit strategy sending a single order in the
+/// opposite direction, for the filled quantity. The legs share the entry order id as OCA group,
+/// so that the first filled leg cancels the others.
+///
+/// Clones of a bracket share its stop and target distances. Modifying them reprices the active
+/// stop loss and take profit orders of every entry order of the bracket, through modify
+/// requests sent by their exit strategies on the next time slice.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Bracket {
+    stop: Rc<RefCell<Option<BracketDistance>>>,
+    target: Rc<RefCell<Option<BracketDistance>>>,
+    timeout: Option<Duration>
+}
+
+impl Bracket {
+
+    pub fn new() -> Bracket {
+        Bracket {
+            stop: Rc::new(RefCell::new(None)),
+            target: Rc::new(RefCell::new(None)),
+            timeout: None
+        }
+    }
+
+    pub fn stop(&self) -> Option<BracketDistance> {
+        self.stop.borrow().clone()
+    }
+
+    pub fn set_stop(self, value: Option<BracketDistance>) -> Self {
+        self.modify_stop(value);
+        self
+    }
+
+    /// Change the stop distance, the active stop loss legs are repriced with it
+    pub fn modify_stop(&self, value: Option<BracketDistance>) {
+        *self.stop.borrow_mut() = value;
+    }
+
+    pub fn target(&self) -> Option<BracketDistance> {
+        self.target.borrow().clone()
+    }
+
+    pub fn set_target(self, value: Option<BracketDistance>) -> Self {
+        self.modify_target(value);
+        self
+    }
+
+    /// Change the target distance, the active take profit legs are repriced with it
+    pub fn modify_target(&self, value: Option<BracketDistance>) {
+        *self.target.borrow_mut() = value;
+    }
+
+    /// Duration after the entry fill after which the position is closed at market
+    pub fn timeout(&self) -> &Option<Duration> {
+        &self.timeout
+    }
+
+    pub fn set_timeout(mut self, value: Option<Duration>) -> Self {
+        self.timeout = value;
+        self
+    }
+
+    /// Price of the stop loss leg for a filled or partially filled entry order
+    pub fn stop_price(&self, entry_order: &Order) -> Option<Price> {
+        let price = *entry_order.average_execution()?.price();
+        let amount = self.stop.borrow().as_ref()?.amount(price)?;
+        Some(match *entry_order.direction() {
+            Direction::Long => price - amount,
+            Direction::Short => price + amount
+        })
+    }
+
+    /// Price of the take profit leg for a filled or partially filled entry order
+    pub fn target_price(&self, entry_order: &Order) -> Option<Price> {
+        let price = *entry_order.average_execution()?.price();
+        let amount = self.target.borrow().as_ref()?.amount(price)?;
+        Some(match *entry_order.direction() {
+            Direction::Long => price + amount,
+            Direction::Short => price - amount
+        })
+    }
+
+    /// Exit strategies of the legs, to be returned by `Model::exit_strategies`, for the filled
+    /// quantity of the entry order
+    pub fn exit_strategies(&self, entry_order: &Order) -> Vec<Strategy> {
+        let execution = match entry_order.average_execution() {
+            Some(execution) => execution,
+            None => return vec![]
+        };
+        let strategy = |detector: Box<DetectSignal>, kind: OrderKind, active_after: Option<DateTime<Utc>>| Strategy::new(
+            detector,
+            Box::new(SimpleOrderPolicy::new(kind)
+                .set_quantity(*execution.quantity())
+                .set_oca(Some(entry_order.id().clone()))
+                .set_active_after(active_after)
+            )
+        );
+        let mut strategies = vec![];
+        if let Some(price) = self.stop_price(entry_order) {
+            strategies.push(strategy(Box::new(self.leg(Leg::Stop, entry_order)), OrderKind::StopOrder(price), None));
+        }
+        if let Some(price) = self.target_price(entry_order) {
+            strategies.push(strategy(Box::new(self.leg(Leg::Target, entry_order)), OrderKind::LimitOrder(price), None));
+        }
+        if let Some(timeout) = self.timeout {
+            strategies.push(strategy(
+                Box::new(Once::new(entry_order.symbol_id().clone(), exit_direction(entry_order.direction()))),
+                OrderKind::MarketOrder,
+                Some(*execution.datetime() + timeout)
+            ));
+        }
+        strategies
+    }
+
+    fn leg(&self, leg: Leg, entry_order: &Order) -> BracketLeg {
+        BracketLeg {
+            once: Once::new(entry_order.symbol_id().clone(), exit_direction(entry_order.direction())),
+            leg,
+            bracket: self.clone(),
+            entry_order: entry_order.clone(),
+            order: None
+        }
+    }
+
+}
+
+impl Default for Bracket {
+    fn default() -> Bracket {
+        Bracket::new()
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Leg {
+    Stop,
+    Target
+}
+
+/// Detector of a stop loss or take profit leg, signals once and then modifies the order of the
+/// leg when the distance of the leg in the bracket changes
+struct BracketLeg {
+    once: Once,
+    leg: Leg,
+    bracket: Bracket,
+    entry_order: Order,
+    order: Option<Order>
+}
+
+impl BracketLeg {
+
+    fn kind(&self) -> Option<OrderKind> {
+        match self.leg {
+            Leg::Stop => self.bracket.stop_price(&self.entry_order).map(OrderKind::StopOrder),
+            Leg::Target => self.bracket.target_price(&self.entry_order).map(OrderKind::LimitOrder)
+        }
+    }
+
+}
+
+impl DetectSignal for BracketLeg {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        self.once.detect_signal(datetime)
+    }
+
+    fn on_order_update(&mut self, order: &Order, status: &OrderStatus) {
+        if let OrderStatus::NotSent = *status {
+            self.order = Some(order.clone());
+        }
+    }
+
+    fn order_requests(&mut self, _datetime: &DateTime<Utc>) -> Vec<OrderRequest> {
+        let kind = match self.kind() {
+            Some(kind) => kind,
+            None => return vec![]
+        };
+        match self.order {
+            Some(ref mut order) if *order.kind() != kind => {
+                order.set_kind(kind.clone());
+                vec![OrderRequest::Modify(order.id().clone(), kind, order.quantity())]
+            },
+            _ => vec![]
+        }
+    }
+}
+
+fn exit_direction(direction: &Direction) -> Direction {
+    match *direction {
+        Direction::Long => Direction::Short,
+        Direction::Short => Direction::Long
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use self::chrono::prelude::{DateTime, Utc, TimeZone};
+    use execution::Execution;
+    use order::{OrderBuilder, OrderId, OrderStatus};
+    use quantity::Quantity;
+    use symbol::SymbolId;
+
+    fn entry_order(direction: Direction, datetime: DateTime<Utc>) -> Order {
+        let symbol_id = SymbolId::from("eur/usd");
+        OrderBuilder::unallocated(OrderKind::MarketOrder, symbol_id.clone(), direction)
+            .set_id(OrderId::from("entry order"))
+            .set_quantity(Quantity::new(2.))
+            .set_status(OrderStatus::Filled(Execution::new(symbol_id, Quantity::new(2.), Price::new(100.), datetime)))
+            .build().unwrap()
+    }
+
+    #[test]
+    fn leg_prices() {
+        let datetime = Utc.ymd(2016, 1, 3).and_hms(17, 0, 0);
+        let bracket = Bracket::new()
+            .set_stop(Some(BracketDistance::Amount(Price::new(2.))))
+            .set_target(Some(BracketDistance::Percentage(5.)));
+        let long_entry = entry_order(Direction::Long, datetime);
+        assert_eq!(bracket.stop_price(&long_entry), Some(Price::new(98.)));
+        assert_eq!(bracket.target_price(&long_entry), Some(Price::new(105.)));
+        let short_entry = entry_order(Direction::Short, datetime);
+        assert_eq!(bracket.stop_price(&short_entry), Some(Price::new(102.)));
+        assert_eq!(bracket.target_price(&short_entry), Some(Price::new(95.)));
+    }
+
+    #[test]
+    fn exit_strategies() {
+        let datetime = Utc.ymd(2016, 1, 3).and_hms(17, 0, 0);
+        let entry_order = entry_order(Direction::Long, datetime);
+        let bracket = Bracket::new()
+            .set_stop(Some(BracketDistance::Amount(Price::new(2.))))
+            .set_target(Some(BracketDistance::Amount(Price::new(3.))))
+            .set_timeout(Some(Duration::minutes(30)));
+        let orders = bracket.exit_strategies(&entry_order).iter_mut()
+            .map(|strategy| strategy.run(&datetime).unwrap().remove(0).1.set_id(OrderId::from("leg")).build().unwrap())
+            .collect::<Vec<Order>>();
+        let kinds = orders.iter().map(|order| order.kind().clone()).collect::<Vec<OrderKind>>();
+        assert_eq!(kinds, vec![
+            OrderKind::StopOrder(Price::new(98.)),
+            OrderKind::LimitOrder(Price::new(103.)),
+            OrderKind::MarketOrder
+        ]);
+        for order in orders.iter() {
+            assert_eq!(order.direction(), &Direction::Short);
+            assert_eq!(order.quantity(), Quantity::new(2.));
+            assert_eq!(order.oca(), &Some(entry_order.id().clone()));
+        }
+        assert_eq!(orders[2].active_after(), &Some(Utc.ymd(2016, 1, 3).and_hms(17, 30, 0)));
+    }
+
+    #[test]
+    fn exit_strategies_not_filled() {
+        let entry_order = OrderBuilder::unallocated(OrderKind::MarketOrder, SymbolId::from("eur/usd"), Direction::Long)
+            .set_id(OrderId::from("entry order"))
+            .build().unwrap();
+        let bracket = Bracket::new().set_stop(Some(BracketDistance::Amount(Price::new(2.))));
+        assert!(bracket.exit_strategies(&entry_order).is_empty());
+    }
+
+    #[test]
+    fn modify_legs() {
+        let datetime = Utc.ymd(2016, 1, 3).and_hms(17, 0, 0);
+        let entry_order = entry_order(Direction::Long, datetime);
+        let bracket = Bracket::new()
+            .set_stop(Some(BracketDistance::Amount(Price::new(2.))))
+            .set_target(Some(BracketDistance::Amount(Price::new(3.))));
+        let mut strategies = bracket.exit_strategies(&entry_order);
+        for (i, strategy) in strategies.iter_mut().enumerate() {
+            let order = strategy.run(&datetime).unwrap().remove(0).1.set_id(i.to_string()).build().unwrap();
+            strategy.on_order_update(&order, &OrderStatus::NotSent);
+        }
+        let mut order_requests = || strategies.iter_mut()
+            .flat_map(|strategy| strategy.order_requests(&datetime))
+            .collect::<Vec<OrderRequest>>();
+        assert!(order_requests().is_empty());
+
+        // move the stop to breakeven
+        bracket.modify_stop(Some(BracketDistance::Amount(Price::zero())));
+        assert_eq!(
+            order_requests(),
+            vec![OrderRequest::Modify(OrderId::from("0"), OrderKind::StopOrder(Price::new(100.)), Quantity::new(2.))]
+        );
+        assert!(order_requests().is_empty());
+    }
+}
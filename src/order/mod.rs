@@ -7,6 +7,11 @@ extern crate snowflake;
 mod order_id;
 mod order_id_generator;
 mod order_status;
+mod bracket;
+mod time_in_force;
+mod validator;
+mod order_request;
+mod order_event;
 
 pub mod policy;
 
@@ -14,15 +19,44 @@ use self::chrono::prelude::{DateTime, Utc};
 use direction::Direction;
 use symbol::SymbolId;
 use execution::Execution;
+use quantity::Quantity;
+use price::Price;
+use signal::SignalMetadata;
+use strategy::StrategyId;
 pub use self::order_id::OrderId;
 pub use self::order_id_generator::{UUIDOrderIdGenerator, GenerateOrderId};
-pub use self::order_status::{OrderStatus, CancellationReason};
+pub use self::order_status::{OrderStatus, CancellationReason, RejectionReason};
+pub use self::bracket::{Bracket, BracketDistance};
+pub use self::time_in_force::TimeInForce;
+pub use self::validator::{OrderValidator, SymbolRules};
+pub use self::order_request::OrderRequest;
+pub use self::order_event::{OrderEvent, OrderEventKind};
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub enum OrderKind {
     MarketOrder,
-    LimitOrder(f64),
-    StopOrder(f64)
+    LimitOrder(Price),
+    StopOrder(Price),
+    /// Limit order at the second price, sent once the first price is reached
+    StopLimitOrder(Price, Price),
+    /// Stop order following the best price since the order became active
+    TrailingStopOrder(TrailingDistance),
+    /// Market order executed at the open of the first bar of a day
+    MarketOnOpen,
+    /// Market order executed at the close of the session, or of the last bar of a day
+    MarketOnClose,
+    /// Market on close order executed only when the close is at the limit or better
+    LimitOnClose(Price)
+}
+
+/// Distance between a trailing stop and the best price
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum TrailingDistance {
+    Amount(f64),
+    /// Percent of the best price
+    Percentage(f64),
+    /// Multiple of the average true range over a period
+    Atr(usize, f64)
 }
 
 pub type OcaGroup = String;
@@ -32,12 +66,17 @@ pub struct Order {
     id: OrderId,
     symbol_id: SymbolId,
     direction: Direction,
-    quantity: u32,
+    quantity: Quantity,
     status: OrderStatus,
     kind: OrderKind,
     oca: Option<OcaGroup>,
     active_until: Option<DateTime<Utc>>,
     active_after: Option<DateTime<Utc>>,
+    time_in_force: TimeInForce,
+    signal: Option<SignalMetadata>,
+    strategy_id: Option<StrategyId>,
+    executions: Vec<Execution>,
+    events: Vec<OrderEvent>
 }
 
 impl Order {
@@ -54,10 +93,14 @@ impl Order {
         &self.direction
     }
 
-    pub fn quantity(&self) -> u32 {
+    pub fn quantity(&self) -> Quantity {
         self.quantity
     }
 
+    pub fn set_quantity(&mut self, value: Quantity) {
+        self.quantity = value
+    }
+
     pub fn status(&self) -> &OrderStatus {
         &self.status
     }
@@ -70,6 +113,10 @@ impl Order {
         &self.kind
     }
 
+    pub fn set_kind(&mut self, value: OrderKind) {
+        self.kind = value
+    }
+
     pub fn oca(&self) -> &Option<OcaGroup> {
         &self.oca
     }
@@ -82,6 +129,15 @@ impl Order {
         &self.active_after
     }
 
+    pub fn time_in_force(&self) -> &TimeInForce {
+        &self.time_in_force
+    }
+
+    /// Metadata of the signal the order was created from
+    pub fn signal(&self) -> &Option<SignalMetadata> {
+        &self.signal
+    }
+
     pub fn execution(&self) -> Option<&Execution> {
         match *self.status() {
             OrderStatus::Filled(ref execution) => Some(execution),
@@ -89,6 +145,38 @@ impl Order {
         }
     }
 
+    /// Executions of the parts of the order filled so far
+    pub fn executions(&self) -> &Vec<Execution> {
+        &self.executions
+    }
+
+    pub fn add_execution(&mut self, execution: Execution) {
+        self.executions.push(execution)
+    }
+
+    pub fn filled_quantity(&self) -> Quantity {
+        self.executions.iter().map(|execution| *execution.quantity()).sum()
+    }
+
+    /// Average of the executions of the order, or the execution of its filled status
+    pub fn average_execution(&self) -> Option<Execution> {
+        Execution::average(&self.executions).or_else(|| self.execution().cloned())
+    }
+
+    /// Strategy which created the order
+    pub fn strategy_id(&self) -> &Option<StrategyId> {
+        &self.strategy_id
+    }
+
+    /// Events of the order, in the order they were recorded
+    pub fn events(&self) -> &Vec<OrderEvent> {
+        &self.events
+    }
+
+    pub fn add_event(&mut self, event: OrderEvent) {
+        self.events.push(event)
+    }
+
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -96,12 +184,15 @@ pub struct OrderBuilder {
     id: Option<OrderId>,
     symbol_id: SymbolId,
     direction: Direction,
-    quantity: u32,
+    quantity: Quantity,
     status: OrderStatus,
     kind: OrderKind,
     oca: Option<OcaGroup>,
     active_until: Option<DateTime<Utc>>,
     active_after: Option<DateTime<Utc>>,
+    time_in_force: TimeInForce,
+    signal: Option<SignalMetadata>,
+    strategy_id: Option<StrategyId>
 }
 
 impl OrderBuilder {
@@ -113,12 +204,15 @@ impl OrderBuilder {
             id: None,
             symbol_id: symbol_id,
             direction: direction,
-            quantity: 0,
+            quantity: Quantity::zero(),
             status: OrderStatus::NotSent,
             kind: kind,
             oca: None,
             active_until: None,
-            active_after: None
+            active_after: None,
+            time_in_force: TimeInForce::default(),
+            signal: None,
+            strategy_id: None
         }
     }
 
@@ -170,11 +264,11 @@ impl OrderBuilder {
         self
     }
 
-    pub fn quantity(&self) -> u32 {
+    pub fn quantity(&self) -> Quantity {
         self.quantity
     }
 
-    pub fn set_quantity(mut self, value: u32) -> Self {
+    pub fn set_quantity(mut self, value: Quantity) -> Self {
         self.quantity = value;
         self
     }
@@ -188,7 +282,39 @@ impl OrderBuilder {
         self
     }
 
+    pub fn time_in_force(&self) -> &TimeInForce {
+        &self.time_in_force
+    }
+
+    pub fn set_time_in_force(mut self, value: TimeInForce) -> Self {
+        self.time_in_force = value;
+        self
+    }
+
+    pub fn signal(&self) -> &Option<SignalMetadata> {
+        &self.signal
+    }
+
+    pub fn set_signal(mut self, value: Option<SignalMetadata>) -> Self {
+        self.signal = value;
+        self
+    }
+
+    pub fn strategy_id(&self) -> &Option<StrategyId> {
+        &self.strategy_id
+    }
+
+    pub fn set_strategy_id(mut self, value: Option<StrategyId>) -> Self {
+        self.strategy_id = value;
+        self
+    }
+
+    /// Build the order, with the execution of its status if it is filled
     pub fn build(self) -> Result<Order, BuildOrderError> {
+        let executions = match self.status {
+            OrderStatus::Filled(ref execution) | OrderStatus::PartiallyFilled(ref execution) => vec![execution.clone()],
+            _ => vec![]
+        };
         Ok(
             Order {
                 id: self.id.ok_or(BuildOrderError::UndefinedId)?,
@@ -199,7 +325,12 @@ impl OrderBuilder {
                 kind: self.kind,
                 oca: self.oca,
                 active_until: self.active_until,
-                active_after: self.active_after
+                active_after: self.active_after,
+                time_in_force: self.time_in_force,
+                signal: self.signal,
+                strategy_id: self.strategy_id,
+                executions,
+                events: vec![]
             }
         )
     }
@@ -0,0 +1,60 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc};
+use order::{Order, OrderId, OrderKind, CancellationReason, RejectionReason};
+use execution::Execution;
+use quantity::Quantity;
+use strategy::StrategyId;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum OrderEventKind {
+    Created,
+    /// The order reached the market, after the order latency
+    Sent,
+    /// The stop price of a stop-limit order was reached
+    Triggered,
+    PartiallyFilled(Execution),
+    /// The last part of the order was executed
+    Filled(Execution),
+    Cancelled(CancellationReason),
+    Rejected(RejectionReason),
+    /// The kind and quantity of the order were replaced on request of its strategy
+    Modified(OrderKind, Quantity),
+    /// A modify request of its strategy failed a pre-trade check, the order is left unchanged
+    ModifyRejected(RejectionReason)
+}
+
+/// Event in the life of an order, with the strategy which created the order
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct OrderEvent {
+    datetime: DateTime<Utc>,
+    order_id: OrderId,
+    strategy_id: Option<StrategyId>,
+    kind: OrderEventKind
+}
+
+impl OrderEvent {
+    pub fn new(datetime: DateTime<Utc>, order: &Order, kind: OrderEventKind) -> OrderEvent {
+        OrderEvent {
+            datetime,
+            order_id: order.id().clone(),
+            strategy_id: *order.strategy_id(),
+            kind
+        }
+    }
+
+    pub fn datetime(&self) -> &DateTime<Utc> {
+        &self.datetime
+    }
+
+    pub fn order_id(&self) -> &OrderId {
+        &self.order_id
+    }
+
+    pub fn strategy_id(&self) -> &Option<StrategyId> {
+        &self.strategy_id
+    }
+
+    pub fn kind(&self) -> &OrderEventKind {
+        &self.kind
+    }
+}
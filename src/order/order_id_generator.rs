@@ -7,17 +7,19 @@ use signal::Signal;
 
 pub trait GenerateOrderId {
 
+    /// Get the id of an order created by a strategy run, where `index` is the position of the
+    /// order among the orders of this run
     fn get_id(&self, strategy_id: StrategyId, signal: &Signal,
-                  order_builder: &OrderBuilder) -> OrderId;
+                  order_builder: &OrderBuilder, index: usize) -> OrderId;
 
 }
 
 impl<M: GenerateOrderId + ?Sized> GenerateOrderId for Box<M> {
 
     fn get_id(&self, strategy_id: StrategyId, signal: &Signal,
-              order_builder: &OrderBuilder) -> OrderId
+              order_builder: &OrderBuilder, index: usize) -> OrderId
     {
-        (**self).get_id(strategy_id, signal, order_builder)
+        (**self).get_id(strategy_id, signal, order_builder, index)
     }
 
 }
@@ -30,12 +32,12 @@ impl UUIDOrderIdGenerator {
 
 impl GenerateOrderId for UUIDOrderIdGenerator {
 
-    fn get_id(&self, strategy_id: StrategyId, signal: &Signal, _order_builder: &OrderBuilder)
-        -> OrderId
+    fn get_id(&self, strategy_id: StrategyId, signal: &Signal, _order_builder: &OrderBuilder,
+              index: usize) -> OrderId
     {
         Uuid::new_v5(
             &uuid::NAMESPACE_OID,
-            format!("{} - {}", strategy_id, signal.datetime()).as_str()
+            format!("{} - {} - {}", strategy_id, signal.datetime(), index).as_str()
         ).hyphenated().to_string()
     }
 
@@ -0,0 +1,19 @@
+use order::{OrderId, OrderKind};
+use quantity::Quantity;
+
+/// Request of a strategy about one of its active orders, processed by the market simulation
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum OrderRequest {
+    Cancel(OrderId),
+    /// Cancel and replace the order with a new kind and quantity, such as a stop moved to
+    /// breakeven; the quantity includes the part already filled
+    Modify(OrderId, OrderKind, Quantity)
+}
+
+impl OrderRequest {
+    pub fn order_id(&self) -> &OrderId {
+        match *self {
+            OrderRequest::Cancel(ref order_id) | OrderRequest::Modify(ref order_id, _, _) => order_id
+        }
+    }
+}
@@ -4,12 +4,44 @@ use execution::Execution;
 pub enum OrderStatus {
     NotSent,
     Sent,
+    /// Part of the quantity has been executed, with the execution of the last part
+    PartiallyFilled(Execution),
     Filled(Execution),
-    Cancelled(CancellationReason)
+    Cancelled(CancellationReason),
+    /// Not sent because it failed a pre-trade check
+    Rejected(RejectionReason)
 }
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum CancellationReason {
     FilledOca,
-    OutdatedOrder
+    OutdatedOrder,
+    /// Day order still active at the end of its day
+    DayEnded,
+    /// Good till date order still active at its date
+    DateReached,
+    /// Immediate or cancel order not filled on its first bar
+    NotFilledImmediately,
+    /// Fill or kill order not entirely filled on its first bar
+    NotFilledEntirely,
+    /// Cancel request of the strategy of the order
+    Requested
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum RejectionReason {
+    /// Zero or negative quantity
+    InvalidQuantity,
+    /// Not enough cash for the value of a long order
+    InsufficientBuyingPower,
+    /// Short order exceeding the long position of a symbol which can't be shorted
+    ShortingNotAllowed,
+    /// Price which is not a multiple of the tick size of the symbol
+    OffTickPrice,
+    /// Quantity which is not a multiple of the lot size of the symbol
+    OddLot,
+    UnknownSymbol,
+    /// Long order without price, on a symbol without bar to value it, when buying power is
+    /// checked
+    NoPrice
 }
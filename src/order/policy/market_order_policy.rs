@@ -11,7 +11,7 @@ impl MarketOrderPolicy {
 }
 
 impl OrderPolicy for MarketOrderPolicy {
-    fn create_order(&self, signal: &Signal) -> Result<OrderBuilder, OrderPolicyError> {
+    fn create_order(&mut self, signal: &Signal) -> Result<OrderBuilder, OrderPolicyError> {
         Ok(
             OrderBuilder::unallocated(
                 OrderKind::MarketOrder,
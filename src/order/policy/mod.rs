@@ -1,4 +1,5 @@
 use signal::Signal;
+use ohlcv::TimeSlice;
 use order::OrderBuilder;
 
 mod market_order_policy;
@@ -12,6 +13,22 @@ pub enum OrderPolicyError {
 }
 
 pub trait OrderPolicy {
-    fn create_order(&self, signal: &Signal) -> Result<OrderBuilder, OrderPolicyError>;
+    fn create_order(&mut self, signal: &Signal) -> Result<OrderBuilder, OrderPolicyError>;
+
+    /// Create all orders for a signal, such as the orders of a bracket or of scaling in; by
+    /// default the single order of `create_order`
+    fn create_orders(&mut self, signal: &Signal) -> Result<Vec<OrderBuilder>, OrderPolicyError> {
+        Ok(vec![self.create_order(signal)?])
+    }
+
+    /// Called once before the first time slice of the backtest, or when an exit strategy is
+    /// created
+    fn on_start(&mut self) {}
+
+    /// Called with every time slice of the backtest, before orders are created at its datetime
+    fn on_bar(&mut self, _time_slice: &TimeSlice) {}
+
+    /// Called once after the last time slice of the backtest
+    fn on_end(&mut self) {}
 }
 
@@ -1,26 +1,40 @@
 extern crate chrono;
 use self::chrono::prelude::{DateTime, Utc};
 use signal::Signal;
-use order::{OrderKind, OrderBuilder, OcaGroup};
+use order::{OrderKind, OrderBuilder, OcaGroup, TimeInForce};
+use quantity::Quantity;
 use order::policy::{OrderPolicy, OrderPolicyError};
 
 pub struct SimpleOrderPolicy {
     order_kind: OrderKind,
+    quantity: Quantity,
     oca: Option<OcaGroup>,
     active_until: Option<DateTime<Utc>>,
-    active_after: Option<DateTime<Utc>>
+    active_after: Option<DateTime<Utc>>,
+    time_in_force: TimeInForce
 }
 
 impl SimpleOrderPolicy {
     pub fn new(order_kind: OrderKind) -> Self {
         SimpleOrderPolicy {
             order_kind: order_kind,
+            quantity: Quantity::zero(),
             oca: None,
             active_until: None,
-            active_after: None
+            active_after: None,
+            time_in_force: TimeInForce::default()
         }
     }
 
+    pub fn quantity(&self) -> Quantity {
+        self.quantity
+    }
+
+    pub fn set_quantity(mut self, value: Quantity) -> Self {
+        self.quantity = value;
+        self
+    }
+
     pub fn oca(&self) -> &Option<OcaGroup> {
         &self.oca
     }
@@ -47,19 +61,30 @@ impl SimpleOrderPolicy {
         self.active_after = value;
         self
     }
+
+    pub fn time_in_force(&self) -> &TimeInForce {
+        &self.time_in_force
+    }
+
+    pub fn set_time_in_force(mut self, value: TimeInForce) -> Self {
+        self.time_in_force = value;
+        self
+    }
 }
 
 impl OrderPolicy for SimpleOrderPolicy {
-    fn create_order(&self, signal: &Signal) -> Result<OrderBuilder, OrderPolicyError> {
+    fn create_order(&mut self, signal: &Signal) -> Result<OrderBuilder, OrderPolicyError> {
         Ok(
             OrderBuilder::unallocated(
                 self.order_kind.clone(),
                 signal.symbol_id().clone(),
                 signal.direction().clone()
             )
+                .set_quantity(self.quantity())
                 .set_active_after(self.active_after().clone())
                 .set_active_until(self.active_until().clone())
                 .set_oca(self.oca().clone())
+                .set_time_in_force(self.time_in_force().clone())
         )
     }
 }
@@ -0,0 +1,17 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc};
+
+/// How long an order stays active before it is cancelled
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+pub enum TimeInForce {
+    /// Cancelled at the end of the day it is sent to the market
+    Day,
+    #[default]
+    GoodTillCancelled,
+    /// Cancelled if not filled on the first bar it can be executed
+    ImmediateOrCancel,
+    /// Cancelled if its whole quantity is not filled on the first bar it can be executed
+    FillOrKill,
+    /// Cancelled at the given datetime
+    GoodTillDate(DateTime<Utc>)
+}
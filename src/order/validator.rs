@@ -0,0 +1,443 @@
+use std::collections::HashMap;
+use direction::Direction;
+use ohlcv::TimeSlice;
+use order::{Order, OrderId, OrderKind, OrderStatus, OrderRequest, RejectionReason};
+use portfolio::Portfolio;
+use price::{Price, Money};
+use quantity::Quantity;
+use symbol::SymbolId;
+
+/// Trading rules of a symbol
+#[derive(Clone, PartialEq, Debug)]
+pub struct SymbolRules {
+    tick_size: Option<f64>,
+    lot_size: Option<f64>,
+    shortable: bool
+}
+
+impl SymbolRules {
+
+    pub fn new() -> SymbolRules {
+        SymbolRules {
+            tick_size: None,
+            lot_size: None,
+            shortable: true
+        }
+    }
+
+    /// Minimum price increment
+    pub fn tick_size(&self) -> Option<f64> {
+        self.tick_size
+    }
+
+    pub fn set_tick_size(mut self, value: Option<f64>) -> Self {
+        self.tick_size = value;
+        self
+    }
+
+    /// Minimum quantity increment
+    pub fn lot_size(&self) -> Option<f64> {
+        self.lot_size
+    }
+
+    pub fn set_lot_size(mut self, value: Option<f64>) -> Self {
+        self.lot_size = value;
+        self
+    }
+
+    pub fn shortable(&self) -> bool {
+        self.shortable
+    }
+
+    pub fn set_shortable(mut self, value: bool) -> Self {
+        self.shortable = value;
+        self
+    }
+
+}
+
+impl Default for SymbolRules {
+    fn default() -> SymbolRules {
+        SymbolRules::new()
+    }
+}
+
+/// Pre-trade checks of the orders created by strategies, an order failing a check is rejected
+/// instead of being sent
+///
+/// Symbols are only checked when their rules are given, then orders of other symbols are
+/// rejected. Buying power is only checked when enabled, as the value of long orders against the
+/// cash of the portfolio which isn't reserved by its active long orders; a long order without
+/// price on a symbol without bar in the time slice can't be valued and is rejected. Short orders
+/// on symbols which can't be shorted are checked against the long position left once the active
+/// short orders are filled.
+pub struct OrderValidator {
+    symbols: Option<HashMap<SymbolId, SymbolRules>>,
+    check_buying_power: bool
+}
+
+impl OrderValidator {
+
+    pub fn new() -> OrderValidator {
+        OrderValidator {
+            symbols: None,
+            check_buying_power: false
+        }
+    }
+
+    pub fn symbols(&self) -> &Option<HashMap<SymbolId, SymbolRules>> {
+        &self.symbols
+    }
+
+    pub fn set_symbols(mut self, value: Option<HashMap<SymbolId, SymbolRules>>) -> Self {
+        self.symbols = value;
+        self
+    }
+
+    pub fn check_buying_power(&self) -> bool {
+        self.check_buying_power
+    }
+
+    pub fn set_check_buying_power(mut self, value: bool) -> Self {
+        self.check_buying_power = value;
+        self
+    }
+
+    /// Set the rejected status of the orders failing a check, the value and the quantity of each
+    /// accepted order are no longer available to the next orders
+    pub fn validate_orders(&self, orders: Vec<Order>, portfolio: &Portfolio, time_slice: &TimeSlice) -> Vec<Order> {
+        let mut reserved = Reserved::new(portfolio, time_slice, None);
+        orders.into_iter().map(|mut order| {
+            match self.validate(&order, portfolio, time_slice, &reserved) {
+                Ok(value) => reserved.add(&order, value),
+                Err(reason) => order.set_status(OrderStatus::Rejected(reason))
+            }
+            order
+        }).collect()
+    }
+
+    /// Check the orders modify requests would replace the active orders with, returns the
+    /// accepted requests and the rejected ones
+    ///
+    /// Cancel requests and requests about orders which are no longer active are accepted.
+    pub fn validate_requests(&self, requests: Vec<OrderRequest>, portfolio: &Portfolio, time_slice: &TimeSlice)
+        -> (Vec<OrderRequest>, Vec<(OrderRequest, RejectionReason)>)
+    {
+        let mut accepted = vec![];
+        let mut rejected = vec![];
+        for request in requests {
+            let result = match request {
+                OrderRequest::Modify(ref order_id, ref kind, quantity) => match portfolio.active_orders().get(order_id) {
+                    Some(order) => {
+                        let mut modified_order = order.clone();
+                        modified_order.set_kind(kind.clone());
+                        modified_order.set_quantity(quantity);
+                        let reserved = Reserved::new(portfolio, time_slice, Some(order_id));
+                        self.validate(&modified_order, portfolio, time_slice, &reserved).map(|_| ())
+                    },
+                    None => Ok(())
+                },
+                OrderRequest::Cancel(_) => Ok(())
+            };
+            match result {
+                Ok(()) => accepted.push(request),
+                Err(reason) => rejected.push((request, reason))
+            }
+        }
+        (accepted, rejected)
+    }
+
+    /// Check an order, returns the cash it uses
+    fn validate(&self, order: &Order, portfolio: &Portfolio, time_slice: &TimeSlice, reserved: &Reserved)
+        -> Result<Money, RejectionReason>
+    {
+        if order.quantity() <= Quantity::zero() {
+            return Err(RejectionReason::InvalidQuantity);
+        }
+
+        let rules = match self.symbols {
+            Some(ref symbols) => Some(symbols.get(order.symbol_id()).ok_or(RejectionReason::UnknownSymbol)?),
+            None => None
+        };
+        if let Some(tick_size) = rules.and_then(|rules| rules.tick_size()) {
+            if order_prices(order.kind()).iter().any(|price| !is_on_tick(*price, tick_size)) {
+                return Err(RejectionReason::OffTickPrice);
+            }
+        }
+        if let Some(lot_size) = rules.and_then(|rules| rules.lot_size()) {
+            if (order.quantity().round_to_lot(lot_size) - order.quantity()).value().abs() > 1e-9 {
+                return Err(RejectionReason::OddLot);
+            }
+        }
+
+        match *order.direction() {
+            Direction::Short => {
+                let position = portfolio.positions().get(order.symbol_id()).cloned().unwrap_or_default();
+                let short_quantity = reserved.short_quantities.get(order.symbol_id()).cloned().unwrap_or_default();
+                if rules.map_or(false, |rules| !rules.shortable()) && remaining_quantity(order) > position - short_quantity {
+                    return Err(RejectionReason::ShortingNotAllowed);
+                }
+                Ok(Money::zero())
+            },
+            Direction::Long if self.check_buying_power => {
+                let value = order_value(order, time_slice).ok_or(RejectionReason::NoPrice)?;
+                if value > portfolio.cash() - reserved.cash {
+                    return Err(RejectionReason::InsufficientBuyingPower);
+                }
+                Ok(value)
+            },
+            Direction::Long => Ok(Money::zero())
+        }
+    }
+
+}
+
+impl Default for OrderValidator {
+    fn default() -> OrderValidator {
+        OrderValidator::new()
+    }
+}
+
+/// Cash and short quantities reserved by the active orders of the portfolio and by the orders
+/// accepted before
+struct Reserved {
+    cash: Money,
+    short_quantities: HashMap<SymbolId, Quantity>
+}
+
+impl Reserved {
+
+    /// Reserved by the active orders, but the excluded one, which are valued when a price is known
+    fn new(portfolio: &Portfolio, time_slice: &TimeSlice, excluded: Option<&OrderId>) -> Reserved {
+        let mut reserved = Reserved {
+            cash: Money::zero(),
+            short_quantities: HashMap::new()
+        };
+        for order in portfolio.active_orders().values().filter(|order| Some(order.id()) != excluded) {
+            reserved.add(order, order_value(order, time_slice).unwrap_or_default());
+        }
+        reserved
+    }
+
+    fn add(&mut self, order: &Order, value: Money) {
+        match *order.direction() {
+            Direction::Long => self.cash += value,
+            Direction::Short => {
+                *self.short_quantities.entry(order.symbol_id().clone()).or_default() += remaining_quantity(order);
+            }
+        }
+    }
+
+}
+
+/// Quantity of an order left to execute
+fn remaining_quantity(order: &Order) -> Quantity {
+    order.quantity() - order.filled_quantity()
+}
+
+/// Value of the quantity left to execute, priced orders are valued at their price and others at
+/// the close of the bar of their symbol
+fn order_value(order: &Order, time_slice: &TimeSlice) -> Option<Money> {
+    let price = order_prices(order.kind()).last().cloned()
+        .or_else(|| time_slice.ohlcv(order.symbol_id()).map(|ohlcv| Price::new(ohlcv.close())))?;
+    Some(price * remaining_quantity(order))
+}
+
+/// Prices set by an order kind, the execution price comes last
+fn order_prices(kind: &OrderKind) -> Vec<Price> {
+    match *kind {
+        OrderKind::LimitOrder(price) | OrderKind::StopOrder(price) | OrderKind::LimitOnClose(price) => vec![price],
+        OrderKind::StopLimitOrder(stop, limit) => vec![stop, limit],
+        _ => vec![]
+    }
+}
+
+fn is_on_tick(price: Price, tick_size: f64) -> bool {
+    let ticks = price.value() / tick_size;
+    (ticks - ticks.round()).abs() < 1e-6
+}
+
+#[cfg(test)]
+mod test {
+    extern crate chrono;
+    use super::*;
+    use std::collections::BTreeMap;
+    use self::chrono::prelude::{Utc, TimeZone};
+    use execution::Execution;
+    use ohlcv::{Ohlcv, SliceBar};
+    use order::{OrderBuilder, OrderId};
+
+    fn order(kind: OrderKind, symbol_id: &str, direction: Direction, quantity: f64) -> Order {
+        OrderBuilder::unallocated(kind, SymbolId::from(symbol_id), direction)
+            .set_id(OrderId::from(format!("{} {:?} {}", symbol_id, direction, quantity)))
+            .set_quantity(Quantity::new(quantity))
+            .build().unwrap()
+    }
+
+    fn time_slice() -> TimeSlice {
+        let datetime = Utc.ymd(2016, 1, 3).and_hms(17, 0, 0);
+        let symbol_id = SymbolId::from("eur/usd");
+        let mut bars = BTreeMap::new();
+        bars.insert(
+            symbol_id.clone(),
+            SliceBar::Traded(Ohlcv::new(symbol_id, datetime, 1.1, 1.2, 1., 1.1, Quantity::new(1.)))
+        );
+        TimeSlice::new(datetime, bars)
+    }
+
+    fn statuses(orders: Vec<Order>) -> Vec<OrderStatus> {
+        orders.iter().map(|order| order.status().clone()).collect()
+    }
+
+    #[test]
+    fn validate_quantity() {
+        let orders = OrderValidator::new().validate_orders(
+            vec![
+                order(OrderKind::MarketOrder, "eur/usd", Direction::Long, 1.),
+                order(OrderKind::MarketOrder, "eur/usd", Direction::Long, 0.),
+                order(OrderKind::MarketOrder, "eur/usd", Direction::Short, -1.)
+            ],
+            &Portfolio::new(),
+            &time_slice()
+        );
+        assert_eq!(statuses(orders), vec![
+            OrderStatus::NotSent,
+            OrderStatus::Rejected(RejectionReason::InvalidQuantity),
+            OrderStatus::Rejected(RejectionReason::InvalidQuantity)
+        ]);
+    }
+
+    #[test]
+    fn validate_buying_power() {
+        let validator = OrderValidator::new().set_check_buying_power(true);
+        let portfolio = Portfolio::new().set_cash(Money::new(300.));
+        let orders = validator.validate_orders(
+            vec![
+                // market order valued at the last close of 1.1
+                order(OrderKind::MarketOrder, "eur/usd", Direction::Long, 100.),
+                order(OrderKind::LimitOrder(Price::new(1.)), "eur/usd", Direction::Long, 200.),
+                order(OrderKind::LimitOrder(Price::new(1.)), "eur/usd", Direction::Long, 100.),
+                order(OrderKind::MarketOrder, "eur/usd", Direction::Short, 1000.)
+            ],
+            &portfolio,
+            &time_slice()
+        );
+        assert_eq!(statuses(orders), vec![
+            OrderStatus::NotSent,
+            OrderStatus::Rejected(RejectionReason::InsufficientBuyingPower),
+            OrderStatus::NotSent,
+            OrderStatus::NotSent
+        ]);
+    }
+
+    #[test]
+    fn validate_symbol_rules() {
+        let symbols = [
+            (SymbolId::from("eur/usd"), SymbolRules::new().set_tick_size(Some(0.0001)).set_lot_size(Some(0.01))),
+            (SymbolId::from("aapl"), SymbolRules::new().set_shortable(false))
+        ].iter().cloned().collect();
+        let validator = OrderValidator::new().set_symbols(Some(symbols));
+        let mut portfolio = Portfolio::new();
+        let long_order = OrderBuilder::unallocated(OrderKind::MarketOrder, SymbolId::from("aapl"), Direction::Long)
+            .set_id(OrderId::from("long order"))
+            .set_quantity(Quantity::new(10.))
+            .build().unwrap();
+        portfolio.add_orders(vec![long_order.clone()]);
+        portfolio.update_orders(&[(
+            long_order.id().clone(),
+            OrderStatus::Filled(Execution::new(SymbolId::from("aapl"), Quantity::new(10.), Price::new(1.), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0)))
+        )].iter().cloned().collect());
+
+        let orders = validator.validate_orders(
+            vec![
+                order(OrderKind::LimitOrder(Price::new(1.0871)), "eur/usd", Direction::Long, 1.),
+                order(OrderKind::StopLimitOrder(Price::new(1.0871), Price::new(1.08715)), "eur/usd", Direction::Long, 1.),
+                order(OrderKind::MarketOrder, "eur/usd", Direction::Long, 0.07),
+                order(OrderKind::MarketOrder, "eur/usd", Direction::Long, 0.075),
+                order(OrderKind::MarketOrder, "aapl", Direction::Short, 10.),
+                order(OrderKind::MarketOrder, "aapl", Direction::Short, 11.),
+                order(OrderKind::MarketOrder, "gbp/usd", Direction::Long, 1.)
+            ],
+            &portfolio,
+            &time_slice()
+        );
+        assert_eq!(statuses(orders), vec![
+            OrderStatus::NotSent,
+            OrderStatus::Rejected(RejectionReason::OffTickPrice),
+            OrderStatus::NotSent,
+            OrderStatus::Rejected(RejectionReason::OddLot),
+            OrderStatus::NotSent,
+            OrderStatus::Rejected(RejectionReason::ShortingNotAllowed),
+            OrderStatus::Rejected(RejectionReason::UnknownSymbol)
+        ]);
+    }
+
+    #[test]
+    fn validate_pending_orders() {
+        let symbols = [(SymbolId::from("aapl"), SymbolRules::new().set_shortable(false))].iter().cloned().collect();
+        let mut portfolio = Portfolio::new().set_cash(Money::new(300.));
+        let long_order = order(OrderKind::MarketOrder, "aapl", Direction::Long, 10.);
+        portfolio.add_orders(vec![
+            long_order.clone(),
+            order(OrderKind::MarketOrder, "aapl", Direction::Short, 6.),
+            order(OrderKind::LimitOrder(Price::new(1.)), "eur/usd", Direction::Long, 100.)
+        ]);
+        portfolio.update_orders(&[(
+            long_order.id().clone(),
+            OrderStatus::Filled(Execution::new(SymbolId::from("aapl"), Quantity::new(10.), Price::new(1.), Utc.ymd(2016, 1, 3).and_hms(17, 0, 0)))
+        )].iter().cloned().collect());
+
+        // the pending short order leaves 4 of the position
+        let orders = OrderValidator::new().set_symbols(Some(symbols)).validate_orders(
+            vec![
+                order(OrderKind::MarketOrder, "aapl", Direction::Short, 5.),
+                order(OrderKind::MarketOrder, "aapl", Direction::Short, 4.),
+                order(OrderKind::MarketOrder, "aapl", Direction::Short, 1.)
+            ],
+            &portfolio,
+            &time_slice()
+        );
+        assert_eq!(statuses(orders), vec![
+            OrderStatus::Rejected(RejectionReason::ShortingNotAllowed),
+            OrderStatus::NotSent,
+            OrderStatus::Rejected(RejectionReason::ShortingNotAllowed)
+        ]);
+
+        // the pending long order reserves 100 of the 290 left after the fill
+        let orders = OrderValidator::new().set_check_buying_power(true).validate_orders(
+            vec![
+                order(OrderKind::LimitOrder(Price::new(1.)), "eur/usd", Direction::Long, 200.),
+                order(OrderKind::LimitOrder(Price::new(1.)), "eur/usd", Direction::Long, 190.),
+                order(OrderKind::MarketOrder, "gbp/usd", Direction::Long, 1.)
+            ],
+            &portfolio,
+            &time_slice()
+        );
+        assert_eq!(statuses(orders), vec![
+            OrderStatus::Rejected(RejectionReason::InsufficientBuyingPower),
+            OrderStatus::NotSent,
+            OrderStatus::Rejected(RejectionReason::NoPrice)
+        ]);
+    }
+
+    #[test]
+    fn validate_requests() {
+        let portfolio_order = order(OrderKind::LimitOrder(Price::new(1.)), "eur/usd", Direction::Long, 100.);
+        let mut portfolio = Portfolio::new().set_cash(Money::new(300.));
+        portfolio.add_orders(vec![portfolio_order.clone()]);
+        let modify = |quantity: f64| OrderRequest::Modify(
+            portfolio_order.id().clone(), OrderKind::LimitOrder(Price::new(1.)), Quantity::new(quantity)
+        );
+        let (accepted, rejected) = OrderValidator::new().set_check_buying_power(true).validate_requests(
+            vec![modify(300.), modify(400.), modify(0.), OrderRequest::Cancel(portfolio_order.id().clone())],
+            &portfolio,
+            &time_slice()
+        );
+        // the modified order doesn't reserve cash for the order it replaces
+        assert_eq!(accepted, vec![modify(300.), OrderRequest::Cancel(portfolio_order.id().clone())]);
+        assert_eq!(rejected, vec![
+            (modify(400.), RejectionReason::InsufficientBuyingPower),
+            (modify(0.), RejectionReason::InvalidQuantity)
+        ]);
+    }
+}
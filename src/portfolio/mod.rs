@@ -1,10 +1,17 @@
 use std::collections::HashMap;
-use order::{Order, OrderId, OrderStatus};
+use order::{Order, OrderId, OrderStatus, OrderRequest, OrderEvent, CancellationReason};
+use direction::Direction;
+use execution::Execution;
+use symbol::SymbolId;
+use quantity::Quantity;
+use price::Money;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Portfolio {
     active_orders: HashMap<OrderId, Order>,
-    closed_orders: HashMap<OrderId, Order>
+    closed_orders: HashMap<OrderId, Order>,
+    cash: Money,
+    positions: HashMap<SymbolId, Quantity>
 }
 
 impl Portfolio {
@@ -12,15 +19,24 @@ impl Portfolio {
     pub fn new() -> Portfolio {
         Portfolio {
             active_orders: HashMap::new(),
-            closed_orders: HashMap::new()
+            closed_orders: HashMap::new(),
+            cash: Money::zero(),
+            positions: HashMap::new()
         }
     }
 
     pub fn add_orders(&mut self, orders: Vec<Order>) {
         for order in orders {
+            match *order.status() {
+                OrderStatus::Filled(ref execution) | OrderStatus::PartiallyFilled(ref execution) => {
+                    self.apply_execution(order.direction(), execution);
+                },
+                _ => ()
+            }
             let mut property = match *order.status() {
                 OrderStatus::Filled(_) => &mut self.closed_orders,
                 OrderStatus::Cancelled(_) => &mut self.closed_orders,
+                OrderStatus::Rejected(_) => &mut self.closed_orders,
                 _ => &mut self.active_orders
             };
             assert_eq!(property.insert(order.id().clone(), order), None);
@@ -30,6 +46,7 @@ impl Portfolio {
     pub fn update_orders(&mut self, order_updates: &HashMap<OrderId, OrderStatus>) {
         for (updated_order_id, updated_order_status) in order_updates {
             match *updated_order_status {
+                OrderStatus::PartiallyFilled(ref execution) => self.partially_fill_active_order(updated_order_id, execution),
                 OrderStatus::Filled(_) => self.move_active_order_to_closed_orders(updated_order_id, updated_order_status.clone()),
                 OrderStatus::Cancelled(_) => self.move_active_order_to_closed_orders(updated_order_id, updated_order_status.clone()),
                 _ => ()
@@ -37,9 +54,63 @@ impl Portfolio {
         }
     }
 
+    /// Get an active or closed order
+    pub fn order(&self, order_id: &OrderId) -> Option<&Order> {
+        self.active_orders.get(order_id).or_else(|| self.closed_orders.get(order_id))
+    }
+
+    /// Apply the execution of a part of an order which stays active for the rest of its quantity
+    fn partially_fill_active_order(&mut self, order_id: &OrderId, execution: &Execution) {
+        let direction = match self.active_orders.get_mut(order_id) {
+            Some(order) => {
+                order.add_execution(execution.clone());
+                order.set_status(OrderStatus::PartiallyFilled(execution.clone()));
+                *order.direction()
+            },
+            None => panic!("Order not found: {}", order_id)
+        };
+        self.apply_execution(&direction, execution);
+    }
+
+    /// Apply cancel and modify requests processed by the market simulation
+    pub fn apply_requests(&mut self, requests: &[OrderRequest]) {
+        for request in requests {
+            match *request {
+                OrderRequest::Cancel(ref order_id) => if self.active_orders.contains_key(order_id) {
+                    self.move_active_order_to_closed_orders(order_id, OrderStatus::Cancelled(CancellationReason::Requested));
+                },
+                OrderRequest::Modify(ref order_id, ref kind, quantity) => if let Some(order) = self.active_orders.get_mut(order_id) {
+                    order.set_kind(kind.clone());
+                    order.set_quantity(quantity);
+                }
+            }
+        }
+    }
+
+    /// Record events in the log of their active or closed orders
+    pub fn add_events(&mut self, events: Vec<OrderEvent>) {
+        let closed_orders = &mut self.closed_orders;
+        for event in events {
+            let order = self.active_orders.get_mut(event.order_id())
+                .or_else(|| closed_orders.get_mut(event.order_id()));
+            if let Some(order) = order {
+                order.add_event(event);
+            }
+        }
+    }
+
     fn move_active_order_to_closed_orders(&mut self, order_id: &OrderId, order_status: OrderStatus) {
         match self.active_orders.remove(order_id) {
             Some(mut order) => {
+                let order_status = match order_status {
+                    // the order is filled at the average price of all its parts
+                    OrderStatus::Filled(execution) => {
+                        self.apply_execution(order.direction(), &execution);
+                        order.add_execution(execution);
+                        OrderStatus::Filled(Execution::average(order.executions()).unwrap())
+                    },
+                    order_status => order_status
+                };
                 order.set_status(order_status);
                 match *order.status() {
                     OrderStatus::Filled(_) => {
@@ -55,6 +126,22 @@ impl Portfolio {
         };
     }
 
+    /// Update cash and position with the execution of an order
+    fn apply_execution(&mut self, direction: &Direction, execution: &Execution) {
+        let value = *execution.price() * *execution.quantity();
+        let position = self.positions.entry(execution.symbol_id().clone()).or_insert(Quantity::zero());
+        match *direction {
+            Direction::Long => {
+                self.cash -= value;
+                *position += *execution.quantity();
+            },
+            Direction::Short => {
+                self.cash += value;
+                *position -= *execution.quantity();
+            }
+        }
+    }
+
     pub fn active_orders(&self) -> &HashMap<OrderId, Order> {
         &self.active_orders
     }
@@ -63,14 +150,31 @@ impl Portfolio {
         &self.closed_orders
     }
 
+    pub fn cash(&self) -> Money {
+        self.cash
+    }
+
+    pub fn set_cash(mut self, value: Money) -> Self {
+        self.cash = value;
+        self
+    }
+
+    pub fn positions(&self) -> &HashMap<SymbolId, Quantity> {
+        &self.positions
+    }
+
 }
 
 #[cfg(test)]
 mod test {
+    extern crate chrono;
     use super::*;
     use direction::Direction;
     use symbol::SymbolId;
-    use order::{OrderKind, OrderBuilder};
+    use order::{OrderKind, OrderBuilder, OrderRequest, OrderEventKind, CancellationReason};
+    use execution::Execution;
+    use price::{Price, Money};
+    use self::chrono::prelude::{Utc, TimeZone};
 
     #[test]
     fn add_order() {
@@ -86,4 +190,92 @@ mod test {
             &[(order.id().clone(), order)].iter().cloned().collect::<HashMap<OrderId, Order>>()
         );
     }
+
+    #[test]
+    fn update_orders_cash_and_positions() {
+        let symbol_id = SymbolId::from("btc/usd");
+        let order = OrderBuilder::unallocated(
+            OrderKind::MarketOrder, symbol_id.clone(), Direction::Long
+        ).set_id(OrderId::from("test order")).set_quantity(Quantity::new(0.013)).build().unwrap();
+        let mut portfolio = Portfolio::new().set_cash(Money::new(1000.));
+        portfolio.add_orders(vec![order.clone()]);
+        portfolio.update_orders(&[(
+            order.id().clone(),
+            OrderStatus::Filled(
+                Execution::new(symbol_id.clone(), Quantity::new(0.013), Price::new(20000.), Utc.ymd(2017, 12, 1).and_hms(12, 0, 0))
+            )
+        )].iter().cloned().collect());
+        assert_eq!(portfolio.cash(), Money::new(740.));
+        assert_eq!(portfolio.positions().get(&symbol_id), Some(&Quantity::new(0.013)));
+        assert!(portfolio.active_orders().is_empty());
+    }
+
+    #[test]
+    fn update_orders_partially_filled() {
+        let symbol_id = SymbolId::from("btc/usd");
+        let order = OrderBuilder::unallocated(
+            OrderKind::MarketOrder, symbol_id.clone(), Direction::Long
+        ).set_id(OrderId::from("test order")).set_quantity(Quantity::new(4.)).build().unwrap();
+        let mut portfolio = Portfolio::new().set_cash(Money::new(100.));
+        portfolio.add_orders(vec![order.clone()]);
+        let first_execution = Execution::new(symbol_id.clone(), Quantity::new(1.), Price::new(10.), Utc.ymd(2017, 12, 1).and_hms(12, 0, 0));
+        portfolio.update_orders(&[
+            (order.id().clone(), OrderStatus::PartiallyFilled(first_execution.clone()))
+        ].iter().cloned().collect());
+        assert_eq!(portfolio.cash(), Money::new(90.));
+        assert_eq!(portfolio.active_orders().get(order.id()).unwrap().filled_quantity(), Quantity::new(1.));
+
+        let last_execution = Execution::new(symbol_id.clone(), Quantity::new(3.), Price::new(14.), Utc.ymd(2017, 12, 1).and_hms(12, 1, 0));
+        portfolio.update_orders(&[
+            (order.id().clone(), OrderStatus::Filled(last_execution.clone()))
+        ].iter().cloned().collect());
+        assert_eq!(portfolio.cash(), Money::new(48.));
+        assert_eq!(portfolio.positions().get(&symbol_id), Some(&Quantity::new(4.)));
+        let closed_order = portfolio.order(order.id()).unwrap();
+        assert_eq!(closed_order.executions(), &vec![first_execution, last_execution]);
+        assert_eq!(
+            closed_order.status(),
+            &OrderStatus::Filled(Execution::new(symbol_id.clone(), Quantity::new(4.), Price::new(13.), Utc.ymd(2017, 12, 1).and_hms(12, 1, 0)))
+        );
+    }
+
+    #[test]
+    fn apply_requests() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let limit_order = OrderBuilder::unallocated(
+            OrderKind::LimitOrder(Price::new(10.)), symbol_id.clone(), Direction::Long
+        ).set_id(OrderId::from("limit order")).set_quantity(Quantity::new(1.)).build().unwrap();
+        let stop_order = OrderBuilder::unallocated(
+            OrderKind::StopOrder(Price::new(8.)), symbol_id.clone(), Direction::Short
+        ).set_id(OrderId::from("stop order")).set_quantity(Quantity::new(1.)).build().unwrap();
+        let mut portfolio = Portfolio::new();
+        portfolio.add_orders(vec![limit_order.clone(), stop_order.clone()]);
+
+        portfolio.apply_requests(&[
+            OrderRequest::Cancel(limit_order.id().clone()),
+            OrderRequest::Modify(stop_order.id().clone(), OrderKind::StopOrder(Price::new(9.)), Quantity::new(2.))
+        ]);
+        let cancelled_order = portfolio.closed_orders().get(limit_order.id()).unwrap();
+        assert_eq!(cancelled_order.status(), &OrderStatus::Cancelled(CancellationReason::Requested));
+        let modified_order = portfolio.active_orders().get(stop_order.id()).unwrap();
+        assert_eq!(modified_order.kind(), &OrderKind::StopOrder(Price::new(9.)));
+        assert_eq!(modified_order.quantity(), Quantity::new(2.));
+    }
+
+    #[test]
+    fn add_events() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let order = OrderBuilder::unallocated(OrderKind::MarketOrder, symbol_id.clone(), Direction::Long)
+            .set_id(OrderId::from("test order")).set_quantity(Quantity::new(1.)).build().unwrap();
+        let mut portfolio = Portfolio::new();
+        portfolio.add_orders(vec![order.clone()]);
+        let datetime = Utc.ymd(2017, 12, 1).and_hms(12, 0, 0);
+        let execution = Execution::new(symbol_id.clone(), Quantity::new(1.), Price::new(10.), datetime);
+        let created = OrderEvent::new(datetime, &order, OrderEventKind::Created);
+        portfolio.add_events(vec![created.clone()]);
+        portfolio.update_orders(&[(order.id().clone(), OrderStatus::Filled(execution.clone()))].iter().cloned().collect());
+        let filled = OrderEvent::new(datetime, &order, OrderEventKind::Filled(execution));
+        portfolio.add_events(vec![filled.clone()]);
+        assert_eq!(portfolio.order(order.id()).unwrap().events(), &vec![created, filled]);
+    }
 }
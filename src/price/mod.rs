@@ -0,0 +1,225 @@
+#[cfg(feature = "decimal")]
+extern crate rust_decimal;
+
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Sub, SubAssign, Mul, Div, Neg};
+use std::str::FromStr;
+use quantity::Quantity;
+#[cfg(feature = "decimal")]
+use self::rust_decimal::Decimal;
+#[cfg(feature = "decimal")]
+use self::rust_decimal::prelude::{FromPrimitive, ToPrimitive, Zero};
+
+#[cfg(not(feature = "decimal"))]
+type Value = f64;
+#[cfg(feature = "decimal")]
+type Value = Decimal;
+
+/// Price of an instrument
+///
+/// Prices are binary floats by default. With the `decimal` feature they are exact decimal
+/// numbers, so that cash balances reconcile with broker statements.
+#[derive(Serialize, Deserialize, Copy, Clone, Default, PartialEq, PartialOrd, Debug)]
+pub struct Price(Value);
+
+/// Cash balance or profit and loss
+pub type Money = Price;
+
+#[cfg(not(feature = "decimal"))]
+impl Price {
+
+    pub fn new(value: f64) -> Price {
+        Price(value)
+    }
+
+    /// Create a price from a finite float
+    pub fn try_new(value: f64) -> Result<Price, ParsePriceError> {
+        if value.is_finite() {
+            Ok(Price(value))
+        }
+        else {
+            Err(ParsePriceError(format!("Invalid price: {}", value)))
+        }
+    }
+
+    pub fn zero() -> Price {
+        Price(0.)
+    }
+
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+
+    fn factor(value: f64) -> Value {
+        value
+    }
+
+}
+
+#[cfg(feature = "decimal")]
+impl Price {
+
+    /// Create a price from the shortest decimal representation of the float
+    ///
+    /// Panics if the float is NaN, infinite or out of the decimal range, see `try_new`.
+    pub fn new(value: f64) -> Price {
+        Price(Decimal::from_f64(value).expect("Price out of the decimal range"))
+    }
+
+    /// Create a price from a float, unless it's NaN, infinite or out of the decimal range
+    pub fn try_new(value: f64) -> Result<Price, ParsePriceError> {
+        Decimal::from_f64(value).map(Price).ok_or_else(|| ParsePriceError(format!("Invalid price: {}", value)))
+    }
+
+    pub fn zero() -> Price {
+        Price(Decimal::zero())
+    }
+
+    pub fn value(&self) -> f64 {
+        self.0.to_f64().expect("Price out of the float range")
+    }
+
+    fn factor(value: f64) -> Value {
+        Decimal::from_f64(value).expect("Factor out of the decimal range")
+    }
+
+}
+
+impl Price {
+
+    pub fn abs(&self) -> Price {
+        if *self < Price::zero() { -*self } else { *self }
+    }
+
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ParsePriceError(String);
+
+impl FromStr for Price {
+    type Err = ParsePriceError;
+
+    #[cfg(not(feature = "decimal"))]
+    fn from_str(s: &str) -> Result<Price, ParsePriceError> {
+        s.parse::<f64>().map(Price).map_err(|e| ParsePriceError(e.to_string()))
+    }
+
+    #[cfg(feature = "decimal")]
+    fn from_str(s: &str) -> Result<Price, ParsePriceError> {
+        Decimal::from_str(s).map(Price).map_err(|e| ParsePriceError(e.to_string()))
+    }
+}
+
+impl From<f64> for Price {
+    fn from(value: f64) -> Price {
+        Price::new(value)
+    }
+}
+
+impl fmt::Display for Price {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Add for Price {
+    type Output = Price;
+
+    fn add(self, other: Price) -> Price {
+        Price(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Price {
+    fn add_assign(&mut self, other: Price) {
+        self.0 = self.0 + other.0
+    }
+}
+
+impl Sub for Price {
+    type Output = Price;
+
+    fn sub(self, other: Price) -> Price {
+        Price(self.0 - other.0)
+    }
+}
+
+impl SubAssign for Price {
+    fn sub_assign(&mut self, other: Price) {
+        self.0 = self.0 - other.0
+    }
+}
+
+impl Neg for Price {
+    type Output = Price;
+
+    fn neg(self) -> Price {
+        Price(-self.0)
+    }
+}
+
+impl Mul<f64> for Price {
+    type Output = Price;
+
+    fn mul(self, factor: f64) -> Price {
+        Price(self.0 * Price::factor(factor))
+    }
+}
+
+/// Value of a quantity traded at this price
+impl Mul<Quantity> for Price {
+    type Output = Money;
+
+    fn mul(self, quantity: Quantity) -> Money {
+        Price(self.0 * Price::factor(quantity.value()))
+    }
+}
+
+/// Price of each unit of a quantity traded for this amount
+impl Div<Quantity> for Money {
+    type Output = Price;
+
+    fn div(self, quantity: Quantity) -> Price {
+        Price(self.0 / Price::factor(quantity.value()))
+    }
+}
+
+impl Sum for Price {
+    fn sum<I: Iterator<Item=Price>>(iter: I) -> Price {
+        iter.fold(Price::zero(), |total, price| total + price)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!("1.08713".parse::<Price>().unwrap(), Price::new(1.08713));
+        assert!("erroneous".parse::<Price>().is_err());
+    }
+
+    #[test]
+    fn try_new() {
+        assert_eq!(Price::try_new(1.5), Ok(Price::new(1.5)));
+        assert!(Price::try_new(f64::NAN).is_err());
+        assert!(Price::try_new(f64::INFINITY).is_err());
+    }
+
+    #[test]
+    fn value_of_quantity() {
+        assert_eq!(Price::new(1.5) * Quantity::new(4.), Price::new(6.));
+        assert_eq!(Price::new(6.) / Quantity::new(4.), Price::new(1.5));
+        assert_eq!((Price::new(2.) - Price::new(3.)).abs(), Price::new(1.));
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn exact_sum() {
+        let total: Money = vec![Price::new(0.1), Price::new(0.2)].into_iter().sum();
+        assert_eq!(total, Price::new(0.3));
+        assert_eq!(total.to_string(), "0.3");
+    }
+}
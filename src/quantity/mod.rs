@@ -0,0 +1,130 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Sub, SubAssign, Mul};
+
+/// Amount of an instrument or traded volume, fractional amounts are allowed
+#[derive(Serialize, Deserialize, Copy, Clone, Default, PartialEq, PartialOrd, Debug)]
+pub struct Quantity(f64);
+
+impl Quantity {
+
+    pub fn new(value: f64) -> Quantity {
+        Quantity(value)
+    }
+
+    pub fn zero() -> Quantity {
+        Quantity(0.)
+    }
+
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == 0.
+    }
+
+    /// Round down to a whole number of lots, e.g. 0.0137 with a lot size of 0.001 gives 0.013
+    pub fn round_to_lot(&self, lot_size: f64) -> Quantity {
+        // the epsilon avoids losing a lot to binary representation errors, e.g. 0.3 / 0.1
+        let lots = (self.0 / lot_size + 1e-9).floor();
+        // dividing by the number of lots per unit is exact for decimal lot sizes such as 0.001
+        let lots_per_unit = 1. / lot_size;
+        if lot_size < 1. && (lots_per_unit - lots_per_unit.round()).abs() < 1e-6 {
+            Quantity(lots / lots_per_unit.round())
+        }
+        else {
+            Quantity(lots * lot_size)
+        }
+    }
+
+    pub fn min(self, other: Quantity) -> Quantity {
+        if other < self { other } else { self }
+    }
+
+}
+
+impl From<u32> for Quantity {
+    fn from(value: u32) -> Quantity {
+        Quantity(value as f64)
+    }
+}
+
+impl From<u64> for Quantity {
+    fn from(value: u64) -> Quantity {
+        Quantity(value as f64)
+    }
+}
+
+impl From<f64> for Quantity {
+    fn from(value: f64) -> Quantity {
+        Quantity(value)
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Add for Quantity {
+    type Output = Quantity;
+
+    fn add(self, other: Quantity) -> Quantity {
+        Quantity(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Quantity {
+    fn add_assign(&mut self, other: Quantity) {
+        self.0 += other.0
+    }
+}
+
+impl Sub for Quantity {
+    type Output = Quantity;
+
+    fn sub(self, other: Quantity) -> Quantity {
+        Quantity(self.0 - other.0)
+    }
+}
+
+impl SubAssign for Quantity {
+    fn sub_assign(&mut self, other: Quantity) {
+        self.0 -= other.0
+    }
+}
+
+impl Mul<f64> for Quantity {
+    type Output = Quantity;
+
+    fn mul(self, factor: f64) -> Quantity {
+        Quantity(self.0 * factor)
+    }
+}
+
+impl Sum for Quantity {
+    fn sum<I: Iterator<Item=Quantity>>(iter: I) -> Quantity {
+        iter.fold(Quantity::zero(), |total, quantity| total + quantity)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_to_lot() {
+        assert_eq!(Quantity::new(0.0137).round_to_lot(0.001), Quantity::new(0.013));
+        assert_eq!(Quantity::new(0.3).round_to_lot(0.1), Quantity::new(0.3));
+        assert_eq!(Quantity::new(0.29).round_to_lot(0.25), Quantity::new(0.25));
+        assert_eq!(Quantity::from(1250u32).round_to_lot(100.), Quantity::from(1200u32));
+    }
+
+    #[test]
+    fn large_volume() {
+        let volume = Quantity::from(4_000_000_000u32) + Quantity::from(4_000_000_000u32);
+        assert_eq!(volume.to_string(), "8000000000");
+    }
+}
@@ -0,0 +1,147 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc};
+use ohlcv::TimeSlice;
+use order::{Order, OrderId, OrderStatus, OrderRequest};
+use signal::{Signal, SignalTags};
+use signal::detector::{DetectSignal, DetectSignalError};
+
+/// Detect a signal when all the detectors detect one in the same direction at the same datetime
+///
+/// The signal of the first detector is returned, labelled with the labels of all the signals,
+/// with their lowest strength and the tags of all of them, the tags of the last detectors taking
+/// precedence. When the detectors detect several signals at once, the first signal of each is
+/// combined.
+pub struct AllOf {
+    detectors: Vec<Box<DetectSignal>>
+}
+
+impl AllOf {
+    pub fn new(detectors: Vec<Box<DetectSignal>>) -> AllOf {
+        AllOf { detectors }
+    }
+}
+
+impl DetectSignal for AllOf {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        // every detector is run so that stateful detectors see every datetime
+        let mut signals = vec![];
+        for detector in &mut self.detectors {
+            signals.push(detector.detect_signal(datetime)?);
+        }
+        Ok(all_of(signals))
+    }
+
+    fn detect_signals(&mut self, datetime: &DateTime<Utc>) -> Result<Vec<Signal>, DetectSignalError> {
+        let mut signals = vec![];
+        for detector in &mut self.detectors {
+            signals.push(detector.detect_signals(datetime)?.into_iter().next());
+        }
+        Ok(all_of(signals).into_iter().collect())
+    }
+
+    fn on_start(&mut self) {
+        for detector in &mut self.detectors {
+            detector.on_start();
+        }
+    }
+
+    fn on_bar(&mut self, time_slice: &TimeSlice) {
+        for detector in &mut self.detectors {
+            detector.on_bar(time_slice);
+        }
+    }
+
+    fn on_end(&mut self) {
+        for detector in &mut self.detectors {
+            detector.on_end();
+        }
+    }
+
+    fn on_order_update(&mut self, order: &Order, status: &OrderStatus) {
+        for detector in &mut self.detectors {
+            detector.on_order_update(order, status);
+        }
+    }
+
+    fn on_position_closed(&mut self, entry_order_id: &OrderId) {
+        for detector in &mut self.detectors {
+            detector.on_position_closed(entry_order_id);
+        }
+    }
+
+    fn order_requests(&mut self, datetime: &DateTime<Utc>) -> Vec<OrderRequest> {
+        self.detectors.iter_mut().flat_map(|detector| detector.order_requests(datetime)).collect()
+    }
+}
+
+fn all_of(signals: Vec<Option<Signal>>) -> Option<Signal> {
+    if signals.is_empty() || signals.iter().any(|signal| signal.is_none()) {
+        return None;
+    }
+
+    let signals: Vec<Signal> = signals.into_iter().map(|signal| signal.unwrap()).collect();
+    let first = &signals[0];
+    if signals.iter().any(|signal| signal.direction() != first.direction()) {
+        return None;
+    }
+
+    let label = signals.iter().map(|signal| signal.label().clone()).collect::<Vec<String>>().join(" and ");
+    let strength = signals.iter()
+        .filter_map(|signal| signal.strength())
+        .fold(None, |min: Option<f64>, strength| Some(min.map_or(strength, |min| min.min(strength))));
+    let mut tags = SignalTags::new();
+    for signal in &signals {
+        tags.extend(signal.tags().clone());
+    }
+    Some(
+        Signal::new(first.symbol_id().clone(), *first.direction(), *first.datetime(), label)
+            .set_strength(strength)
+            .set_tags(tags)
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use self::chrono::prelude::TimeZone;
+    use direction::Direction;
+    use symbol::SymbolId;
+    use signal::detector::{Always, Once};
+
+    #[test]
+    fn detect_signal() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let mut detector = AllOf::new(vec![
+            Box::new(Always::new(symbol_id.clone(), Direction::Long)),
+            Box::new(Once::new(symbol_id.clone(), Direction::Long))
+        ]);
+        let datetime = Utc.ymd(2016, 1, 3).and_hms(17, 0, 0);
+        assert_eq!(
+            detector.detect_signal(&datetime).unwrap(),
+            Some(Signal::new(symbol_id.clone(), Direction::Long, datetime, String::from("always detect signal and once")))
+        );
+        assert_eq!(detector.detect_signal(&datetime).unwrap(), None);
+    }
+
+    #[test]
+    fn combine_signals() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let datetime = Utc.ymd(2016, 1, 3).and_hms(17, 0, 0);
+        let signal = |direction: Direction, strength: Option<f64>, tag: &str| Some(
+            Signal::new(symbol_id.clone(), direction, datetime, String::from(tag))
+                .set_strength(strength)
+                .set_tag(String::from(tag), String::from("1"))
+        );
+        assert_eq!(
+            all_of(vec![signal(Direction::Long, Some(0.8), "a"), signal(Direction::Long, None, "b"), signal(Direction::Long, Some(0.5), "c")]),
+            Some(
+                Signal::new(symbol_id.clone(), Direction::Long, datetime, String::from("a and b and c"))
+                    .set_strength(Some(0.5))
+                    .set_tag(String::from("a"), String::from("1"))
+                    .set_tag(String::from("b"), String::from("1"))
+                    .set_tag(String::from("c"), String::from("1"))
+            )
+        );
+        assert_eq!(all_of(vec![signal(Direction::Long, None, "a"), signal(Direction::Short, None, "b")]), None);
+    }
+}
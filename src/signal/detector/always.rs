@@ -17,7 +17,7 @@ impl Always {
 }
 
 impl DetectSignal for Always {
-    fn detect_signal(&self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
         let signal = Signal::new(
             self.symbol_id.clone(),
             self.direction.clone(),
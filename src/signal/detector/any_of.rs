@@ -0,0 +1,103 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc};
+use ohlcv::TimeSlice;
+use order::{Order, OrderId, OrderStatus, OrderRequest};
+use signal::Signal;
+use signal::detector::{DetectSignal, DetectSignalError};
+
+/// Detect the signal of the first detector detecting one
+pub struct AnyOf {
+    detectors: Vec<Box<DetectSignal>>
+}
+
+impl AnyOf {
+    pub fn new(detectors: Vec<Box<DetectSignal>>) -> AnyOf {
+        AnyOf { detectors }
+    }
+}
+
+impl DetectSignal for AnyOf {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        // every detector is run so that stateful detectors see every datetime
+        let mut result = None;
+        for detector in &mut self.detectors {
+            let signal = detector.detect_signal(datetime)?;
+            if result.is_none() {
+                result = signal;
+            }
+        }
+        Ok(result)
+    }
+
+    fn detect_signals(&mut self, datetime: &DateTime<Utc>) -> Result<Vec<Signal>, DetectSignalError> {
+        let mut result = vec![];
+        for detector in &mut self.detectors {
+            let signals = detector.detect_signals(datetime)?;
+            if result.is_empty() {
+                result = signals;
+            }
+        }
+        Ok(result)
+    }
+
+    fn on_start(&mut self) {
+        for detector in &mut self.detectors {
+            detector.on_start();
+        }
+    }
+
+    fn on_bar(&mut self, time_slice: &TimeSlice) {
+        for detector in &mut self.detectors {
+            detector.on_bar(time_slice);
+        }
+    }
+
+    fn on_end(&mut self) {
+        for detector in &mut self.detectors {
+            detector.on_end();
+        }
+    }
+
+    fn on_order_update(&mut self, order: &Order, status: &OrderStatus) {
+        for detector in &mut self.detectors {
+            detector.on_order_update(order, status);
+        }
+    }
+
+    fn on_position_closed(&mut self, entry_order_id: &OrderId) {
+        for detector in &mut self.detectors {
+            detector.on_position_closed(entry_order_id);
+        }
+    }
+
+    fn order_requests(&mut self, datetime: &DateTime<Utc>) -> Vec<OrderRequest> {
+        self.detectors.iter_mut().flat_map(|detector| detector.order_requests(datetime)).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use self::chrono::prelude::TimeZone;
+    use direction::Direction;
+    use symbol::SymbolId;
+    use signal::detector::{Always, Once};
+
+    #[test]
+    fn detect_signal() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let mut detector = AnyOf::new(vec![
+            Box::new(Once::new(symbol_id.clone(), Direction::Short)),
+            Box::new(Always::new(symbol_id.clone(), Direction::Long))
+        ]);
+        let datetime = Utc.ymd(2016, 1, 3).and_hms(17, 0, 0);
+        assert_eq!(
+            detector.detect_signal(&datetime).unwrap(),
+            Some(Signal::new(symbol_id.clone(), Direction::Short, datetime, String::from("once")))
+        );
+        assert_eq!(
+            detector.detect_signal(&datetime).unwrap(),
+            Some(Signal::new(symbol_id.clone(), Direction::Long, datetime, String::from("always detect signal")))
+        );
+    }
+}
@@ -0,0 +1,81 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc};
+use std::mem;
+use direction::Direction;
+use indicator::Indicator;
+use ohlcv::TimeSlice;
+use signal::Signal;
+use signal::detector::{DetectSignal, DetectSignalError, Cross};
+use symbol::SymbolId;
+
+/// Detect a signal when the close crosses an indicator band, such as a Bollinger band
+pub struct BandCross {
+    symbol_id: SymbolId,
+    direction: Direction,
+    band: Box<Indicator>,
+    cross: Cross,
+    previous: Option<(f64, f64)>,
+    crossed: bool
+}
+
+impl BandCross {
+    pub fn new(symbol_id: SymbolId, direction: Direction, band: Box<Indicator>, cross: Cross) -> BandCross {
+        BandCross {
+            symbol_id,
+            direction,
+            band,
+            cross,
+            previous: None,
+            crossed: false
+        }
+    }
+}
+
+impl DetectSignal for BandCross {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        if mem::replace(&mut self.crossed, false) {
+            Ok(Some(Signal::new(self.symbol_id.clone(), self.direction, *datetime, String::from("band cross"))))
+        }
+        else {
+            Ok(None)
+        }
+    }
+
+    fn on_bar(&mut self, time_slice: &TimeSlice) {
+        self.crossed = false;
+        let close = match time_slice.ohlcv(&self.symbol_id) {
+            Some(ohlcv) => ohlcv.close(),
+            None => return
+        };
+        if let Some(band) = self.band.update(close) {
+            if let Some(previous) = self.previous {
+                self.crossed = self.cross.crossed(previous, (close, band));
+            }
+            self.previous = Some((close, band));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use indicator::{BollingerBand, Band};
+    use signal::detector::test_time_slice;
+
+    #[test]
+    fn detect_signal() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let mut detector = BandCross::new(
+            symbol_id.clone(),
+            Direction::Long,
+            Box::new(BollingerBand::new(Band::Lower, 3, 1.)),
+            Cross::Below
+        );
+        let signals = vec![2., 2., 2., 1.].into_iter().enumerate().map(|(minute, close)| {
+            let time_slice = test_time_slice(&symbol_id, minute as u32, close, close, close, close);
+            detector.on_bar(&time_slice);
+            detector.detect_signal(time_slice.datetime()).unwrap().is_some()
+        }).collect::<Vec<bool>>();
+        assert_eq!(signals, vec![false, false, false, true]);
+    }
+}
@@ -0,0 +1,78 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc};
+use std::mem;
+use direction::Direction;
+use indicator::{Indicator, Highest, Lowest};
+use ohlcv::TimeSlice;
+use signal::Signal;
+use signal::detector::{DetectSignal, DetectSignalError};
+use symbol::SymbolId;
+
+/// Detect a long signal when the close breaks above the highest high of the previous `bars`
+/// bars, or a short signal when it breaks below their lowest low
+pub struct Breakout {
+    symbol_id: SymbolId,
+    direction: Direction,
+    highest: Highest,
+    lowest: Lowest,
+    broken: bool
+}
+
+impl Breakout {
+    pub fn new(symbol_id: SymbolId, direction: Direction, bars: usize) -> Breakout {
+        Breakout {
+            symbol_id,
+            direction,
+            highest: Highest::new(bars),
+            lowest: Lowest::new(bars),
+            broken: false
+        }
+    }
+
+    pub fn bars(&self) -> usize {
+        self.highest.period()
+    }
+}
+
+impl DetectSignal for Breakout {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        if mem::replace(&mut self.broken, false) {
+            Ok(Some(Signal::new(self.symbol_id.clone(), self.direction, *datetime, String::from("breakout"))))
+        }
+        else {
+            Ok(None)
+        }
+    }
+
+    fn on_bar(&mut self, time_slice: &TimeSlice) {
+        self.broken = false;
+        let ohlcv = match time_slice.ohlcv(&self.symbol_id) {
+            Some(ohlcv) => ohlcv,
+            None => return
+        };
+        self.broken = match self.direction {
+            Direction::Long => self.highest.value().map_or(false, |high| ohlcv.close() > high),
+            Direction::Short => self.lowest.value().map_or(false, |low| ohlcv.close() < low)
+        };
+        self.highest.update(ohlcv.high());
+        self.lowest.update(ohlcv.low());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use signal::detector::test_time_slice;
+
+    #[test]
+    fn detect_signal() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let mut detector = Breakout::new(symbol_id.clone(), Direction::Short, 2);
+        let signals = vec![(3., 2.), (4., 2.5), (3., 2.2), (2.5, 1.8)].into_iter().enumerate().map(|(minute, (high, low))| {
+            let time_slice = test_time_slice(&symbol_id, minute as u32, low, high, low, low);
+            detector.on_bar(&time_slice);
+            detector.detect_signal(time_slice.datetime()).unwrap().is_some()
+        }).collect::<Vec<bool>>();
+        assert_eq!(signals, vec![false, false, false, true]);
+    }
+}
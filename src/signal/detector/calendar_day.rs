@@ -0,0 +1,115 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc, Datelike, NaiveDate, Weekday};
+use self::chrono::Duration;
+use direction::Direction;
+use signal::Signal;
+use signal::detector::{DetectSignal, DetectSignalError};
+use symbol::SymbolId;
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum CalendarPeriod {
+    Week,
+    Month
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum CalendarPosition {
+    /// First day with a bar in the period, from the first change of period
+    First,
+    /// Last weekday of the period, from Monday to Friday, without knowledge of holidays
+    Last
+}
+
+/// Detect a signal on the first bar of the first or last trading day of each week or month
+pub struct CalendarDay {
+    symbol_id: SymbolId,
+    direction: Direction,
+    period: CalendarPeriod,
+    position: CalendarPosition,
+    last_period: Option<(i32, u32)>,
+    last_detection: Option<(i32, u32)>
+}
+
+impl CalendarDay {
+    pub fn new(symbol_id: SymbolId, direction: Direction, period: CalendarPeriod, position: CalendarPosition) -> CalendarDay {
+        CalendarDay { symbol_id, direction, period, position, last_period: None, last_detection: None }
+    }
+
+    /// Year and index of the period containing the date
+    fn period_of(&self, date: &NaiveDate) -> (i32, u32) {
+        match self.period {
+            CalendarPeriod::Week => (date.iso_week().year(), date.iso_week().week()),
+            CalendarPeriod::Month => (date.year(), date.month())
+        }
+    }
+
+    fn last_weekday(&self, date: &NaiveDate) -> NaiveDate {
+        let mut last_day = match self.period {
+            CalendarPeriod::Week => *date + Duration::days(6 - date.weekday().num_days_from_monday() as i64),
+            CalendarPeriod::Month => {
+                let (year, month) = if date.month() == 12 { (date.year() + 1, 1) } else { (date.year(), date.month() + 1) };
+                NaiveDate::from_ymd_opt(year, month, 1).unwrap() - Duration::days(1)
+            }
+        };
+        while last_day.weekday() == Weekday::Sat || last_day.weekday() == Weekday::Sun {
+            last_day -= Duration::days(1);
+        }
+        last_day
+    }
+
+    fn label(&self) -> &'static str {
+        match (self.position, self.period) {
+            (CalendarPosition::First, CalendarPeriod::Week) => "first day of week",
+            (CalendarPosition::First, CalendarPeriod::Month) => "first day of month",
+            (CalendarPosition::Last, CalendarPeriod::Week) => "last day of week",
+            (CalendarPosition::Last, CalendarPeriod::Month) => "last day of month"
+        }
+    }
+}
+
+impl DetectSignal for CalendarDay {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        let date = datetime.date_naive();
+        let period = self.period_of(&date);
+        let last_period = self.last_period.replace(period);
+        if self.last_detection == Some(period) {
+            return Ok(None);
+        }
+        match self.position {
+            // the first bar seen may be in the middle of its period
+            CalendarPosition::First => if last_period.unwrap_or(period) == period {
+                return Ok(None);
+            },
+            CalendarPosition::Last => if date < self.last_weekday(&date) {
+                return Ok(None);
+            }
+        }
+        self.last_detection = Some(period);
+        Ok(Some(Signal::new(self.symbol_id.clone(), self.direction, *datetime, String::from(self.label()))))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use self::chrono::prelude::TimeZone;
+
+    #[test]
+    fn detect_signal() {
+        // from Thursday the 28th of January 2016 to Tuesday the 2nd of February
+        let datetimes = [
+            Utc.ymd(2016, 1, 28).and_hms(17, 0, 0),
+            Utc.ymd(2016, 1, 29).and_hms(17, 0, 0),
+            Utc.ymd(2016, 1, 29).and_hms(18, 0, 0),
+            Utc.ymd(2016, 2, 1).and_hms(17, 0, 0),
+            Utc.ymd(2016, 2, 2).and_hms(17, 0, 0)
+        ];
+        let mut first = CalendarDay::new(SymbolId::from("eur/usd"), Direction::Long, CalendarPeriod::Month, CalendarPosition::First);
+        let signals = datetimes.iter().map(|datetime| first.detect_signal(datetime).unwrap().is_some()).collect::<Vec<bool>>();
+        assert_eq!(signals, vec![false, false, false, true, false]);
+
+        let mut last = CalendarDay::new(SymbolId::from("eur/usd"), Direction::Long, CalendarPeriod::Week, CalendarPosition::Last);
+        let signals = datetimes.iter().map(|datetime| last.detect_signal(datetime).unwrap().is_some()).collect::<Vec<bool>>();
+        assert_eq!(signals, vec![false, true, false, false, false]);
+    }
+}
@@ -0,0 +1,87 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc};
+use std::mem;
+use std::collections::VecDeque;
+use direction::Direction;
+use ohlcv::{Ohlcv, TimeSlice, CandlestickPattern};
+use signal::Signal;
+use signal::detector::{DetectSignal, DetectSignalError};
+use symbol::SymbolId;
+
+/// Detect a signal labelled with the name of the pattern when a candlestick pattern ends on the
+/// last bar of the symbol
+pub struct Candlestick {
+    symbol_id: SymbolId,
+    direction: Direction,
+    pattern: CandlestickPattern,
+    window: VecDeque<Ohlcv>,
+    found: bool
+}
+
+impl Candlestick {
+    pub fn new(symbol_id: SymbolId, direction: Direction, pattern: CandlestickPattern) -> Candlestick {
+        Candlestick {
+            symbol_id,
+            direction,
+            pattern,
+            window: VecDeque::with_capacity(pattern.bars() + 1),
+            found: false
+        }
+    }
+
+    pub fn pattern(&self) -> &CandlestickPattern {
+        &self.pattern
+    }
+}
+
+impl DetectSignal for Candlestick {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        if mem::replace(&mut self.found, false) {
+            Ok(Some(Signal::new(
+                self.symbol_id.clone(),
+                self.direction,
+                *datetime,
+                String::from(self.pattern.name(self.direction))
+            )))
+        }
+        else {
+            Ok(None)
+        }
+    }
+
+    fn on_bar(&mut self, time_slice: &TimeSlice) {
+        self.found = false;
+        let ohlcv = match time_slice.ohlcv(&self.symbol_id) {
+            Some(ohlcv) => ohlcv,
+            None => return
+        };
+        self.window.push_back(ohlcv.clone());
+        if self.window.len() > self.pattern.bars() {
+            self.window.pop_front();
+        }
+        let bars = self.window.iter().cloned().collect::<Vec<Ohlcv>>();
+        self.found = self.pattern.is_found(self.direction, &bars);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use signal::detector::test_time_slice;
+
+    #[test]
+    fn detect_signal() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let mut detector = Candlestick::new(symbol_id.clone(), Direction::Short, CandlestickPattern::Hammer);
+        let time_slice = test_time_slice(&symbol_id, 0, 1., 2., 1., 2.);
+        detector.on_bar(&time_slice);
+        assert_eq!(detector.detect_signal(time_slice.datetime()).unwrap(), None);
+
+        let time_slice = test_time_slice(&symbol_id, 1, 1., 2., 1., 1.2);
+        detector.on_bar(&time_slice);
+        assert_eq!(
+            detector.detect_signal(time_slice.datetime()).unwrap(),
+            Some(Signal::new(symbol_id.clone(), Direction::Short, *time_slice.datetime(), String::from("shooting star")))
+        );
+    }
+}
@@ -0,0 +1,123 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc};
+use self::chrono::Duration;
+use ohlcv::TimeSlice;
+use order::{Order, OrderId, OrderStatus, OrderRequest};
+use signal::Signal;
+use signal::detector::{DetectSignal, DetectSignalError};
+
+/// How long signals are suppressed after a signal
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum CooldownPeriod {
+    /// Number of following detections
+    Bars(usize),
+    /// Time elapsed since the signal
+    Duration(Duration)
+}
+
+/// Suppress the signals of a detector during a period after each of its signals
+pub struct Cooldown {
+    detector: Box<DetectSignal>,
+    period: CooldownPeriod,
+    last_signal: Option<DateTime<Utc>>,
+    bars_since_signal: usize
+}
+
+impl Cooldown {
+    pub fn new(detector: Box<DetectSignal>, period: CooldownPeriod) -> Cooldown {
+        Cooldown {
+            detector,
+            period,
+            last_signal: None,
+            bars_since_signal: 0
+        }
+    }
+
+    fn is_cooling_down(&self, datetime: &DateTime<Utc>) -> bool {
+        match (self.last_signal, self.period) {
+            (None, _) => false,
+            (Some(_), CooldownPeriod::Bars(bars)) => self.bars_since_signal <= bars,
+            (Some(last_signal), CooldownPeriod::Duration(duration)) => *datetime < last_signal + duration
+        }
+    }
+
+    fn cool_down(&mut self, signals: Vec<Signal>, datetime: &DateTime<Utc>) -> Vec<Signal> {
+        self.bars_since_signal += 1;
+        if signals.is_empty() || self.is_cooling_down(datetime) {
+            return vec![];
+        }
+        self.last_signal = Some(*datetime);
+        self.bars_since_signal = 0;
+        signals
+    }
+}
+
+impl DetectSignal for Cooldown {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        // the detector is run during the cooldown so that it sees every datetime
+        let signal = self.detector.detect_signal(datetime)?;
+        Ok(self.cool_down(signal.into_iter().collect(), datetime).into_iter().next())
+    }
+
+    fn detect_signals(&mut self, datetime: &DateTime<Utc>) -> Result<Vec<Signal>, DetectSignalError> {
+        let signals = self.detector.detect_signals(datetime)?;
+        Ok(self.cool_down(signals, datetime))
+    }
+
+    fn on_start(&mut self) {
+        self.detector.on_start();
+    }
+
+    fn on_bar(&mut self, time_slice: &TimeSlice) {
+        self.detector.on_bar(time_slice);
+    }
+
+    fn on_end(&mut self) {
+        self.detector.on_end();
+    }
+
+    fn on_order_update(&mut self, order: &Order, status: &OrderStatus) {
+        self.detector.on_order_update(order, status);
+    }
+
+    fn on_position_closed(&mut self, entry_order_id: &OrderId) {
+        self.detector.on_position_closed(entry_order_id);
+    }
+
+    fn order_requests(&mut self, datetime: &DateTime<Utc>) -> Vec<OrderRequest> {
+        self.detector.order_requests(datetime)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use self::chrono::prelude::TimeZone;
+    use direction::Direction;
+    use symbol::SymbolId;
+    use signal::detector::Always;
+
+    #[test]
+    fn bars() {
+        let mut detector = Cooldown::new(
+            Box::new(Always::new(SymbolId::from("eur/usd"), Direction::Long)),
+            CooldownPeriod::Bars(2)
+        );
+        let signals = (0..5).map(|minute| {
+            detector.detect_signal(&Utc.ymd(2016, 1, 3).and_hms(17, minute, 0)).unwrap().is_some()
+        }).collect::<Vec<bool>>();
+        assert_eq!(signals, vec![true, false, false, true, false]);
+    }
+
+    #[test]
+    fn duration() {
+        let mut detector = Cooldown::new(
+            Box::new(Always::new(SymbolId::from("eur/usd"), Direction::Long)),
+            CooldownPeriod::Duration(Duration::seconds(90))
+        );
+        let signals = (0..5).map(|minute| {
+            detector.detect_signal(&Utc.ymd(2016, 1, 3).and_hms(17, minute, 0)).unwrap().is_some()
+        }).collect::<Vec<bool>>();
+        assert_eq!(signals, vec![true, false, true, false, true]);
+    }
+}
@@ -0,0 +1,186 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc, Datelike, Timelike};
+use std::str::FromStr;
+use direction::Direction;
+use signal::Signal;
+use signal::detector::{DetectSignal, DetectSignalError};
+use symbol::SymbolId;
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ParseCronError(String);
+
+/// Values allowed by one field of a cron expression
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+struct CronField {
+    values: u64,
+    restricted: bool
+}
+
+impl CronField {
+
+    fn parse(field: &str, min: u32, max: u32) -> Result<CronField, ParseCronError> {
+        let mut values = 0u64;
+        for item in field.split(',') {
+            let (range, step) = match item.find('/') {
+                Some(idx) => (&item[..idx], parse_number(&item[idx + 1..])?),
+                None => (item, 1)
+            };
+            let (start, end) = if range == "*" {
+                (min, max)
+            }
+            else if let Some(idx) = range.find('-') {
+                (parse_number(&range[..idx])?, parse_number(&range[idx + 1..])?)
+            }
+            else {
+                let start = parse_number(range)?;
+                (start, if item.contains('/') { max } else { start })
+            };
+            if step == 0 || start < min || end > max || start > end {
+                return Err(ParseCronError(format!("Invalid cron field: {}", field)));
+            }
+            for value in (start..end + 1).step_by(step as usize) {
+                values |= 1 << value;
+            }
+        }
+        // steps over all the values such as `*/2` don't restrict the field
+        Ok(CronField { values, restricted: !field.starts_with('*') })
+    }
+
+    fn matches(&self, value: u32) -> bool {
+        self.values & (1 << value) != 0
+    }
+
+}
+
+fn parse_number(s: &str) -> Result<u32, ParseCronError> {
+    u32::from_str(s).map_err(|e| ParseCronError(format!("{}: {}", s, e)))
+}
+
+/// Detect a signal on the first bar of every minute which matches a cron expression
+///
+/// The expression has the five usual fields: minute, hour, day of the month, month and day of
+/// the week, where Sunday is 0 or 7. Each field accepts `*`, values, ranges, lists and steps such
+/// as `*/15` or `1-5`. As in cron, a bar matches either day field when both are restricted.
+pub struct Cron {
+    symbol_id: SymbolId,
+    direction: Direction,
+    expression: String,
+    minute: CronField,
+    hour: CronField,
+    day_of_month: CronField,
+    month: CronField,
+    day_of_week: CronField,
+    last_minute: Option<DateTime<Utc>>
+}
+
+impl Cron {
+
+    pub fn new(symbol_id: SymbolId, direction: Direction, expression: &str) -> Result<Cron, ParseCronError> {
+        let fields = expression.split_whitespace().collect::<Vec<&str>>();
+        if fields.len() != 5 {
+            return Err(ParseCronError(format!("Expected 5 fields in cron expression: {}", expression)));
+        }
+        let mut day_of_week = CronField::parse(fields[4], 0, 7)?;
+        if day_of_week.matches(7) {
+            day_of_week.values |= 1;
+        }
+        Ok(Cron {
+            symbol_id,
+            direction,
+            expression: String::from(expression),
+            minute: CronField::parse(fields[0], 0, 59)?,
+            hour: CronField::parse(fields[1], 0, 23)?,
+            day_of_month: CronField::parse(fields[2], 1, 31)?,
+            month: CronField::parse(fields[3], 1, 12)?,
+            day_of_week,
+            last_minute: None
+        })
+    }
+
+    pub fn expression(&self) -> &String {
+        &self.expression
+    }
+
+    fn matches(&self, datetime: &DateTime<Utc>) -> bool {
+        let day_of_month = self.day_of_month.matches(datetime.day());
+        let day_of_week = self.day_of_week.matches(datetime.weekday().num_days_from_sunday());
+        let day = if self.day_of_month.restricted && self.day_of_week.restricted {
+            day_of_month || day_of_week
+        }
+        else {
+            day_of_month && day_of_week
+        };
+        self.minute.matches(datetime.minute())
+            && self.hour.matches(datetime.hour())
+            && self.month.matches(datetime.month())
+            && day
+    }
+
+}
+
+impl DetectSignal for Cron {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        let minute = datetime.with_second(0).and_then(|datetime| datetime.with_nanosecond(0));
+        if self.matches(datetime) && minute != self.last_minute {
+            self.last_minute = minute;
+            Ok(Some(Signal::new(self.symbol_id.clone(), self.direction, *datetime, String::from("cron"))))
+        }
+        else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use self::chrono::prelude::TimeZone;
+    use self::chrono::Duration;
+
+    #[test]
+    fn detect_signal() {
+        // every 30 minutes from 9:00 to 10:59 on weekdays
+        let mut detector = Cron::new(SymbolId::from("eur/usd"), Direction::Long, "*/30 9-10 * * 1-5").unwrap();
+        let signals = [
+            Utc.ymd(2016, 1, 4).and_hms(9, 0, 0),
+            Utc.ymd(2016, 1, 4).and_hms(9, 15, 0),
+            Utc.ymd(2016, 1, 4).and_hms(10, 30, 0),
+            Utc.ymd(2016, 1, 4).and_hms(11, 0, 0),
+            Utc.ymd(2016, 1, 3).and_hms(9, 0, 0)
+        ].iter().map(|datetime| detector.detect_signal(datetime).unwrap().is_some()).collect::<Vec<bool>>();
+        assert_eq!(signals, vec![true, false, true, false, false]);
+    }
+
+    #[test]
+    fn detect_signal_sub_minute_bars() {
+        let mut detector = Cron::new(SymbolId::from("eur/usd"), Direction::Long, "0 9 * * *").unwrap();
+        let signals = (0..24)
+            .map(|i| Utc.ymd(2016, 1, 4).and_hms(9, 0, 0) + Duration::seconds(5 * i))
+            .filter(|datetime| detector.detect_signal(datetime).unwrap().is_some())
+            .collect::<Vec<DateTime<Utc>>>();
+        assert_eq!(signals, vec![Utc.ymd(2016, 1, 4).and_hms(9, 0, 0)]);
+        assert!(detector.detect_signal(&Utc.ymd(2016, 1, 5).and_hms(9, 0, 5)).unwrap().is_some());
+    }
+
+    #[test]
+    fn day_fields() {
+        // the first day of the month or any Sunday at midnight
+        let detector = Cron::new(SymbolId::from("eur/usd"), Direction::Long, "0 0 1 * 7").unwrap();
+        assert!(detector.matches(&Utc.ymd(2016, 2, 1).and_hms(0, 0, 0)));
+        assert!(detector.matches(&Utc.ymd(2016, 1, 3).and_hms(0, 0, 0)));
+        assert!(!detector.matches(&Utc.ymd(2016, 1, 4).and_hms(0, 0, 0)));
+
+        // a step over every day of the week doesn't restrict it, only Sundays, Tuesdays... match
+        let detector = Cron::new(SymbolId::from("eur/usd"), Direction::Long, "0 0 1 * */2").unwrap();
+        assert!(detector.matches(&Utc.ymd(2016, 3, 1).and_hms(0, 0, 0)));
+        assert!(!detector.matches(&Utc.ymd(2016, 2, 1).and_hms(0, 0, 0)));
+        assert!(!detector.matches(&Utc.ymd(2016, 1, 3).and_hms(0, 0, 0)));
+    }
+
+    #[test]
+    fn parse_error() {
+        assert!(Cron::new(SymbolId::from("eur/usd"), Direction::Long, "* * *").is_err());
+        assert!(Cron::new(SymbolId::from("eur/usd"), Direction::Long, "60 * * * *").is_err());
+        assert!(Cron::new(SymbolId::from("eur/usd"), Direction::Long, "*/0 * * * *").is_err());
+    }
+}
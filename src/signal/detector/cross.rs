@@ -0,0 +1,31 @@
+/// Side from which a series crosses another one
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Cross {
+    Above,
+    Below
+}
+
+impl Cross {
+
+    /// Whether the first value crossed the second one from this side between two bars
+    pub fn crossed(&self, previous: (f64, f64), current: (f64, f64)) -> bool {
+        match *self {
+            Cross::Above => previous.0 <= previous.1 && current.0 > current.1,
+            Cross::Below => previous.0 >= previous.1 && current.0 < current.1
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn crossed() {
+        assert!(Cross::Above.crossed((1., 2.), (3., 2.)));
+        assert!(!Cross::Above.crossed((3., 2.), (4., 2.)));
+        assert!(Cross::Below.crossed((2., 2.), (1., 2.)));
+        assert!(!Cross::Below.crossed((1., 2.), (3., 2.)));
+    }
+}
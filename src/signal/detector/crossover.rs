@@ -0,0 +1,87 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc};
+use std::mem;
+use direction::Direction;
+use indicator::{Indicator, Sma};
+use ohlcv::TimeSlice;
+use signal::Signal;
+use signal::detector::{DetectSignal, DetectSignalError, Cross};
+use symbol::SymbolId;
+
+/// Detect a signal when a fast indicator of the close crosses a slow one, upwards for a long
+/// signal and downwards for a short signal
+pub struct Crossover {
+    symbol_id: SymbolId,
+    direction: Direction,
+    fast: Box<Indicator>,
+    slow: Box<Indicator>,
+    previous: Option<(f64, f64)>,
+    crossed: bool
+}
+
+impl Crossover {
+    pub fn new(symbol_id: SymbolId, direction: Direction, fast: Box<Indicator>, slow: Box<Indicator>) -> Crossover {
+        Crossover {
+            symbol_id,
+            direction,
+            fast,
+            slow,
+            previous: None,
+            crossed: false
+        }
+    }
+
+    /// Crossover of two simple moving averages
+    pub fn moving_averages(symbol_id: SymbolId, direction: Direction, fast_period: usize, slow_period: usize) -> Crossover {
+        Crossover::new(symbol_id, direction, Box::new(Sma::new(fast_period)), Box::new(Sma::new(slow_period)))
+    }
+}
+
+impl DetectSignal for Crossover {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        if mem::replace(&mut self.crossed, false) {
+            Ok(Some(Signal::new(self.symbol_id.clone(), self.direction, *datetime, String::from("crossover"))))
+        }
+        else {
+            Ok(None)
+        }
+    }
+
+    fn on_bar(&mut self, time_slice: &TimeSlice) {
+        self.crossed = false;
+        let ohlcv = match time_slice.ohlcv(&self.symbol_id) {
+            Some(ohlcv) => ohlcv,
+            None => return
+        };
+        let fast = self.fast.update(ohlcv.close());
+        let slow = self.slow.update(ohlcv.close());
+        if let (Some(fast), Some(slow)) = (fast, slow) {
+            let cross = match self.direction {
+                Direction::Long => Cross::Above,
+                Direction::Short => Cross::Below
+            };
+            if let Some(previous) = self.previous {
+                self.crossed = cross.crossed(previous, (fast, slow));
+            }
+            self.previous = Some((fast, slow));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use signal::detector::test_time_slice;
+
+    #[test]
+    fn detect_signal() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let mut detector = Crossover::moving_averages(symbol_id.clone(), Direction::Long, 1, 2);
+        let signals = vec![3., 2., 1., 3., 4.].into_iter().enumerate().map(|(minute, close)| {
+            let time_slice = test_time_slice(&symbol_id, minute as u32, close, close, close, close);
+            detector.on_bar(&time_slice);
+            detector.detect_signal(time_slice.datetime()).unwrap().is_some()
+        }).collect::<Vec<bool>>();
+        assert_eq!(signals, vec![false, false, false, true, false]);
+    }
+}
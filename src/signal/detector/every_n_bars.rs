@@ -0,0 +1,51 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc};
+use direction::Direction;
+use signal::Signal;
+use signal::detector::{DetectSignal, DetectSignalError};
+use symbol::SymbolId;
+
+/// Detect a signal on the first bar and then every `bars` bars
+pub struct EveryNBars {
+    symbol_id: SymbolId,
+    direction: Direction,
+    bars: usize,
+    count: usize
+}
+
+impl EveryNBars {
+    pub fn new(symbol_id: SymbolId, direction: Direction, bars: usize) -> EveryNBars {
+        EveryNBars { symbol_id, direction, bars, count: 0 }
+    }
+}
+
+impl DetectSignal for EveryNBars {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        if self.bars == 0 {
+            return Ok(None);
+        }
+        let count = self.count;
+        self.count = (count + 1) % self.bars;
+        if count == 0 {
+            Ok(Some(Signal::new(self.symbol_id.clone(), self.direction, *datetime, String::from("every n bars"))))
+        }
+        else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use self::chrono::prelude::TimeZone;
+
+    #[test]
+    fn detect_signal() {
+        let mut detector = EveryNBars::new(SymbolId::from("eur/usd"), Direction::Long, 2);
+        let signals = (0..5).map(|minute| {
+            detector.detect_signal(&Utc.ymd(2016, 1, 3).and_hms(17, minute, 0)).unwrap().is_some()
+        }).collect::<Vec<bool>>();
+        assert_eq!(signals, vec![true, false, true, false, true]);
+    }
+}
@@ -0,0 +1,99 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc};
+use ohlcv::TimeSlice;
+use order::{Order, OrderId, OrderStatus, OrderRequest};
+use signal::Signal;
+use signal::detector::{DetectSignal, DetectSignalError};
+
+/// Detect the signals of a detector only when the gate detects a signal in the same direction,
+/// e.g. long entries only when a trend detector is long
+pub struct Filter {
+    detector: Box<DetectSignal>,
+    gate: Box<DetectSignal>
+}
+
+impl Filter {
+    pub fn new(detector: Box<DetectSignal>, gate: Box<DetectSignal>) -> Filter {
+        Filter { detector, gate }
+    }
+}
+
+impl DetectSignal for Filter {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        let gate_signal = self.gate.detect_signal(datetime)?;
+        let signal = self.detector.detect_signal(datetime)?;
+        Ok(filter(signal.into_iter().collect(), &gate_signal.into_iter().collect::<Vec<Signal>>()).into_iter().next())
+    }
+
+    /// Detect the signals in the direction of any of the signals of the gate
+    fn detect_signals(&mut self, datetime: &DateTime<Utc>) -> Result<Vec<Signal>, DetectSignalError> {
+        let gate_signals = self.gate.detect_signals(datetime)?;
+        let signals = self.detector.detect_signals(datetime)?;
+        Ok(filter(signals, &gate_signals))
+    }
+
+    fn on_start(&mut self) {
+        self.gate.on_start();
+        self.detector.on_start();
+    }
+
+    fn on_bar(&mut self, time_slice: &TimeSlice) {
+        self.gate.on_bar(time_slice);
+        self.detector.on_bar(time_slice);
+    }
+
+    fn on_end(&mut self) {
+        self.gate.on_end();
+        self.detector.on_end();
+    }
+
+    fn on_order_update(&mut self, order: &Order, status: &OrderStatus) {
+        self.gate.on_order_update(order, status);
+        self.detector.on_order_update(order, status);
+    }
+
+    fn on_position_closed(&mut self, entry_order_id: &OrderId) {
+        self.gate.on_position_closed(entry_order_id);
+        self.detector.on_position_closed(entry_order_id);
+    }
+
+    fn order_requests(&mut self, datetime: &DateTime<Utc>) -> Vec<OrderRequest> {
+        let mut requests = self.gate.order_requests(datetime);
+        requests.extend(self.detector.order_requests(datetime));
+        requests
+    }
+}
+
+fn filter(signals: Vec<Signal>, gate_signals: &[Signal]) -> Vec<Signal> {
+    signals.into_iter()
+        .filter(|signal| gate_signals.iter().any(|gate_signal| signal.direction() == gate_signal.direction()))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use self::chrono::prelude::TimeZone;
+    use direction::Direction;
+    use symbol::SymbolId;
+    use signal::detector::{Always, Once};
+
+    #[test]
+    fn detect_signal() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let datetime = Utc.ymd(2016, 1, 3).and_hms(17, 0, 0);
+
+        let mut detector = Filter::new(
+            Box::new(Always::new(symbol_id.clone(), Direction::Long)),
+            Box::new(Once::new(symbol_id.clone(), Direction::Long))
+        );
+        assert!(detector.detect_signal(&datetime).unwrap().is_some());
+        assert_eq!(detector.detect_signal(&datetime).unwrap(), None);
+
+        let mut detector = Filter::new(
+            Box::new(Always::new(symbol_id.clone(), Direction::Long)),
+            Box::new(Always::new(symbol_id.clone(), Direction::Short))
+        );
+        assert_eq!(detector.detect_signal(&datetime).unwrap(), None);
+    }
+}
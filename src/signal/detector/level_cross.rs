@@ -0,0 +1,92 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc};
+use std::mem;
+use direction::Direction;
+use indicator::{Indicator, Rsi};
+use ohlcv::TimeSlice;
+use signal::Signal;
+use signal::detector::{DetectSignal, DetectSignalError, Cross};
+use symbol::SymbolId;
+
+/// Detect a signal when an oscillator of the close crosses a constant level
+pub struct LevelCross {
+    symbol_id: SymbolId,
+    direction: Direction,
+    oscillator: Box<Indicator>,
+    level: f64,
+    cross: Cross,
+    previous: Option<f64>,
+    crossed: bool
+}
+
+impl LevelCross {
+    pub fn new(symbol_id: SymbolId, direction: Direction, oscillator: Box<Indicator>, level: f64, cross: Cross) -> LevelCross {
+        LevelCross {
+            symbol_id,
+            direction,
+            oscillator,
+            level,
+            cross,
+            previous: None,
+            crossed: false
+        }
+    }
+
+    /// RSI crossing the level upwards for a long signal, such as 30, and downwards for a short
+    /// signal, such as 70
+    pub fn rsi(symbol_id: SymbolId, direction: Direction, period: usize, level: f64) -> LevelCross {
+        let cross = match direction {
+            Direction::Long => Cross::Above,
+            Direction::Short => Cross::Below
+        };
+        LevelCross::new(symbol_id, direction, Box::new(Rsi::new(period)), level, cross)
+    }
+
+    pub fn level(&self) -> f64 {
+        self.level
+    }
+}
+
+impl DetectSignal for LevelCross {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        if mem::replace(&mut self.crossed, false) {
+            Ok(Some(Signal::new(self.symbol_id.clone(), self.direction, *datetime, String::from("level cross"))))
+        }
+        else {
+            Ok(None)
+        }
+    }
+
+    fn on_bar(&mut self, time_slice: &TimeSlice) {
+        self.crossed = false;
+        let close = match time_slice.ohlcv(&self.symbol_id) {
+            Some(ohlcv) => ohlcv.close(),
+            None => return
+        };
+        if let Some(value) = self.oscillator.update(close) {
+            if let Some(previous) = self.previous {
+                self.crossed = self.cross.crossed((previous, self.level), (value, self.level));
+            }
+            self.previous = Some(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use signal::detector::test_time_slice;
+
+    #[test]
+    fn detect_signal() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let mut detector = LevelCross::rsi(symbol_id.clone(), Direction::Long, 2, 30.);
+        // the RSI is 0, 0 and then 50
+        let signals = vec![3., 2., 1., 2.].into_iter().enumerate().map(|(minute, close)| {
+            let time_slice = test_time_slice(&symbol_id, minute as u32, close, close, close, close);
+            detector.on_bar(&time_slice);
+            detector.detect_signal(time_slice.datetime()).unwrap().is_some()
+        }).collect::<Vec<bool>>();
+        assert_eq!(signals, vec![false, false, false, true]);
+    }
+}
@@ -0,0 +1,247 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc};
+use std::collections::HashSet;
+use ohlcv::TimeSlice;
+use order::{Order, OrderId, OrderStatus, OrderRequest};
+use signal::Signal;
+use signal::detector::{DetectSignal, DetectSignalError};
+
+/// Orders of the signal of an entry
+#[derive(Debug)]
+struct Entry {
+    signal: Signal,
+    /// Orders not filled, cancelled or rejected yet
+    pending: HashSet<OrderId>,
+    /// Filled orders whose position isn't closed yet
+    open: HashSet<OrderId>,
+    updated: bool,
+    /// Whether the signal was detected since the last bar, so that new orders are matched to it
+    current: bool
+}
+
+impl Entry {
+    fn new(signal: Signal) -> Entry {
+        Entry { signal, pending: HashSet::new(), open: HashSet::new(), updated: false, current: true }
+    }
+
+    fn contains(&self, order_id: &OrderId) -> bool {
+        self.pending.contains(order_id) || self.open.contains(order_id)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.updated && self.pending.is_empty() && self.open.is_empty()
+    }
+}
+
+/// Suppress the signals of an entry detector while `max` of its entries are pending or open
+///
+/// Each signal is an entry. The new orders of the strategy are matched to the entries of the
+/// signals detected since the last bar, by symbol, direction and signal metadata. An entry is
+/// pending until all its orders are filled, cancelled or rejected, and open until the positions
+/// of its filled orders are closed. Entries without any order by the next bar are released.
+pub struct MaxConcurrency {
+    detector: Box<DetectSignal>,
+    max: usize,
+    entries: Vec<Entry>
+}
+
+impl MaxConcurrency {
+    pub fn new(detector: Box<DetectSignal>, max: usize) -> MaxConcurrency {
+        MaxConcurrency {
+            detector,
+            max,
+            entries: vec![]
+        }
+    }
+
+    /// Number of pending and open entries
+    pub fn entries(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// The signals which don't exceed the maximum number of entries, each one becomes an entry
+    fn limit(&mut self, mut signals: Vec<Signal>) -> Vec<Signal> {
+        signals.truncate(self.max.saturating_sub(self.entries()));
+        self.entries.extend(signals.iter().cloned().map(Entry::new));
+        signals
+    }
+
+    /// Index of the entry of the order, matching a new order to a signal of the current bar
+    fn entry_of(&self, order: &Order) -> Option<usize> {
+        if let Some(index) = self.entries.iter().position(|entry| entry.contains(order.id())) {
+            return Some(index);
+        }
+        let candidates = self.entries.iter().enumerate()
+            .filter(|&(_, entry)| {
+                entry.current
+                    && entry.signal.symbol_id() == order.symbol_id()
+                    && entry.signal.direction() == order.direction()
+            })
+            .collect::<Vec<(usize, &Entry)>>();
+        // signals with the same metadata get an order each before getting several
+        let same_signal = |&&(_, entry): &&(usize, &Entry)| order.signal().as_ref() == Some(&entry.signal.metadata());
+        candidates.iter().find(|candidate| same_signal(candidate) && !candidate.1.updated)
+            .or_else(|| candidates.iter().find(same_signal))
+            .or_else(|| candidates.first())
+            .map(|&(index, _)| index)
+    }
+
+    fn update_entry(&mut self, order: &Order, status: &OrderStatus) {
+        let index = match self.entry_of(order) {
+            Some(index) => index,
+            None => return
+        };
+        let closed = {
+            let entry = &mut self.entries[index];
+            entry.updated = true;
+            match *status {
+                OrderStatus::Filled(_) => {
+                    entry.pending.remove(order.id());
+                    entry.open.insert(order.id().clone());
+                },
+                // the position of the partially filled part stays open
+                OrderStatus::Cancelled(_) if !order.executions().is_empty() => {
+                    entry.pending.remove(order.id());
+                    entry.open.insert(order.id().clone());
+                },
+                OrderStatus::Cancelled(_) | OrderStatus::Rejected(_) => {
+                    entry.pending.remove(order.id());
+                },
+                _ => {
+                    entry.pending.insert(order.id().clone());
+                }
+            }
+            entry.is_closed()
+        };
+        if closed {
+            self.entries.remove(index);
+        }
+    }
+}
+
+impl DetectSignal for MaxConcurrency {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        let signal = self.detector.detect_signal(datetime)?;
+        Ok(self.limit(signal.into_iter().collect()).into_iter().next())
+    }
+
+    fn detect_signals(&mut self, datetime: &DateTime<Utc>) -> Result<Vec<Signal>, DetectSignalError> {
+        let signals = self.detector.detect_signals(datetime)?;
+        Ok(self.limit(signals))
+    }
+
+    fn on_start(&mut self) {
+        self.detector.on_start();
+    }
+
+    fn on_bar(&mut self, time_slice: &TimeSlice) {
+        // release the signals which didn't get any order, such as the ones dropped by the policy
+        self.entries.retain(|entry| entry.updated);
+        for entry in &mut self.entries {
+            entry.current = false;
+        }
+        self.detector.on_bar(time_slice);
+    }
+
+    fn on_end(&mut self) {
+        self.detector.on_end();
+    }
+
+    fn on_order_update(&mut self, order: &Order, status: &OrderStatus) {
+        self.update_entry(order, status);
+        self.detector.on_order_update(order, status);
+    }
+
+    fn on_position_closed(&mut self, entry_order_id: &OrderId) {
+        for entry in &mut self.entries {
+            entry.open.remove(entry_order_id);
+        }
+        self.entries.retain(|entry| !entry.is_closed());
+        self.detector.on_position_closed(entry_order_id);
+    }
+
+    fn order_requests(&mut self, datetime: &DateTime<Utc>) -> Vec<OrderRequest> {
+        self.detector.order_requests(datetime)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use self::chrono::prelude::TimeZone;
+    use direction::Direction;
+    use execution::Execution;
+    use order::{OrderBuilder, OrderKind, CancellationReason};
+    use price::Price;
+    use quantity::Quantity;
+    use symbol::SymbolId;
+    use signal::detector::{Always, test_time_slice};
+
+    #[test]
+    fn detect_signal() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let datetime = Utc.ymd(2016, 1, 3).and_hms(17, 0, 0);
+        let mut detector = MaxConcurrency::new(Box::new(Always::new(symbol_id.clone(), Direction::Long)), 2);
+        let first = detector.detect_signal(&datetime).unwrap().unwrap();
+        let second = detector.detect_signal(&datetime).unwrap().unwrap();
+        assert!(first.tags().is_empty());
+        assert!(detector.detect_signal(&datetime).unwrap().is_none());
+
+        let order = |id: &str, signal: &Signal| OrderBuilder::unallocated(OrderKind::MarketOrder, symbol_id.clone(), Direction::Long)
+            .set_id(OrderId::from(id)).set_signal(Some(signal.metadata())).build().unwrap();
+        let filled = order("filled", &first);
+        detector.on_order_update(&filled, &OrderStatus::NotSent);
+        detector.on_order_update(
+            &filled,
+            &OrderStatus::Filled(Execution::new(symbol_id.clone(), Quantity::new(1.), Price::new(1.), datetime))
+        );
+        let cancelled = order("cancelled", &second);
+        detector.on_order_update(&cancelled, &OrderStatus::NotSent);
+        detector.on_order_update(&cancelled, &OrderStatus::Cancelled(CancellationReason::OutdatedOrder));
+        assert_eq!(detector.entries(), 1);
+        assert!(detector.detect_signal(&datetime).unwrap().is_some());
+        assert!(detector.detect_signal(&datetime).unwrap().is_none());
+
+        detector.on_position_closed(filled.id());
+        assert_eq!(detector.entries(), 1);
+    }
+
+    #[test]
+    fn several_orders_per_signal() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let datetime = Utc.ymd(2016, 1, 3).and_hms(17, 0, 0);
+        let mut detector = MaxConcurrency::new(Box::new(Always::new(symbol_id.clone(), Direction::Long)), 1);
+        let signal = detector.detect_signal(&datetime).unwrap().unwrap();
+        let order = |id: &str| OrderBuilder::unallocated(OrderKind::MarketOrder, symbol_id.clone(), Direction::Long)
+            .set_id(OrderId::from(id)).set_signal(Some(signal.metadata())).build().unwrap();
+        let (filled, cancelled) = (order("filled"), order("cancelled"));
+        detector.on_order_update(&filled, &OrderStatus::NotSent);
+        detector.on_order_update(&cancelled, &OrderStatus::NotSent);
+
+        // the entry is pending while one of its orders is
+        detector.on_order_update(&cancelled, &OrderStatus::Cancelled(CancellationReason::OutdatedOrder));
+        assert_eq!(detector.entries(), 1);
+        assert!(detector.detect_signal(&datetime).unwrap().is_none());
+
+        detector.on_order_update(
+            &filled,
+            &OrderStatus::Filled(Execution::new(symbol_id.clone(), Quantity::new(1.), Price::new(1.), datetime))
+        );
+        assert_eq!(detector.entries(), 1);
+        detector.on_position_closed(filled.id());
+        assert_eq!(detector.entries(), 0);
+        assert!(detector.detect_signal(&datetime).unwrap().is_some());
+    }
+
+    #[test]
+    fn release_signals_without_orders() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let datetime = Utc.ymd(2016, 1, 3).and_hms(17, 0, 0);
+        let mut detector = MaxConcurrency::new(Box::new(Always::new(symbol_id.clone(), Direction::Long)), 1);
+        assert!(detector.detect_signal(&datetime).unwrap().is_some());
+        assert!(detector.detect_signal(&datetime).unwrap().is_none());
+        detector.on_bar(&test_time_slice(&symbol_id, 1, 1., 1., 1., 1.));
+        assert_eq!(detector.entries(), 0);
+        assert!(detector.detect_signal(&datetime).unwrap().is_some());
+    }
+}
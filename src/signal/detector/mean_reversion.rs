@@ -0,0 +1,94 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc};
+use std::mem;
+use direction::Direction;
+use indicator::{Indicator, StdDev};
+use ohlcv::TimeSlice;
+use signal::Signal;
+use signal::detector::{DetectSignal, DetectSignalError, Cross};
+use symbol::SymbolId;
+
+/// Detect a signal when the z-score of the close over `period` bars moves beyond the
+/// threshold: below `-threshold` for a long signal, above `threshold` for a short signal
+pub struct MeanReversion {
+    symbol_id: SymbolId,
+    direction: Direction,
+    std_dev: StdDev,
+    threshold: f64,
+    previous: Option<f64>,
+    crossed: bool
+}
+
+impl MeanReversion {
+    pub fn new(symbol_id: SymbolId, direction: Direction, period: usize, threshold: f64) -> MeanReversion {
+        MeanReversion {
+            symbol_id,
+            direction,
+            std_dev: StdDev::new(period),
+            threshold,
+            previous: None,
+            crossed: false
+        }
+    }
+
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Z-score of the last close
+    pub fn z_score(&self) -> Option<f64> {
+        self.previous
+    }
+}
+
+impl DetectSignal for MeanReversion {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        if mem::replace(&mut self.crossed, false) {
+            Ok(Some(Signal::new(self.symbol_id.clone(), self.direction, *datetime, String::from("mean reversion"))))
+        }
+        else {
+            Ok(None)
+        }
+    }
+
+    fn on_bar(&mut self, time_slice: &TimeSlice) {
+        self.crossed = false;
+        let close = match time_slice.ohlcv(&self.symbol_id) {
+            Some(ohlcv) => ohlcv.close(),
+            None => return
+        };
+        let z_score = match (self.std_dev.update(close), self.std_dev.mean()) {
+            (Some(deviation), Some(mean)) if deviation > 0. => (close - mean) / deviation,
+            (Some(_), Some(_)) => 0.,
+            _ => return
+        };
+        let (cross, level) = match self.direction {
+            Direction::Long => (Cross::Below, -self.threshold),
+            Direction::Short => (Cross::Above, self.threshold)
+        };
+        if let Some(previous) = self.previous {
+            self.crossed = cross.crossed((previous, level), (z_score, level));
+        }
+        self.previous = Some(z_score);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use signal::detector::test_time_slice;
+
+    #[test]
+    fn detect_signal() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let mut detector = MeanReversion::new(symbol_id.clone(), Direction::Short, 2, 0.5);
+        // the z-score is 0, 0 and then 1
+        let signals = vec![1., 1., 1., 2.].into_iter().enumerate().map(|(minute, close)| {
+            let time_slice = test_time_slice(&symbol_id, minute as u32, close, close, close, close);
+            detector.on_bar(&time_slice);
+            detector.detect_signal(time_slice.datetime()).unwrap().is_some()
+        }).collect::<Vec<bool>>();
+        assert_eq!(signals, vec![false, false, false, true]);
+        assert_eq!(detector.z_score(), Some(1.));
+    }
+}
@@ -1,12 +1,60 @@
 extern crate chrono;
 
 use signal::Signal;
+use ohlcv::TimeSlice;
+use order::{Order, OrderId, OrderStatus, OrderRequest};
+#[cfg(test)]
+use symbol::SymbolId;
 use self::chrono::prelude::{DateTime, Utc};
 
 mod once;
 pub use signal::detector::once::Once;
 mod always;
 pub use signal::detector::always::Always;
+mod all_of;
+pub use signal::detector::all_of::AllOf;
+mod any_of;
+pub use signal::detector::any_of::AnyOf;
+mod not;
+pub use signal::detector::not::Not;
+mod sequence;
+pub use signal::detector::sequence::Sequence;
+mod filter;
+pub use signal::detector::filter::Filter;
+mod cross;
+pub use signal::detector::cross::Cross;
+mod crossover;
+pub use signal::detector::crossover::Crossover;
+mod band_cross;
+pub use signal::detector::band_cross::BandCross;
+mod level_cross;
+pub use signal::detector::level_cross::LevelCross;
+mod breakout;
+pub use signal::detector::breakout::Breakout;
+mod mean_reversion;
+pub use signal::detector::mean_reversion::MeanReversion;
+mod candlestick;
+pub use signal::detector::candlestick::Candlestick;
+mod replay;
+pub use signal::detector::replay::{Replay, ReplayError};
+mod every_n_bars;
+pub use signal::detector::every_n_bars::EveryNBars;
+mod time_of_day;
+pub use signal::detector::time_of_day::TimeOfDay;
+mod calendar_day;
+pub use signal::detector::calendar_day::{CalendarDay, CalendarPeriod, CalendarPosition};
+mod session;
+pub use signal::detector::session::{Session, SessionEvent};
+mod cron;
+pub use signal::detector::cron::{Cron, ParseCronError};
+mod cooldown;
+pub use signal::detector::cooldown::{Cooldown, CooldownPeriod};
+mod on_change;
+pub use signal::detector::on_change::OnChange;
+mod max_concurrency;
+pub use signal::detector::max_concurrency::MaxConcurrency;
+mod rule;
+pub use signal::detector::rule::Rule;
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum DetectSignalError {
@@ -14,6 +62,143 @@ pub enum DetectSignalError {
 }
 
 pub trait DetectSignal {
-    fn detect_signal(&self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError>;
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError>;
+
+    /// Detect all signals at the given datetime, such as the legs of a pair; by default the
+    /// single signal of `detect_signal`
+    fn detect_signals(&mut self, datetime: &DateTime<Utc>) -> Result<Vec<Signal>, DetectSignalError> {
+        Ok(self.detect_signal(datetime)?.into_iter().collect())
+    }
+
+    /// Called once before the first time slice of the backtest, or when an exit strategy is
+    /// created
+    fn on_start(&mut self) {}
+
+    /// Called with every time slice of the backtest, before signals are detected at its datetime
+    fn on_bar(&mut self, _time_slice: &TimeSlice) {}
+
+    /// Called once after the last time slice of the backtest
+    fn on_end(&mut self) {}
+
+    /// Called when an order created from a signal of this detector is added to the portfolio,
+    /// partially filled, filled, cancelled or rejected
+    fn on_order_update(&mut self, _order: &Order, _status: &OrderStatus) {}
+
+    /// Cancel and modify requests about the orders of this detector, called after signals are
+    /// detected at the given datetime
+    fn order_requests(&mut self, _datetime: &DateTime<Utc>) -> Vec<OrderRequest> {
+        vec![]
+    }
+
+    /// Called on an entry detector when an exit order of one of its entry orders is filled
+    fn on_position_closed(&mut self, _entry_order_id: &OrderId) {}
 }
 
+/// Time slice with a single traded bar of the symbol at the given minute
+#[cfg(test)]
+pub fn test_time_slice(symbol_id: &SymbolId, minute: u32, open: f64, high: f64, low: f64, close: f64) -> TimeSlice {
+    use std::collections::BTreeMap;
+    use self::chrono::prelude::TimeZone;
+    use ohlcv::{Ohlcv, SliceBar};
+    use quantity::Quantity;
+
+    let datetime = Utc.ymd(2016, 1, 3).and_hms(17, minute, 0);
+    let mut bars = BTreeMap::new();
+    bars.insert(
+        symbol_id.clone(),
+        SliceBar::Traded(Ohlcv::new(symbol_id.clone(), datetime, open, high, low, close, Quantity::new(1.)))
+    );
+    TimeSlice::new(datetime, bars)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use self::chrono::prelude::TimeZone;
+    use direction::Direction;
+    use order::{OrderBuilder, OrderKind};
+
+    /// Detect a signal at every datetime, two with `detect_signals`, recording the hooks called
+    struct RecordHooks(Rc<RefCell<Vec<&'static str>>>);
+
+    impl DetectSignal for RecordHooks {
+        fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+            self.0.borrow_mut().push("detect_signal");
+            Ok(Some(Signal::new(SymbolId::from("eur/usd"), Direction::Long, *datetime, String::from("record"))))
+        }
+
+        fn detect_signals(&mut self, datetime: &DateTime<Utc>) -> Result<Vec<Signal>, DetectSignalError> {
+            self.0.borrow_mut().push("detect_signals");
+            let signal = Signal::new(SymbolId::from("eur/usd"), Direction::Long, *datetime, String::from("record"));
+            Ok(vec![signal.clone(), signal])
+        }
+
+        fn on_start(&mut self) {
+            self.0.borrow_mut().push("on_start");
+        }
+
+        fn on_bar(&mut self, _time_slice: &TimeSlice) {
+            self.0.borrow_mut().push("on_bar");
+        }
+
+        fn on_end(&mut self) {
+            self.0.borrow_mut().push("on_end");
+        }
+
+        fn on_order_update(&mut self, _order: &Order, _status: &OrderStatus) {
+            self.0.borrow_mut().push("on_order_update");
+        }
+
+        fn order_requests(&mut self, _datetime: &DateTime<Utc>) -> Vec<OrderRequest> {
+            self.0.borrow_mut().push("order_requests");
+            vec![]
+        }
+
+        fn on_position_closed(&mut self, _entry_order_id: &OrderId) {
+            self.0.borrow_mut().push("on_position_closed");
+        }
+    }
+
+    #[test]
+    fn wrappers_forward_hooks() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let datetime = Utc.ymd(2016, 1, 3).and_hms(17, 0, 0);
+        let order = OrderBuilder::unallocated(OrderKind::MarketOrder, symbol_id.clone(), Direction::Long)
+            .set_id(OrderId::from("order"))
+            .build().unwrap();
+        let hooks = Rc::new(RefCell::new(vec![]));
+        let record = || Box::new(RecordHooks(hooks.clone()));
+        let wrappers: Vec<Box<DetectSignal>> = vec![
+            Box::new(AllOf::new(vec![record(), record()])),
+            Box::new(AnyOf::new(vec![record(), record()])),
+            Box::new(Not::new(record(), symbol_id.clone(), Direction::Short)),
+            Box::new(Sequence::new(record(), record(), 1)),
+            Box::new(Filter::new(record(), record())),
+            Box::new(Cooldown::new(record(), CooldownPeriod::Bars(1))),
+            Box::new(OnChange::new(record())),
+            Box::new(MaxConcurrency::new(record(), 2))
+        ];
+        let mut expected = vec![
+            "detect_signal", "detect_signals", "on_bar", "on_end", "on_order_update", "on_position_closed",
+            "on_start", "order_requests"
+        ];
+        expected.sort();
+        for mut wrapper in wrappers {
+            wrapper.on_start();
+            wrapper.on_bar(&test_time_slice(&symbol_id, 0, 1., 1., 1., 1.));
+            wrapper.detect_signal(&datetime).unwrap();
+            wrapper.detect_signals(&datetime).unwrap();
+            wrapper.on_order_update(&order, &OrderStatus::NotSent);
+            wrapper.order_requests(&datetime);
+            wrapper.on_position_closed(order.id());
+            wrapper.on_end();
+
+            let mut called = hooks.borrow_mut().drain(..).collect::<Vec<&str>>();
+            called.sort();
+            called.dedup();
+            assert_eq!(called, expected);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc};
+use direction::Direction;
+use ohlcv::TimeSlice;
+use order::{Order, OrderId, OrderStatus, OrderRequest};
+use signal::Signal;
+use signal::detector::{DetectSignal, DetectSignalError};
+use symbol::SymbolId;
+
+/// Detect a signal when the detector doesn't detect any
+pub struct Not {
+    detector: Box<DetectSignal>,
+    symbol_id: SymbolId,
+    direction: Direction
+}
+
+impl Not {
+    pub fn new(detector: Box<DetectSignal>, symbol_id: SymbolId, direction: Direction) -> Not {
+        Not { detector, symbol_id, direction }
+    }
+
+    fn signal(&self, datetime: &DateTime<Utc>) -> Signal {
+        Signal::new(self.symbol_id.clone(), self.direction, *datetime, String::from("not"))
+    }
+}
+
+impl DetectSignal for Not {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        match self.detector.detect_signal(datetime)? {
+            Some(_) => Ok(None),
+            None => Ok(Some(self.signal(datetime)))
+        }
+    }
+
+    fn detect_signals(&mut self, datetime: &DateTime<Utc>) -> Result<Vec<Signal>, DetectSignalError> {
+        if self.detector.detect_signals(datetime)?.is_empty() {
+            Ok(vec![self.signal(datetime)])
+        }
+        else {
+            Ok(vec![])
+        }
+    }
+
+    fn on_start(&mut self) {
+        self.detector.on_start();
+    }
+
+    fn on_bar(&mut self, time_slice: &TimeSlice) {
+        self.detector.on_bar(time_slice);
+    }
+
+    fn on_end(&mut self) {
+        self.detector.on_end();
+    }
+
+    fn on_order_update(&mut self, order: &Order, status: &OrderStatus) {
+        self.detector.on_order_update(order, status);
+    }
+
+    fn on_position_closed(&mut self, entry_order_id: &OrderId) {
+        self.detector.on_position_closed(entry_order_id);
+    }
+
+    fn order_requests(&mut self, datetime: &DateTime<Utc>) -> Vec<OrderRequest> {
+        self.detector.order_requests(datetime)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use self::chrono::prelude::TimeZone;
+    use signal::detector::Once;
+
+    #[test]
+    fn detect_signal() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let mut detector = Not::new(
+            Box::new(Once::new(symbol_id.clone(), Direction::Long)),
+            symbol_id.clone(),
+            Direction::Short
+        );
+        let datetime = Utc.ymd(2016, 1, 3).and_hms(17, 0, 0);
+        assert_eq!(detector.detect_signal(&datetime).unwrap(), None);
+        assert_eq!(
+            detector.detect_signal(&datetime).unwrap(),
+            Some(Signal::new(symbol_id.clone(), Direction::Short, datetime, String::from("not")))
+        );
+    }
+}
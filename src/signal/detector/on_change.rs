@@ -0,0 +1,98 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc};
+use std::mem;
+use direction::Direction;
+use ohlcv::TimeSlice;
+use order::{Order, OrderId, OrderStatus, OrderRequest};
+use signal::Signal;
+use signal::detector::{DetectSignal, DetectSignalError};
+
+/// Detect the signals of a detector only when its state changes: when it starts detecting
+/// signals, or when their direction changes
+pub struct OnChange {
+    detector: Box<DetectSignal>,
+    previous_direction: Option<Direction>
+}
+
+impl OnChange {
+    pub fn new(detector: Box<DetectSignal>) -> OnChange {
+        OnChange { detector, previous_direction: None }
+    }
+
+    /// The signals when the direction of the first one changes
+    fn on_change(&mut self, signals: Vec<Signal>) -> Vec<Signal> {
+        let direction = signals.first().map(|signal| *signal.direction());
+        let previous_direction = mem::replace(&mut self.previous_direction, direction);
+        if direction.is_some() && direction != previous_direction {
+            signals
+        }
+        else {
+            vec![]
+        }
+    }
+}
+
+impl DetectSignal for OnChange {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        let signal = self.detector.detect_signal(datetime)?;
+        Ok(self.on_change(signal.into_iter().collect()).into_iter().next())
+    }
+
+    fn detect_signals(&mut self, datetime: &DateTime<Utc>) -> Result<Vec<Signal>, DetectSignalError> {
+        let signals = self.detector.detect_signals(datetime)?;
+        Ok(self.on_change(signals))
+    }
+
+    fn on_start(&mut self) {
+        self.detector.on_start();
+    }
+
+    fn on_bar(&mut self, time_slice: &TimeSlice) {
+        self.detector.on_bar(time_slice);
+    }
+
+    fn on_end(&mut self) {
+        self.detector.on_end();
+    }
+
+    fn on_order_update(&mut self, order: &Order, status: &OrderStatus) {
+        self.detector.on_order_update(order, status);
+    }
+
+    fn on_position_closed(&mut self, entry_order_id: &OrderId) {
+        self.detector.on_position_closed(entry_order_id);
+    }
+
+    fn order_requests(&mut self, datetime: &DateTime<Utc>) -> Vec<OrderRequest> {
+        self.detector.order_requests(datetime)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use self::chrono::prelude::TimeZone;
+    use symbol::SymbolId;
+    use signal::detector::{Always, EveryNBars, Not};
+
+    #[test]
+    fn detect_signal() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let mut detector = OnChange::new(Box::new(Always::new(symbol_id.clone(), Direction::Long)));
+        let signals = (0..3).map(|minute| {
+            detector.detect_signal(&Utc.ymd(2016, 1, 3).and_hms(17, minute, 0)).unwrap().is_some()
+        }).collect::<Vec<bool>>();
+        assert_eq!(signals, vec![true, false, false]);
+
+        // detects a signal for two bars out of three
+        let mut detector = OnChange::new(Box::new(Not::new(
+            Box::new(EveryNBars::new(symbol_id.clone(), Direction::Long, 3)),
+            symbol_id.clone(),
+            Direction::Long
+        )));
+        let signals = (0..6).map(|minute| {
+            detector.detect_signal(&Utc.ymd(2016, 1, 3).and_hms(17, minute, 0)).unwrap().is_some()
+        }).collect::<Vec<bool>>();
+        assert_eq!(signals, vec![false, true, false, false, true, false]);
+    }
+}
@@ -1,6 +1,5 @@
 extern crate chrono;
 use self::chrono::prelude::{DateTime, Utc};
-use std::cell::Cell;
 use direction::Direction;
 use signal::Signal;
 use signal::detector::{DetectSignal, DetectSignalError};
@@ -9,22 +8,22 @@ use symbol::SymbolId;
 pub struct Once {
     symbol_id: SymbolId,
     direction: Direction,
-    detected: Cell<bool>
+    detected: bool
 }
 
 impl Once {
     pub fn new(symbol_id: SymbolId, direction: Direction) -> Once {
         Once {
-            symbol_id: symbol_id,
-            direction: direction,
-            detected: Cell::new(false)
+            symbol_id,
+            direction,
+            detected: false
         }
     }
 }
 
 impl DetectSignal for Once {
-    fn detect_signal(&self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
-        if self.detected.get() {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        if self.detected {
             Ok(None)
         }
         else {
@@ -34,7 +33,7 @@ impl DetectSignal for Once {
                 datetime.clone(),
                 String::from("once")
             );
-            self.detected.set(true);
+            self.detected = true;
             Ok(Some(signal))
         }
     }
@@ -0,0 +1,185 @@
+extern crate chrono;
+extern crate csv;
+extern crate serde_json;
+use self::chrono::prelude::{DateTime, Utc};
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Read};
+use direction::Direction;
+use signal::Signal;
+use signal::detector::{DetectSignal, DetectSignalError};
+use symbol::SymbolId;
+
+/// Signal precomputed by an external tool, one per line of the replayed file
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+struct SignalRecord {
+    datetime: DateTime<Utc>,
+    symbol: SymbolId,
+    direction: Direction,
+    label: String,
+    #[serde(default)]
+    strength: Option<f64>
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    InvalidCsvRecord(csv::Error),
+    InvalidJsonRecord(serde_json::Error),
+    Io(io::Error)
+}
+
+/// Replay signals read from a file, with the columns `datetime` (RFC 3339), `symbol`,
+/// `direction` (`Long` or `Short`), `label` and an optional `strength`
+///
+/// Each signal is detected at the first datetime at or after its own datetime, together with
+/// all the other signals due at that datetime. Signals dated before the first datetime the
+/// detector is run at are stale, they are dropped instead of being detected.
+pub struct Replay {
+    signals: VecDeque<Signal>,
+    stale: Vec<Signal>,
+    started: bool
+}
+
+impl Replay {
+
+    pub fn new(mut signals: Vec<Signal>) -> Replay {
+        signals.sort_by_key(|signal| *signal.datetime());
+        Replay {
+            signals: signals.into_iter().collect(),
+            stale: vec![],
+            started: false
+        }
+    }
+
+    pub fn from_csv<R: Read>(mut reader: csv::Reader<R>) -> Result<Replay, ReplayError> {
+        let mut signals = vec![];
+        for record in reader.deserialize::<SignalRecord>() {
+            signals.push(to_signal(record.map_err(ReplayError::InvalidCsvRecord)?));
+        }
+        Ok(Replay::new(signals))
+    }
+
+    /// Read one JSON object per line, skipping blank lines
+    pub fn from_json_lines<R: BufRead>(reader: R) -> Result<Replay, ReplayError> {
+        let mut signals = vec![];
+        for line in reader.lines() {
+            let line = line.map_err(ReplayError::Io)?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record = serde_json::from_str::<SignalRecord>(&line).map_err(ReplayError::InvalidJsonRecord)?;
+            signals.push(to_signal(record));
+        }
+        Ok(Replay::new(signals))
+    }
+
+    /// Number of signals not detected yet
+    pub fn remaining(&self) -> usize {
+        self.signals.len()
+    }
+
+    /// Signals dropped because they are dated before the first datetime the detector was run at
+    pub fn stale(&self) -> &Vec<Signal> {
+        &self.stale
+    }
+
+    fn drop_stale(&mut self, datetime: &DateTime<Utc>) {
+        if self.started {
+            return;
+        }
+        self.started = true;
+        while self.signals.front().map_or(false, |signal| signal.datetime() < datetime) {
+            self.stale.extend(self.signals.pop_front());
+        }
+    }
+
+    fn is_due(&self, datetime: &DateTime<Utc>) -> bool {
+        self.signals.front().map_or(false, |signal| signal.datetime() <= datetime)
+    }
+
+}
+
+fn to_signal(record: SignalRecord) -> Signal {
+    Signal::new(record.symbol, record.direction, record.datetime, record.label)
+        .set_strength(record.strength)
+}
+
+impl DetectSignal for Replay {
+    /// Detect the first due signal, the others stay due
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        self.drop_stale(datetime);
+        if self.is_due(datetime) {
+            Ok(self.signals.pop_front())
+        }
+        else {
+            Ok(None)
+        }
+    }
+
+    fn detect_signals(&mut self, datetime: &DateTime<Utc>) -> Result<Vec<Signal>, DetectSignalError> {
+        self.drop_stale(datetime);
+        let mut signals = vec![];
+        while self.is_due(datetime) {
+            signals.extend(self.signals.pop_front());
+        }
+        Ok(signals)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use self::chrono::prelude::TimeZone;
+
+    #[test]
+    fn from_csv() {
+        let data = "datetime,symbol,direction,label,strength
+2016-01-03T17:01:00Z,eur/usd,Short,model b,0.8
+2016-01-03T17:00:30Z,eur/usd,Long,model a,";
+        let mut detector = Replay::from_csv(csv::Reader::from_reader(data.as_bytes())).unwrap();
+        let datetime = Utc.ymd(2016, 1, 3).and_hms(17, 0, 0);
+        assert_eq!(detector.detect_signal(&datetime).unwrap(), None);
+
+        let datetime = Utc.ymd(2016, 1, 3).and_hms(17, 1, 0);
+        assert_eq!(
+            detector.detect_signal(&datetime).unwrap(),
+            Some(Signal::new(SymbolId::from("eur/usd"), Direction::Long, Utc.ymd(2016, 1, 3).and_hms(17, 0, 30), String::from("model a")))
+        );
+        assert_eq!(
+            detector.detect_signal(&datetime).unwrap().and_then(|signal| signal.strength()),
+            Some(0.8)
+        );
+        assert_eq!(detector.remaining(), 0);
+    }
+
+    #[test]
+    fn detect_signals() {
+        let datetime = Utc.ymd(2016, 1, 3).and_hms(17, 0, 0);
+        let signal = |symbol: &str, minute: u32| Signal::new(
+            SymbolId::from(symbol), Direction::Long, Utc.ymd(2016, 1, 3).and_hms(17, minute, 0), String::from("replay")
+        );
+        let mut detector = Replay::new(vec![
+            signal("eur/usd", 1), signal("usd/jpy", 1), signal("gbp/usd", 1), signal("eur/usd", 2),
+            // before the first bar
+            Signal::new(SymbolId::from("eur/usd"), Direction::Short, Utc.ymd(2016, 1, 2).and_hms(17, 0, 0), String::from("stale"))
+        ]);
+        assert!(detector.detect_signals(&datetime).unwrap().is_empty());
+        assert_eq!(detector.stale().len(), 1);
+
+        // the signals sharing a datetime are all detected on the same bar
+        let signals = detector.detect_signals(&Utc.ymd(2016, 1, 3).and_hms(17, 1, 0)).unwrap();
+        assert_eq!(signals.len(), 3);
+        assert!(signals.iter().all(|signal| signal.datetime() == &Utc.ymd(2016, 1, 3).and_hms(17, 1, 0)));
+        assert_eq!(detector.detect_signals(&Utc.ymd(2016, 1, 3).and_hms(17, 3, 0)).unwrap(), vec![signal("eur/usd", 2)]);
+        assert_eq!(detector.remaining(), 0);
+    }
+
+    #[test]
+    fn from_json_lines() {
+        let data = r#"{"datetime": "2016-01-03T17:00:00Z", "symbol": "eur/usd", "direction": "Long", "label": "model a"}
+
+{"datetime": "2016-01-03T17:01:00Z", "symbol": "eur/usd", "direction": "Short", "label": "model b", "strength": 0.5}"#;
+        let detector = Replay::from_json_lines(data.as_bytes()).unwrap();
+        assert_eq!(detector.remaining(), 2);
+        assert!(Replay::from_json_lines("{\"datetime\": \"erroneous\"}".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,80 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc};
+use std::mem;
+use direction::Direction;
+use ohlcv::TimeSlice;
+use signal::Signal;
+use signal::detector::{DetectSignal, DetectSignalError};
+use signal::expression::{self, CompiledExpression, ExpressionError, Parameters};
+use symbol::SymbolId;
+
+/// Detect a signal labelled with the expression on every bar of the symbol where the expression
+/// holds
+pub struct Rule {
+    symbol_id: SymbolId,
+    direction: Direction,
+    text: String,
+    expression: CompiledExpression,
+    holds: bool
+}
+
+impl Rule {
+    pub fn new(symbol_id: SymbolId, direction: Direction, text: &str, parameters: &Parameters) -> Result<Rule, ExpressionError> {
+        Ok(Rule {
+            symbol_id,
+            direction,
+            text: String::from(text),
+            expression: expression::compile(text, parameters)?,
+            holds: false
+        })
+    }
+
+    pub fn text(&self) -> &String {
+        &self.text
+    }
+}
+
+impl DetectSignal for Rule {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        if mem::replace(&mut self.holds, false) {
+            Ok(Some(Signal::new(self.symbol_id.clone(), self.direction, *datetime, self.text.clone())))
+        }
+        else {
+            Ok(None)
+        }
+    }
+
+    fn on_bar(&mut self, time_slice: &TimeSlice) {
+        let holds = match time_slice.ohlcv(&self.symbol_id) {
+            Some(ohlcv) => self.expression.update(ohlcv),
+            None => false
+        };
+        self.holds = holds;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use signal::detector::test_time_slice;
+
+    #[test]
+    fn detect_signal() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let mut parameters = Parameters::new();
+        parameters.insert(String::from("bars"), 2.);
+        let mut detector = Rule::new(symbol_id.clone(), Direction::Long, "close > 2 and sma(close, bars) > 2", &parameters).unwrap();
+        let time_slice = test_time_slice(&symbol_id, 0, 2., 2., 1., 2.);
+        detector.on_bar(&time_slice);
+        assert_eq!(detector.detect_signal(time_slice.datetime()).unwrap(), None);
+
+        let time_slice = test_time_slice(&symbol_id, 1, 2.5, 3., 1., 2.5);
+        detector.on_bar(&time_slice);
+        assert_eq!(
+            detector.detect_signal(time_slice.datetime()).unwrap(),
+            Some(Signal::new(symbol_id.clone(), Direction::Long, *time_slice.datetime(), String::from("close > 2 and sma(close, bars) > 2")))
+        );
+
+        assert!(Rule::new(symbol_id.clone(), Direction::Long, "close >", &parameters).is_err());
+    }
+}
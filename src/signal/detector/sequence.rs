@@ -0,0 +1,136 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc};
+use ohlcv::TimeSlice;
+use order::{Order, OrderId, OrderStatus, OrderRequest};
+use signal::Signal;
+use signal::detector::{DetectSignal, DetectSignalError};
+
+/// Detect the signal of the second detector when it happens at most `bars` detections after a
+/// signal of the first detector
+pub struct Sequence {
+    first: Box<DetectSignal>,
+    second: Box<DetectSignal>,
+    bars: usize,
+    bars_since_first: Option<usize>
+}
+
+impl Sequence {
+    pub fn new(first: Box<DetectSignal>, second: Box<DetectSignal>, bars: usize) -> Sequence {
+        Sequence {
+            first,
+            second,
+            bars,
+            bars_since_first: None
+        }
+    }
+
+    /// Signals of the second detector which follow a signal of the first one
+    fn follow(&mut self, first_detected: bool, second_signals: Vec<Signal>) -> Vec<Signal> {
+        let bars_since_first = self.bars_since_first
+            .map(|bars| bars + 1)
+            .and_then(|bars| if bars <= self.bars { Some(bars) } else { None });
+
+        if bars_since_first.is_some() && !second_signals.is_empty() {
+            self.bars_since_first = None;
+            return second_signals;
+        }
+
+        self.bars_since_first = if first_detected { Some(0) } else { bars_since_first };
+        vec![]
+    }
+}
+
+impl DetectSignal for Sequence {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        let first_signal = self.first.detect_signal(datetime)?;
+        let second_signal = self.second.detect_signal(datetime)?;
+        Ok(self.follow(first_signal.is_some(), second_signal.into_iter().collect()).into_iter().next())
+    }
+
+    fn detect_signals(&mut self, datetime: &DateTime<Utc>) -> Result<Vec<Signal>, DetectSignalError> {
+        let first_signals = self.first.detect_signals(datetime)?;
+        let second_signals = self.second.detect_signals(datetime)?;
+        Ok(self.follow(!first_signals.is_empty(), second_signals))
+    }
+
+    fn on_start(&mut self) {
+        self.first.on_start();
+        self.second.on_start();
+    }
+
+    fn on_bar(&mut self, time_slice: &TimeSlice) {
+        self.first.on_bar(time_slice);
+        self.second.on_bar(time_slice);
+    }
+
+    fn on_end(&mut self) {
+        self.first.on_end();
+        self.second.on_end();
+    }
+
+    fn on_order_update(&mut self, order: &Order, status: &OrderStatus) {
+        self.first.on_order_update(order, status);
+        self.second.on_order_update(order, status);
+    }
+
+    fn on_position_closed(&mut self, entry_order_id: &OrderId) {
+        self.first.on_position_closed(entry_order_id);
+        self.second.on_position_closed(entry_order_id);
+    }
+
+    fn order_requests(&mut self, datetime: &DateTime<Utc>) -> Vec<OrderRequest> {
+        let mut requests = self.first.order_requests(datetime);
+        requests.extend(self.second.order_requests(datetime));
+        requests
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use self::chrono::prelude::TimeZone;
+    use direction::Direction;
+    use symbol::SymbolId;
+    use signal::detector::{Always, Once};
+
+    /// Detect a signal on the n-th detection only
+    struct NthDetection { n: usize, detections: usize }
+    impl DetectSignal for NthDetection {
+        fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+            self.detections += 1;
+            if self.detections != self.n {
+                return Ok(None);
+            }
+            Ok(Some(Signal::new(SymbolId::from("eur/usd"), Direction::Long, *datetime, String::from("nth"))))
+        }
+    }
+
+    #[test]
+    fn detect_signal_within_bars() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let mut detector = Sequence::new(
+            Box::new(Once::new(symbol_id.clone(), Direction::Long)),
+            Box::new(Always::new(symbol_id.clone(), Direction::Long)),
+            2
+        );
+        let datetime = Utc.ymd(2016, 1, 3).and_hms(17, 0, 0);
+        // the second detector must detect after the first one
+        assert_eq!(detector.detect_signal(&datetime).unwrap(), None);
+        assert!(detector.detect_signal(&datetime).unwrap().is_some());
+        assert_eq!(detector.detect_signal(&datetime).unwrap(), None);
+    }
+
+    #[test]
+    fn detect_signal_too_late() {
+        let symbol_id = SymbolId::from("eur/usd");
+        let mut detector = Sequence::new(
+            Box::new(Once::new(symbol_id.clone(), Direction::Long)),
+            Box::new(NthDetection { n: 4, detections: 0 }),
+            2
+        );
+        let datetime = Utc.ymd(2016, 1, 3).and_hms(17, 0, 0);
+        for _ in 0..5 {
+            assert_eq!(detector.detect_signal(&datetime).unwrap(), None);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc, NaiveDate, NaiveTime};
+use self::chrono::Duration;
+use direction::Direction;
+use signal::Signal;
+use signal::detector::{DetectSignal, DetectSignalError};
+use symbol::SymbolId;
+
+/// Moment of the trading session to detect
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum SessionEvent {
+    /// First bar of the session
+    Open,
+    /// First bar at or after the given duration before the close
+    Close(Duration)
+}
+
+/// Detect a signal once a day at the open or before the close of a session, ignoring the bars
+/// outside of the session
+///
+/// A session closing at or before its open time crosses midnight, it closes on the day after its
+/// open, e.g. from 18:00 to 17:00 for futures.
+pub struct Session {
+    symbol_id: SymbolId,
+    direction: Direction,
+    open: NaiveTime,
+    close: NaiveTime,
+    event: SessionEvent,
+    last_detection: Option<NaiveDate>
+}
+
+impl Session {
+    pub fn new(symbol_id: SymbolId, direction: Direction, open: NaiveTime, close: NaiveTime, event: SessionEvent) -> Session {
+        Session { symbol_id, direction, open, close, event, last_detection: None }
+    }
+
+    pub fn event(&self) -> &SessionEvent {
+        &self.event
+    }
+
+    /// Duration of the session, a full day when it closes at its open time
+    fn length(&self) -> Duration {
+        let length = self.close.signed_duration_since(self.open);
+        if length <= Duration::zero() { length + Duration::days(1) } else { length }
+    }
+}
+
+impl DetectSignal for Session {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        // the session of a datetime is the last one opened before it
+        let since_open = datetime.time().signed_duration_since(self.open);
+        let since_open = if since_open < Duration::zero() { since_open + Duration::days(1) } else { since_open };
+        let date = (*datetime - since_open).date_naive();
+        let length = self.length();
+        let (start, label) = match self.event {
+            SessionEvent::Open => (Duration::zero(), "session open"),
+            SessionEvent::Close(before) => ((length - before).max(Duration::zero()), "session close")
+        };
+        if since_open < start || since_open >= length || self.last_detection == Some(date) {
+            return Ok(None);
+        }
+        self.last_detection = Some(date);
+        Ok(Some(Signal::new(self.symbol_id.clone(), self.direction, *datetime, String::from(label))))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use self::chrono::prelude::TimeZone;
+
+    #[test]
+    fn detect_signal() {
+        let datetimes = [
+            Utc.ymd(2016, 1, 4).and_hms(9, 0, 0),
+            Utc.ymd(2016, 1, 4).and_hms(9, 30, 0),
+            Utc.ymd(2016, 1, 4).and_hms(15, 55, 0),
+            Utc.ymd(2016, 1, 4).and_hms(16, 0, 0)
+        ];
+        let mut open = Session::new(
+            SymbolId::from("spy"), Direction::Long,
+            NaiveTime::from_hms(9, 30, 0), NaiveTime::from_hms(16, 0, 0), SessionEvent::Open
+        );
+        let signals = datetimes.iter().map(|datetime| open.detect_signal(datetime).unwrap().is_some()).collect::<Vec<bool>>();
+        assert_eq!(signals, vec![false, true, false, false]);
+
+        let mut close = Session::new(
+            SymbolId::from("spy"), Direction::Short,
+            NaiveTime::from_hms(9, 30, 0), NaiveTime::from_hms(16, 0, 0), SessionEvent::Close(Duration::minutes(5))
+        );
+        let signals = datetimes.iter().map(|datetime| close.detect_signal(datetime).unwrap().is_some()).collect::<Vec<bool>>();
+        assert_eq!(signals, vec![false, false, true, false]);
+    }
+
+    #[test]
+    fn detect_signal_overnight() {
+        let datetimes = [
+            Utc.ymd(2016, 1, 3).and_hms(17, 30, 0),
+            Utc.ymd(2016, 1, 3).and_hms(18, 0, 0),
+            Utc.ymd(2016, 1, 4).and_hms(9, 0, 0),
+            Utc.ymd(2016, 1, 4).and_hms(16, 55, 0),
+            Utc.ymd(2016, 1, 4).and_hms(17, 0, 0),
+            Utc.ymd(2016, 1, 4).and_hms(18, 0, 0)
+        ];
+        let mut open = Session::new(
+            SymbolId::from("es"), Direction::Long,
+            NaiveTime::from_hms(18, 0, 0), NaiveTime::from_hms(17, 0, 0), SessionEvent::Open
+        );
+        let signals = datetimes.iter().map(|datetime| open.detect_signal(datetime).unwrap().is_some()).collect::<Vec<bool>>();
+        assert_eq!(signals, vec![false, true, false, false, false, true]);
+
+        let mut close = Session::new(
+            SymbolId::from("es"), Direction::Short,
+            NaiveTime::from_hms(18, 0, 0), NaiveTime::from_hms(17, 0, 0), SessionEvent::Close(Duration::minutes(5))
+        );
+        let signals = datetimes.iter().map(|datetime| close.detect_signal(datetime).unwrap().is_some()).collect::<Vec<bool>>();
+        assert_eq!(signals, vec![false, false, false, true, false, false]);
+    }
+}
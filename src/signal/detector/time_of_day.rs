@@ -0,0 +1,53 @@
+extern crate chrono;
+use self::chrono::prelude::{DateTime, Utc, NaiveDate, NaiveTime};
+use direction::Direction;
+use signal::Signal;
+use signal::detector::{DetectSignal, DetectSignalError};
+use symbol::SymbolId;
+
+/// Detect a signal on the first bar at or after a time of the day, once a day
+pub struct TimeOfDay {
+    symbol_id: SymbolId,
+    direction: Direction,
+    time: NaiveTime,
+    last_detection: Option<NaiveDate>
+}
+
+impl TimeOfDay {
+    pub fn new(symbol_id: SymbolId, direction: Direction, time: NaiveTime) -> TimeOfDay {
+        TimeOfDay { symbol_id, direction, time, last_detection: None }
+    }
+
+    pub fn time(&self) -> &NaiveTime {
+        &self.time
+    }
+}
+
+impl DetectSignal for TimeOfDay {
+    fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        let date = datetime.date_naive();
+        if datetime.time() < self.time || self.last_detection == Some(date) {
+            return Ok(None);
+        }
+        self.last_detection = Some(date);
+        Ok(Some(Signal::new(self.symbol_id.clone(), self.direction, *datetime, String::from("time of day"))))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use self::chrono::prelude::TimeZone;
+
+    #[test]
+    fn detect_signal() {
+        let mut detector = TimeOfDay::new(SymbolId::from("eur/usd"), Direction::Long, NaiveTime::from_hms(17, 1, 30));
+        let signals = [
+            Utc.ymd(2016, 1, 3).and_hms(17, 1, 0),
+            Utc.ymd(2016, 1, 3).and_hms(17, 2, 0),
+            Utc.ymd(2016, 1, 3).and_hms(17, 3, 0),
+            Utc.ymd(2016, 1, 4).and_hms(17, 2, 0)
+        ].iter().map(|datetime| detector.detect_signal(datetime).unwrap().is_some()).collect::<Vec<bool>>();
+        assert_eq!(signals, vec![false, true, false, true]);
+    }
+}
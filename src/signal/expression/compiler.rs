@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+use indicator::{Indicator, Atr, Sma, Ema, Rsi, StdDev, Highest, Lowest, BollingerBand, Band};
+use ohlcv::Ohlcv;
+use signal::detector::Cross;
+use signal::expression::ExpressionError;
+use signal::expression::parser::{Expression, UnaryOperator, BinaryOperator};
+
+/// Named values substituted for identifiers of an expression, such as indicator periods
+pub type Parameters = HashMap<String, f64>;
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+enum Type {
+    Number,
+    Boolean
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+enum Field {
+    Open,
+    High,
+    Low,
+    Close,
+    Volume
+}
+
+/// Compiled expression node, holding the state of its indicators
+enum Node {
+    Constant(f64),
+    Field(Field),
+    Indicator(Box<Indicator>, Box<Node>),
+    Atr(Atr),
+    Cross(Cross, Box<Node>, Box<Node>, Option<(f64, f64)>),
+    Negate(Box<Node>),
+    Not(Box<Node>),
+    Binary(BinaryOperator, Box<Node>, Box<Node>)
+}
+
+/// Value of a node for one bar, `None` while an indicator is not ready
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Value {
+    Number(Option<f64>),
+    Boolean(bool)
+}
+
+impl Value {
+
+    fn number(self) -> Option<f64> {
+        match self {
+            Value::Number(number) => number,
+            Value::Boolean(_) => None
+        }
+    }
+
+    fn boolean(self) -> bool {
+        match self {
+            Value::Boolean(boolean) => boolean,
+            Value::Number(_) => false
+        }
+    }
+
+}
+
+impl Node {
+
+    /// Every child is evaluated on every bar so that indicators see every value
+    fn evaluate(&mut self, ohlcv: &Ohlcv) -> Value {
+        match *self {
+            Node::Constant(value) => Value::Number(Some(value)),
+            Node::Field(field) => Value::Number(Some(match field {
+                Field::Open => ohlcv.open(),
+                Field::High => ohlcv.high(),
+                Field::Low => ohlcv.low(),
+                Field::Close => ohlcv.close(),
+                Field::Volume => ohlcv.volume().value()
+            })),
+            Node::Indicator(ref mut indicator, ref mut series) => {
+                Value::Number(series.evaluate(ohlcv).number().and_then(|value| indicator.update(value)))
+            },
+            Node::Atr(ref mut atr) => Value::Number(atr.update(ohlcv)),
+            Node::Cross(cross, ref mut a, ref mut b, ref mut previous) => {
+                let current = match (a.evaluate(ohlcv).number(), b.evaluate(ohlcv).number()) {
+                    (Some(a), Some(b)) => Some((a, b)),
+                    _ => None
+                };
+                let crossed = match (*previous, current) {
+                    (Some(previous), Some(current)) => cross.crossed(previous, current),
+                    _ => false
+                };
+                *previous = current;
+                Value::Boolean(crossed)
+            },
+            Node::Negate(ref mut node) => Value::Number(node.evaluate(ohlcv).number().map(|value| -value)),
+            Node::Not(ref mut node) => Value::Boolean(!node.evaluate(ohlcv).boolean()),
+            Node::Binary(operator, ref mut left, ref mut right) => {
+                let left = left.evaluate(ohlcv);
+                let right = right.evaluate(ohlcv);
+                match operator {
+                    BinaryOperator::And => Value::Boolean(left.boolean() && right.boolean()),
+                    BinaryOperator::Or => Value::Boolean(left.boolean() || right.boolean()),
+                    _ => match (left.number(), right.number()) {
+                        (Some(left), Some(right)) => apply(operator, left, right),
+                        _ => match operator {
+                            BinaryOperator::Add | BinaryOperator::Subtract
+                                | BinaryOperator::Multiply | BinaryOperator::Divide => Value::Number(None),
+                            _ => Value::Boolean(false)
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+}
+
+fn apply(operator: BinaryOperator, left: f64, right: f64) -> Value {
+    match operator {
+        BinaryOperator::Add => Value::Number(Some(left + right)),
+        BinaryOperator::Subtract => Value::Number(Some(left - right)),
+        BinaryOperator::Multiply => Value::Number(Some(left * right)),
+        BinaryOperator::Divide => Value::Number(if right != 0. { Some(left / right) } else { None }),
+        BinaryOperator::Less => Value::Boolean(left < right),
+        BinaryOperator::LessEqual => Value::Boolean(left <= right),
+        BinaryOperator::Greater => Value::Boolean(left > right),
+        BinaryOperator::GreaterEqual => Value::Boolean(left >= right),
+        BinaryOperator::Equal => Value::Boolean(left == right),
+        BinaryOperator::NotEqual => Value::Boolean(left != right),
+        BinaryOperator::And | BinaryOperator::Or => unreachable!("logical operator applied to numbers")
+    }
+}
+
+/// Expression compiled against the indicator set, evaluated bar after bar
+pub struct CompiledExpression {
+    root: Node
+}
+
+impl CompiledExpression {
+
+    /// Compile a boolean expression
+    ///
+    /// The series are `open`, `high`, `low`, `close` and `volume`. The functions are `sma`,
+    /// `ema`, `rsi`, `stddev`, `highest` and `lowest` of a series and a period,
+    /// `bollinger_upper` and `bollinger_lower` of a series, a period and a number of deviations,
+    /// `atr` of a period, and `crossover` and `crossunder` of two series. Periods and other
+    /// constant arguments may use the parameters.
+    pub fn new(expression: &Expression, parameters: &Parameters) -> Result<CompiledExpression, ExpressionError> {
+        let (root, node_type) = compile(expression, parameters)?;
+        if node_type != Type::Boolean {
+            return Err(ExpressionError::TypeMismatch(String::from("the expression must be a condition")));
+        }
+        Ok(CompiledExpression { root })
+    }
+
+    /// Give the next bar to the expression and return whether the condition holds
+    pub fn update(&mut self, ohlcv: &Ohlcv) -> bool {
+        self.root.evaluate(ohlcv).boolean()
+    }
+
+}
+
+fn compile(expression: &Expression, parameters: &Parameters) -> Result<(Node, Type), ExpressionError> {
+    match *expression {
+        Expression::Number(number) => Ok((Node::Constant(number), Type::Number)),
+        Expression::Identifier(ref name) => {
+            let field = match name.as_str() {
+                "open" => Field::Open,
+                "high" => Field::High,
+                "low" => Field::Low,
+                "close" => Field::Close,
+                "volume" => Field::Volume,
+                _ => return parameters.get(name)
+                    .map(|value| (Node::Constant(*value), Type::Number))
+                    .ok_or_else(|| ExpressionError::UnknownIdentifier(name.clone()))
+            };
+            Ok((Node::Field(field), Type::Number))
+        },
+        Expression::Unary(UnaryOperator::Negate, ref operand) => {
+            Ok((Node::Negate(Box::new(compile_typed(operand, parameters, Type::Number)?)), Type::Number))
+        },
+        Expression::Unary(UnaryOperator::Not, ref operand) => {
+            Ok((Node::Not(Box::new(compile_typed(operand, parameters, Type::Boolean)?)), Type::Boolean))
+        },
+        Expression::Binary(operator, ref left, ref right) => {
+            let (operand_type, result_type) = match operator {
+                BinaryOperator::And | BinaryOperator::Or => (Type::Boolean, Type::Boolean),
+                BinaryOperator::Add | BinaryOperator::Subtract
+                    | BinaryOperator::Multiply | BinaryOperator::Divide => (Type::Number, Type::Number),
+                _ => (Type::Number, Type::Boolean)
+            };
+            Ok((
+                Node::Binary(
+                    operator,
+                    Box::new(compile_typed(left, parameters, operand_type)?),
+                    Box::new(compile_typed(right, parameters, operand_type)?)
+                ),
+                result_type
+            ))
+        },
+        Expression::Call(ref name, ref arguments) => compile_call(name, arguments, parameters)
+    }
+}
+
+fn compile_typed(expression: &Expression, parameters: &Parameters, expected: Type) -> Result<Node, ExpressionError> {
+    let (node, node_type) = compile(expression, parameters)?;
+    if node_type == expected {
+        Ok(node)
+    }
+    else {
+        Err(ExpressionError::TypeMismatch(format!("expected a {:?} in {:?}", expected, expression)))
+    }
+}
+
+fn compile_call(name: &str, arguments: &[Expression], parameters: &Parameters) -> Result<(Node, Type), ExpressionError> {
+    let arity = match name {
+        "atr" => 1,
+        "sma" | "ema" | "rsi" | "stddev" | "highest" | "lowest" | "crossover" | "crossunder" => 2,
+        "bollinger_upper" | "bollinger_lower" => 3,
+        _ => return Err(ExpressionError::UnknownFunction(String::from(name)))
+    };
+    if arguments.len() != arity {
+        return Err(ExpressionError::WrongArguments(format!("{} expects {} arguments", name, arity)));
+    }
+
+    if name == "atr" {
+        return Ok((Node::Atr(Atr::new(period(&arguments[0], parameters)?)), Type::Number));
+    }
+    if name == "crossover" || name == "crossunder" {
+        let cross = if name == "crossover" { Cross::Above } else { Cross::Below };
+        let a = compile_typed(&arguments[0], parameters, Type::Number)?;
+        let b = compile_typed(&arguments[1], parameters, Type::Number)?;
+        return Ok((Node::Cross(cross, Box::new(a), Box::new(b), None), Type::Boolean));
+    }
+
+    let series = compile_typed(&arguments[0], parameters, Type::Number)?;
+    let period = period(&arguments[1], parameters)?;
+    let indicator: Box<Indicator> = match name {
+        "sma" => Box::new(Sma::new(period)),
+        "ema" => Box::new(Ema::new(period)),
+        "rsi" => Box::new(Rsi::new(period)),
+        "stddev" => Box::new(StdDev::new(period)),
+        "highest" => Box::new(Highest::new(period)),
+        "lowest" => Box::new(Lowest::new(period)),
+        "bollinger_upper" => Box::new(BollingerBand::new(Band::Upper, period, constant(&arguments[2], parameters)?)),
+        _ => Box::new(BollingerBand::new(Band::Lower, period, constant(&arguments[2], parameters)?))
+    };
+    Ok((Node::Indicator(indicator, Box::new(series)), Type::Number))
+}
+
+/// Value of an argument which must be known at compile time
+fn constant(expression: &Expression, parameters: &Parameters) -> Result<f64, ExpressionError> {
+    match *expression {
+        Expression::Number(number) => Ok(number),
+        Expression::Identifier(ref name) => parameters.get(name).cloned()
+            .ok_or_else(|| ExpressionError::UnknownIdentifier(name.clone())),
+        Expression::Unary(UnaryOperator::Negate, ref operand) => Ok(-constant(operand, parameters)?),
+        Expression::Binary(operator, ref left, ref right) => {
+            match apply_constant(operator, constant(left, parameters)?, constant(right, parameters)?) {
+                Some(value) => Ok(value),
+                None => Err(ExpressionError::WrongArguments(format!("{:?} is not a constant", expression)))
+            }
+        },
+        _ => Err(ExpressionError::WrongArguments(format!("{:?} is not a constant", expression)))
+    }
+}
+
+fn apply_constant(operator: BinaryOperator, left: f64, right: f64) -> Option<f64> {
+    match operator {
+        BinaryOperator::Add => Some(left + right),
+        BinaryOperator::Subtract => Some(left - right),
+        BinaryOperator::Multiply => Some(left * right),
+        BinaryOperator::Divide => Some(left / right),
+        _ => None
+    }
+}
+
+fn period(expression: &Expression, parameters: &Parameters) -> Result<usize, ExpressionError> {
+    let value = constant(expression, parameters)?;
+    if value >= 1. && value.fract() == 0. {
+        Ok(value as usize)
+    }
+    else {
+        Err(ExpressionError::WrongArguments(format!("invalid period {}", value)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate chrono;
+    use super::*;
+    use self::chrono::prelude::{Utc, TimeZone};
+    use quantity::Quantity;
+    use signal::expression::parse;
+    use symbol::SymbolId;
+
+    fn run(text: &str, parameters: &Parameters, closes: Vec<f64>) -> Vec<bool> {
+        let mut expression = CompiledExpression::new(&parse(text).unwrap(), parameters).unwrap();
+        closes.into_iter().enumerate().map(|(minute, close)| {
+            let datetime = Utc.ymd(2016, 1, 3).and_hms(17, minute as u32, 0);
+            expression.update(&Ohlcv::new(SymbolId::from("eur/usd"), datetime, close, close, close, close, Quantity::new(1.)))
+        }).collect()
+    }
+
+    #[test]
+    fn update() {
+        let mut parameters = Parameters::new();
+        parameters.insert(String::from("slow"), 2.);
+        assert_eq!(
+            run("crossover(sma(close, 1), sma(close, slow)) and close < 3.5", &parameters, vec![3., 2., 1., 3., 2., 4.]),
+            vec![false, false, false, true, false, false]
+        );
+        assert_eq!(
+            run("not (close - 1 > 2 or rsi(close, 2) <= 0)", &parameters, vec![3., 2., 1., 2.]),
+            vec![true, true, false, true]
+        );
+    }
+
+    #[test]
+    fn compile_errors() {
+        let parameters = Parameters::new();
+        let compile = |text: &str| CompiledExpression::new(&parse(text).unwrap(), &parameters).err();
+        assert_eq!(compile("sma(close, fast) > 1"), Some(ExpressionError::UnknownIdentifier(String::from("fast"))));
+        assert_eq!(compile("macd(close) > 1"), Some(ExpressionError::UnknownFunction(String::from("macd"))));
+        assert!(compile("sma(close, 1.5) > 1").is_some());
+        assert!(compile("sma(close, close) > 1").is_some());
+        assert!(compile("close + 1").is_some());
+        assert!(compile("close and 1").is_some());
+    }
+}
@@ -0,0 +1,106 @@
+use signal::expression::ExpressionError;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Token {
+    Number(f64),
+    Identifier(String),
+    And,
+    Or,
+    Not,
+    LeftParenthesis,
+    RightParenthesis,
+    Comma,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual
+}
+
+/// Split an expression into tokens
+pub fn tokenize(text: &str) -> Result<Vec<Token>, ExpressionError> {
+    let chars = text.chars().collect::<Vec<char>>();
+    let mut tokens = vec![];
+    let mut idx = 0;
+    while idx < chars.len() {
+        let c = chars[idx];
+        let next = chars.get(idx + 1).cloned();
+        if c.is_whitespace() {
+            idx += 1;
+            continue;
+        }
+        if c.is_ascii_digit() || (c == '.' && next.map_or(false, |n| n.is_ascii_digit())) {
+            let start = idx;
+            while idx < chars.len() && (chars[idx].is_ascii_digit() || chars[idx] == '.') {
+                idx += 1;
+            }
+            let number = chars[start..idx].iter().collect::<String>();
+            tokens.push(Token::Number(number.parse().map_err(|_| ExpressionError::InvalidNumber(number.clone()))?));
+            continue;
+        }
+        if c.is_alphabetic() || c == '_' {
+            let start = idx;
+            while idx < chars.len() && (chars[idx].is_alphanumeric() || chars[idx] == '_') {
+                idx += 1;
+            }
+            let word = chars[start..idx].iter().collect::<String>();
+            tokens.push(match word.as_str() {
+                "and" => Token::And,
+                "or" => Token::Or,
+                "not" => Token::Not,
+                _ => Token::Identifier(word)
+            });
+            continue;
+        }
+        let (token, length) = match (c, next) {
+            ('<', Some('=')) => (Token::LessEqual, 2),
+            ('>', Some('=')) => (Token::GreaterEqual, 2),
+            ('=', Some('=')) => (Token::Equal, 2),
+            ('!', Some('=')) => (Token::NotEqual, 2),
+            ('<', _) => (Token::Less, 1),
+            ('>', _) => (Token::Greater, 1),
+            ('(', _) => (Token::LeftParenthesis, 1),
+            (')', _) => (Token::RightParenthesis, 1),
+            (',', _) => (Token::Comma, 1),
+            ('+', _) => (Token::Plus, 1),
+            ('-', _) => (Token::Minus, 1),
+            ('*', _) => (Token::Star, 1),
+            ('/', _) => (Token::Slash, 1),
+            _ => return Err(ExpressionError::UnexpectedCharacter(idx, c))
+        };
+        tokens.push(token);
+        idx += length;
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tokenize_expression() {
+        assert_eq!(
+            tokenize("rsi(close, 14) <= 70.5 and not x").unwrap(),
+            vec![
+                Token::Identifier(String::from("rsi")),
+                Token::LeftParenthesis,
+                Token::Identifier(String::from("close")),
+                Token::Comma,
+                Token::Number(14.),
+                Token::RightParenthesis,
+                Token::LessEqual,
+                Token::Number(70.5),
+                Token::And,
+                Token::Not,
+                Token::Identifier(String::from("x"))
+            ]
+        );
+        assert_eq!(tokenize("close # 1"), Err(ExpressionError::UnexpectedCharacter(6, '#')));
+    }
+}
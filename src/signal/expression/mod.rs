@@ -0,0 +1,22 @@
+mod lexer;
+mod parser;
+mod compiler;
+pub use signal::expression::parser::{Expression, UnaryOperator, BinaryOperator, parse};
+pub use signal::expression::compiler::{CompiledExpression, Parameters};
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum ExpressionError {
+    UnexpectedCharacter(usize, char),
+    InvalidNumber(String),
+    UnexpectedToken(String),
+    UnexpectedEnd,
+    UnknownIdentifier(String),
+    UnknownFunction(String),
+    WrongArguments(String),
+    TypeMismatch(String)
+}
+
+/// Parse and compile a condition
+pub fn compile(text: &str, parameters: &Parameters) -> Result<CompiledExpression, ExpressionError> {
+    CompiledExpression::new(&parse(text)?, parameters)
+}
@@ -0,0 +1,228 @@
+use signal::expression::ExpressionError;
+use signal::expression::lexer::{Token, tokenize};
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum UnaryOperator {
+    Negate,
+    Not
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum BinaryOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+    And,
+    Or
+}
+
+/// Syntax tree of an expression
+#[derive(Clone, PartialEq, Debug)]
+pub enum Expression {
+    Number(f64),
+    Identifier(String),
+    Call(String, Vec<Expression>),
+    Unary(UnaryOperator, Box<Expression>),
+    Binary(BinaryOperator, Box<Expression>, Box<Expression>)
+}
+
+/// Parse an expression, where `and` binds tighter than `or` and comparisons tighter than both
+pub fn parse(text: &str) -> Result<Expression, ExpressionError> {
+    let mut parser = Parser { tokens: tokenize(text)?, position: 0 };
+    let expression = parser.or()?;
+    match parser.peek() {
+        Some(token) => Err(ExpressionError::UnexpectedToken(format!("{:?}", token))),
+        None => Ok(expression)
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize
+}
+
+impl Parser {
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<Token, ExpressionError> {
+        let token = self.tokens.get(self.position).cloned().ok_or(ExpressionError::UnexpectedEnd)?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ExpressionError> {
+        let token = self.next()?;
+        if token == expected {
+            Ok(())
+        }
+        else {
+            Err(ExpressionError::UnexpectedToken(format!("{:?}", token)))
+        }
+    }
+
+    /// Match the next token against a list of binary operators
+    fn operator(&self, operators: &[(Token, BinaryOperator)]) -> Option<BinaryOperator> {
+        let token = self.peek()?;
+        operators.iter().find(|pair| pair.0 == *token).map(|pair| pair.1)
+    }
+
+    fn or(&mut self) -> Result<Expression, ExpressionError> {
+        let mut expression = self.and()?;
+        while let Some(operator) = self.operator(&[(Token::Or, BinaryOperator::Or)]) {
+            self.position += 1;
+            expression = Expression::Binary(operator, Box::new(expression), Box::new(self.and()?));
+        }
+        Ok(expression)
+    }
+
+    fn and(&mut self) -> Result<Expression, ExpressionError> {
+        let mut expression = self.not()?;
+        while let Some(operator) = self.operator(&[(Token::And, BinaryOperator::And)]) {
+            self.position += 1;
+            expression = Expression::Binary(operator, Box::new(expression), Box::new(self.not()?));
+        }
+        Ok(expression)
+    }
+
+    fn not(&mut self) -> Result<Expression, ExpressionError> {
+        if self.peek() == Some(&Token::Not) {
+            self.position += 1;
+            return Ok(Expression::Unary(UnaryOperator::Not, Box::new(self.not()?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expression, ExpressionError> {
+        let expression = self.sum()?;
+        let operator = self.operator(&[
+            (Token::Less, BinaryOperator::Less),
+            (Token::LessEqual, BinaryOperator::LessEqual),
+            (Token::Greater, BinaryOperator::Greater),
+            (Token::GreaterEqual, BinaryOperator::GreaterEqual),
+            (Token::Equal, BinaryOperator::Equal),
+            (Token::NotEqual, BinaryOperator::NotEqual)
+        ]);
+        match operator {
+            Some(operator) => {
+                self.position += 1;
+                Ok(Expression::Binary(operator, Box::new(expression), Box::new(self.sum()?)))
+            },
+            None => Ok(expression)
+        }
+    }
+
+    fn sum(&mut self) -> Result<Expression, ExpressionError> {
+        let mut expression = self.product()?;
+        while let Some(operator) = self.operator(&[(Token::Plus, BinaryOperator::Add), (Token::Minus, BinaryOperator::Subtract)]) {
+            self.position += 1;
+            expression = Expression::Binary(operator, Box::new(expression), Box::new(self.product()?));
+        }
+        Ok(expression)
+    }
+
+    fn product(&mut self) -> Result<Expression, ExpressionError> {
+        let mut expression = self.unary()?;
+        while let Some(operator) = self.operator(&[(Token::Star, BinaryOperator::Multiply), (Token::Slash, BinaryOperator::Divide)]) {
+            self.position += 1;
+            expression = Expression::Binary(operator, Box::new(expression), Box::new(self.unary()?));
+        }
+        Ok(expression)
+    }
+
+    fn unary(&mut self) -> Result<Expression, ExpressionError> {
+        if self.peek() == Some(&Token::Minus) {
+            self.position += 1;
+            return Ok(Expression::Unary(UnaryOperator::Negate, Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expression, ExpressionError> {
+        match self.next()? {
+            Token::Number(number) => Ok(Expression::Number(number)),
+            Token::Identifier(name) => {
+                if self.peek() != Some(&Token::LeftParenthesis) {
+                    return Ok(Expression::Identifier(name));
+                }
+                self.position += 1;
+                let mut arguments = vec![];
+                if self.peek() != Some(&Token::RightParenthesis) {
+                    arguments.push(self.or()?);
+                    while self.peek() == Some(&Token::Comma) {
+                        self.position += 1;
+                        arguments.push(self.or()?);
+                    }
+                }
+                self.expect(Token::RightParenthesis)?;
+                Ok(Expression::Call(name, arguments))
+            },
+            Token::LeftParenthesis => {
+                let expression = self.or()?;
+                self.expect(Token::RightParenthesis)?;
+                Ok(expression)
+            },
+            token => Err(ExpressionError::UnexpectedToken(format!("{:?}", token)))
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn identifier(name: &str) -> Box<Expression> {
+        Box::new(Expression::Identifier(String::from(name)))
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(
+            parse("a or b and close + 1 * 2 > 3").unwrap(),
+            Expression::Binary(
+                BinaryOperator::Or,
+                identifier("a"),
+                Box::new(Expression::Binary(
+                    BinaryOperator::And,
+                    identifier("b"),
+                    Box::new(Expression::Binary(
+                        BinaryOperator::Greater,
+                        Box::new(Expression::Binary(
+                            BinaryOperator::Add,
+                            identifier("close"),
+                            Box::new(Expression::Binary(
+                                BinaryOperator::Multiply,
+                                Box::new(Expression::Number(1.)),
+                                Box::new(Expression::Number(2.))
+                            ))
+                        )),
+                        Box::new(Expression::Number(3.))
+                    ))
+                ))
+            )
+        );
+    }
+
+    #[test]
+    fn call() {
+        assert_eq!(
+            parse("sma(close, -(20))").unwrap(),
+            Expression::Call(String::from("sma"), vec![
+                Expression::Identifier(String::from("close")),
+                Expression::Unary(UnaryOperator::Negate, Box::new(Expression::Number(20.)))
+            ])
+        );
+        assert_eq!(parse("sma(close, 20"), Err(ExpressionError::UnexpectedEnd));
+        assert!(parse("close close").is_err());
+    }
+}
@@ -1,26 +1,35 @@
 extern crate chrono;
 
+use std::collections::BTreeMap;
 use symbol::SymbolId;
 use direction::Direction;
 use self::chrono::prelude::{DateTime, Utc};
 
 pub mod detector;
+pub mod expression;
+
+/// Key-value tags describing why a signal was detected
+pub type SignalTags = BTreeMap<String, String>;
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub struct Signal {
     symbol_id: SymbolId,
     direction: Direction,
     datetime: DateTime<Utc>,
-    label: String
+    label: String,
+    strength: Option<f64>,
+    tags: SignalTags
 }
 
 impl Signal {
     pub fn new(symbol_id: SymbolId, direction: Direction, datetime: DateTime<Utc>, label: String) -> Signal {
         Signal {
-            symbol_id: symbol_id,
-            direction: direction,
-            datetime: datetime,
-            label: label
+            symbol_id,
+            direction,
+            datetime,
+            label,
+            strength: None,
+            tags: SignalTags::new()
         }
     }
 
@@ -39,4 +48,59 @@ impl Signal {
     pub fn label(&self) -> &String {
         &self.label
     }
+
+    /// Score of the signal, on a scale chosen by the detector
+    pub fn strength(&self) -> Option<f64> {
+        self.strength
+    }
+
+    pub fn set_strength(mut self, value: Option<f64>) -> Self {
+        self.strength = value;
+        self
+    }
+
+    pub fn tags(&self) -> &SignalTags {
+        &self.tags
+    }
+
+    pub fn set_tags(mut self, value: SignalTags) -> Self {
+        self.tags = value;
+        self
+    }
+
+    pub fn set_tag(mut self, key: String, value: String) -> Self {
+        self.tags.insert(key, value);
+        self
+    }
+
+    /// Metadata carried onto the orders created from the signal
+    pub fn metadata(&self) -> SignalMetadata {
+        SignalMetadata {
+            label: self.label.clone(),
+            strength: self.strength,
+            tags: self.tags.clone()
+        }
+    }
+}
+
+/// Label, strength and tags of the signal an order was created from
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct SignalMetadata {
+    label: String,
+    strength: Option<f64>,
+    tags: SignalTags
+}
+
+impl SignalMetadata {
+    pub fn label(&self) -> &String {
+        &self.label
+    }
+
+    pub fn strength(&self) -> Option<f64> {
+        self.strength
+    }
+
+    pub fn tags(&self) -> &SignalTags {
+        &self.tags
+    }
 }
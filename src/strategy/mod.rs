@@ -6,8 +6,9 @@ mod strategy_manager;
 use self::snowflake::ProcessUniqueId;
 use self::chrono::prelude::{DateTime, Utc};
 use signal::Signal;
+use ohlcv::TimeSlice;
 use signal::detector::{DetectSignal, DetectSignalError};
-use order::OrderBuilder;
+use order::{Order, OrderBuilder, OrderId, OrderStatus, OrderRequest};
 use order::policy::{OrderPolicy, OrderPolicyError};
 pub use strategy::strategy_manager::{StrategyManager, StrategyCollection, StrategyType};
 
@@ -36,18 +37,48 @@ impl Strategy {
         }
     }
 
-    pub fn run(&self, datetime: &DateTime<Utc>) -> Result<Option<(Signal, OrderBuilder)>, StrategyError>
+    /// Detect signals at the given datetime and create their orders, in the order of the signals
+    pub fn run(&mut self, datetime: &DateTime<Utc>) -> Result<Vec<(Signal, OrderBuilder)>, StrategyError>
     {
-        match self.signal_detector.detect_signal(datetime) {
-            Ok(Some(signal)) => match self.order_policy.create_order(&signal) {
-                Ok(order_builder) => {
-                    Ok(Some((signal, order_builder)))
-                },
-                Err(e) => Err(StrategyError::CreateOrderError(e))
-            },
-            Ok(None) => Ok(None),
-            Err(e) => Err(StrategyError::DetectSignalError(e))
+        let signals = self.signal_detector.detect_signals(datetime)
+            .map_err(StrategyError::DetectSignalError)?;
+        let mut orders = vec![];
+        for signal in signals {
+            let order_builders = self.order_policy.create_orders(&signal)
+                .map_err(StrategyError::CreateOrderError)?;
+            for order_builder in order_builders {
+                let order_builder = order_builder.set_signal(Some(signal.metadata()));
+                orders.push((signal.clone(), order_builder));
+            }
         }
+        Ok(orders)
+    }
+
+    pub fn on_start(&mut self) {
+        self.signal_detector.on_start();
+        self.order_policy.on_start();
+    }
+
+    pub fn on_bar(&mut self, time_slice: &TimeSlice) {
+        self.signal_detector.on_bar(time_slice);
+        self.order_policy.on_bar(time_slice);
+    }
+
+    pub fn on_end(&mut self) {
+        self.signal_detector.on_end();
+        self.order_policy.on_end();
+    }
+
+    pub fn on_order_update(&mut self, order: &Order, status: &OrderStatus) {
+        self.signal_detector.on_order_update(order, status)
+    }
+
+    pub fn order_requests(&mut self, datetime: &DateTime<Utc>) -> Vec<OrderRequest> {
+        self.signal_detector.order_requests(datetime)
+    }
+
+    pub fn on_position_closed(&mut self, entry_order_id: &OrderId) {
+        self.signal_detector.on_position_closed(entry_order_id)
     }
 
     pub fn id(&self) -> &StrategyId {
@@ -69,35 +100,46 @@ mod tests {
 
     struct SomeSignal { symbol_id: SymbolId }
     impl DetectSignal for SomeSignal {
-        fn detect_signal(&self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
             Ok(Some(Signal::new(self.symbol_id.clone(), Direction::Long, datetime.clone(), String::new())))
         }
     }
 
     struct NoSignal;
     impl DetectSignal for NoSignal {
-        fn detect_signal(&self, _datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        fn detect_signal(&mut self, _datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
             Ok(None)
         }
     }
     
     struct SignalError;
     impl DetectSignal for SignalError {
-        fn detect_signal(&self, _datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        fn detect_signal(&mut self, _datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
             Err(DetectSignalError::IndicatorError)
         }
     }
 
     struct MockOrderPolicy;
     impl OrderPolicy for MockOrderPolicy {
-        fn create_order(&self, signal: &Signal) -> Result<OrderBuilder, OrderPolicyError> {
+        fn create_order(&mut self, signal: &Signal) -> Result<OrderBuilder, OrderPolicyError> {
             Ok(OrderBuilder::unallocated(OrderKind::MarketOrder, signal.symbol_id().clone(),signal.direction().clone()))
         }
     }
 
+    struct ScaleInOrderPolicy;
+    impl OrderPolicy for ScaleInOrderPolicy {
+        fn create_order(&mut self, signal: &Signal) -> Result<OrderBuilder, OrderPolicyError> {
+            Ok(OrderBuilder::unallocated(OrderKind::MarketOrder, signal.symbol_id().clone(),*signal.direction()))
+        }
+
+        fn create_orders(&mut self, signal: &Signal) -> Result<Vec<OrderBuilder>, OrderPolicyError> {
+            Ok(vec![self.create_order(signal)?, self.create_order(signal)?])
+        }
+    }
+
     struct MockOrderPolicyError;
     impl OrderPolicy for MockOrderPolicyError {
-        fn create_order(&self, _signal: &Signal) -> Result<OrderBuilder, OrderPolicyError> {
+        fn create_order(&mut self, _signal: &Signal) -> Result<OrderBuilder, OrderPolicyError> {
             Err(OrderPolicyError::IndicatorError)
         }
     }
@@ -111,11 +153,28 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn run_signal_metadata() {
+        let symbol_id = SymbolId::from("symbol");
+        let (signal, order_builder) = Strategy::new(Box::new(SomeSignal { symbol_id: symbol_id.clone()}), Box::new(MockOrderPolicy {}))
+            .run(&run_date()).unwrap().remove(0);
+        assert_eq!(order_builder.signal(), &Some(signal.metadata()));
+    }
+
+    #[test]
+    fn run_multiple_orders() {
+        let symbol_id = SymbolId::from("symbol");
+        let orders = Strategy::new(Box::new(SomeSignal { symbol_id: symbol_id.clone()}), Box::new(ScaleInOrderPolicy {}))
+            .run(&run_date()).unwrap();
+        assert_eq!(orders.len(), 2);
+        assert_eq!(orders[0], orders[1]);
+    }
+
     #[test]
     fn run_no_signal() {
         let result = Strategy::new(Box::new(NoSignal {}), Box::new(MockOrderPolicy {})).run(&run_date());
         assert!(result.is_ok());
-        assert!(result.unwrap().is_none())
+        assert!(result.unwrap().is_empty())
     }
 
     #[test]
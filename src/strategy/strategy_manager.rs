@@ -3,8 +3,9 @@ extern crate chrono;
 use std::collections::{HashMap, BTreeMap};
 use self::chrono::prelude::{DateTime, Utc};
 use model::Model;
+use ohlcv::TimeSlice;
 use strategy::{Strategy, StrategyError, StrategyId};
-use order::{Order, OrderId, OrderStatus, GenerateOrderId, OrderBuilder};
+use order::{Order, OrderId, OrderStatus, OrderRequest, GenerateOrderId, OrderBuilder};
 
 pub enum StrategyType<'model> {
     EntryStrategy(StrategyId, &'model Model),
@@ -57,6 +58,27 @@ impl StrategyManager {
         strategy_collection
     }
 
+    /// Start all strategies of the collection, before the first time slice
+    pub fn on_start(&self, strategies: &mut StrategyCollection) {
+        for strategy in strategies.entry_strategies.iter_mut().chain(strategies.exit_strategies.values_mut()) {
+            strategy.on_start();
+        }
+    }
+
+    /// Give the market data of a time slice to all strategies of the collection
+    pub fn on_bar(&self, strategies: &mut StrategyCollection, time_slice: &TimeSlice) {
+        for strategy in strategies.entry_strategies.iter_mut().chain(strategies.exit_strategies.values_mut()) {
+            strategy.on_bar(time_slice);
+        }
+    }
+
+    /// End all strategies of the collection, after the last time slice
+    pub fn on_end(&self, strategies: &mut StrategyCollection) {
+        for strategy in strategies.entry_strategies.iter_mut().chain(strategies.exit_strategies.values_mut()) {
+            strategy.on_end();
+        }
+    }
+
     /// Run all strategies of the collection at the specified date
     pub fn run_strategies(&self, strategies: &mut StrategyCollection, datetime: &DateTime<Utc>,
                           order_id_generator: &GenerateOrderId) -> Result<Vec<OrderBuilder>, StrategyError>
@@ -64,11 +86,9 @@ impl StrategyManager {
         let mut order_builders = vec![];
 
         for strategy in strategies.entry_strategies.iter_mut().chain(strategies.exit_strategies.values_mut()) {
-            let result = strategy.run(datetime)?;
-            if let Some(o) = result {
-                let (signal, order_builder) = o;
-                let order_id = order_id_generator.get_id(strategy.id().clone(), &signal, &order_builder);
-                let order_builder = order_builder.set_id(order_id.clone());
+            for (index, (signal, order_builder)) in strategy.run(datetime)?.into_iter().enumerate() {
+                let order_id = order_id_generator.get_id(*strategy.id(), &signal, &order_builder, index);
+                let order_builder = order_builder.set_id(order_id.clone()).set_strategy_id(Some(*strategy.id()));
                 strategies.order_strategy.insert(order_id.clone(), strategy.id().clone());
                 order_builders.push(order_builder);
             }
@@ -77,16 +97,33 @@ impl StrategyManager {
         Ok(order_builders)
     }
 
-    /// Update strategies when an order is updated
+    /// Get the cancel and modify requests of all strategies of the collection, a strategy can
+    /// only make requests about its own orders
+    pub fn order_requests(&self, strategies: &mut StrategyCollection, datetime: &DateTime<Utc>) -> Vec<OrderRequest> {
+        let mut requests = vec![];
+
+        for strategy in strategies.entry_strategies.iter_mut().chain(strategies.exit_strategies.values_mut()) {
+            let order_strategy = &strategies.order_strategy;
+            requests.extend(strategy.order_requests(datetime).into_iter().filter(|request| {
+                order_strategy.get(request.order_id()) == Some(strategy.id())
+            }));
+        }
+
+        requests
+    }
+
+    /// Update strategies when an order is updated, exit strategies are added once an entry order
+    /// is filled, or cancelled after being partially filled
     pub fn update_strategies(&self, strategy_collection: &mut StrategyCollection,
                              order_updates: &Vec<(&Order, OrderStatus)>)
     {
         for update in order_updates {
+            self.notify_strategies(strategy_collection, update.0, &update.1);
             match update.1 {
                 OrderStatus::Filled(_) => {
                     self.update_exit_strategies(strategy_collection, update.0, &update.1);
                 },
-                OrderStatus::Cancelled(_) => {
+                OrderStatus::Cancelled(_) | OrderStatus::Rejected(_) => {
                     self.update_exit_strategies(strategy_collection, update.0, &update.1);
                 },
                 _ => ()
@@ -94,8 +131,46 @@ impl StrategyManager {
         }
     }
 
+    /// Tell the strategy of a new, partially filled, filled, cancelled or rejected order about it,
+    /// and the entry strategy about its closed position when an exit order is filled
+    fn notify_strategies(&self, strategies: &mut StrategyCollection, order: &Order, order_status: &OrderStatus) {
+        match *order_status {
+            OrderStatus::NotSent | OrderStatus::PartiallyFilled(_) | OrderStatus::Filled(_) |
+            OrderStatus::Cancelled(_) | OrderStatus::Rejected(_) => (),
+            _ => return
+        }
+        let strategy_id = match strategies.order_strategy.get(order.id()) {
+            Some(strategy_id) => *strategy_id,
+            None => return
+        };
+        if let Some(strategy) = self.find_strategy(strategies, &strategy_id) {
+            strategy.on_order_update(order, order_status);
+        }
+
+        if let OrderStatus::Filled(_) = *order_status {
+            let entry_order_id = match strategies.strategy_types.get(&strategy_id) {
+                Some(StrategyType::ExitStrategy(_, _, entry_order_id)) => entry_order_id.clone(),
+                _ => return
+            };
+            let entry_strategy_id = match strategies.order_strategy.get(&entry_order_id) {
+                Some(entry_strategy_id) => *entry_strategy_id,
+                None => return
+            };
+            if let Some(entry_strategy) = self.find_strategy(strategies, &entry_strategy_id) {
+                entry_strategy.on_position_closed(&entry_order_id);
+            }
+        }
+    }
+
+    fn find_strategy<'a>(&self, strategies: &'a mut StrategyCollection, strategy_id: &StrategyId) -> Option<&'a mut Strategy> {
+        match strategies.entry_strategies.iter_mut().find(|strategy| strategy.id() == strategy_id) {
+            Some(strategy) => Some(strategy),
+            None => strategies.exit_strategies.get_mut(strategy_id)
+        }
+    }
+
     /// Add exit strategies if an entry order is executed, remove exit strategy if its order
-    /// is cancelled or executed
+    /// is cancelled, rejected or executed
     fn update_exit_strategies<'model>(&self, strategies: &mut StrategyCollection<'model>,
                                       closed_order: &Order, order_status: &OrderStatus)
     {
@@ -113,6 +188,10 @@ impl StrategyManager {
                         &OrderStatus::Filled(_) => {
                             Some(StrategiesUpdate::AddExitStrategies(model.exit_strategies(closed_order), model))
                         },
+                        // the rest of a partially filled entry order is cancelled
+                        &OrderStatus::Cancelled(_) if !closed_order.executions().is_empty() => {
+                            Some(StrategiesUpdate::AddExitStrategies(model.exit_strategies(closed_order), model))
+                        },
                         _ => None
                     }
                 },
@@ -122,7 +201,7 @@ impl StrategyManager {
                         &OrderStatus::Filled(_) => {
                             Some(StrategiesUpdate::RemoveExitStrategy(*strategy_id))
                         },
-                        &OrderStatus::Cancelled(_) => {
+                        &OrderStatus::Cancelled(_) | &OrderStatus::Rejected(_) => {
                             Some(StrategiesUpdate::RemoveExitStrategy(*strategy_id))
                         },
                         _ => None
@@ -135,7 +214,8 @@ impl StrategyManager {
         if let Some(updates) = strategy_updates {
             match updates {
                 StrategiesUpdate::AddExitStrategies(new_strategies, model) => {
-                    for strategy in new_strategies {
+                    for mut strategy in new_strategies {
+                        strategy.on_start();
                         strategies.strategy_types.insert(
                             strategy.id().clone(),
                             StrategyType::ExitStrategy(
@@ -148,7 +228,9 @@ impl StrategyManager {
                     }
                 },
                 StrategiesUpdate::RemoveExitStrategy(strategy_id) => {
-                    strategies.exit_strategies.remove(&strategy_id);
+                    if let Some(mut strategy) = strategies.exit_strategies.remove(&strategy_id) {
+                        strategy.on_end();
+                    }
                 }
             }
         }
@@ -164,15 +246,19 @@ enum StrategiesUpdate<'model> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
     use self::chrono::prelude::{TimeZone};
     use order::{Order, OrderBuilder, OrderKind, CancellationReason, UUIDOrderIdGenerator};
     use signal::Signal;
-    use signal::detector::{DetectSignal, DetectSignalError};
+    use signal::detector::{DetectSignal, DetectSignalError, MaxConcurrency};
     use symbol::SymbolId;
     use model::ModelId;
     use direction::Direction;
     use order::policy::MarketOrderPolicy;
     use execution::Execution;
+    use quantity::Quantity;
+    use price::Price;
 
     #[derive(Clone)]
     struct MockModel {
@@ -208,14 +294,53 @@ mod test {
 
     struct SomeSignal { symbol: SymbolId }
     impl DetectSignal for SomeSignal {
-        fn detect_signal(&self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
             Ok(Some(Signal::new(self.symbol.clone(), Direction::Long, datetime.clone(), String::new())))
         }
     }
 
+    /// Detect a long signal on one symbol and a short signal on the other
+    struct PairSignals { first: SymbolId, second: SymbolId }
+    impl DetectSignal for PairSignals {
+        fn detect_signal(&mut self, _datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+            Ok(None)
+        }
+
+        fn detect_signals(&mut self, datetime: &DateTime<Utc>) -> Result<Vec<Signal>, DetectSignalError> {
+            Ok(vec![
+                Signal::new(self.first.clone(), Direction::Long, *datetime, String::from("pair")),
+                Signal::new(self.second.clone(), Direction::Short, *datetime, String::from("pair"))
+            ])
+        }
+    }
+
+    /// Cancel an order of its own and an order of another strategy
+    struct CancelOrders;
+    impl DetectSignal for CancelOrders {
+        fn detect_signal(&mut self, _datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+            Ok(None)
+        }
+
+        fn order_requests(&mut self, _datetime: &DateTime<Utc>) -> Vec<OrderRequest> {
+            vec![OrderRequest::Cancel(OrderId::from("own order")), OrderRequest::Cancel(OrderId::from("other order"))]
+        }
+    }
+
+    /// Record that the detector ended
+    struct RecordEnd(Rc<Cell<bool>>);
+    impl DetectSignal for RecordEnd {
+        fn detect_signal(&mut self, _datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+            Ok(None)
+        }
+
+        fn on_end(&mut self) {
+            self.0.set(true);
+        }
+    }
+
     struct SignalError;
     impl DetectSignal for SignalError {
-        fn detect_signal(&self, _datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        fn detect_signal(&mut self, _datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
             Err(DetectSignalError::IndicatorError)
         }
     }
@@ -245,13 +370,13 @@ mod test {
             &vec![(
                 &OrderBuilder::unallocated(OrderKind::MarketOrder, symbol_id.clone(), Direction::Long)
                     .set_id(order_id.clone())
-                    .set_quantity(3)
+                    .set_quantity(Quantity::new(3.))
                     .build().unwrap(),
                 OrderStatus::Filled(
                     Execution::new(
                         symbol_id.clone(),
-                        3,
-                        1234.,
+                        Quantity::new(3.),
+                        Price::new(1234.),
                         Utc.ymd(2017, 12, 1).and_hms(12, 0, 0)
                     )
                 )
@@ -260,7 +385,39 @@ mod test {
         assert_eq!(strategy_collection.exit_strategies.len(), 2);
     }
 
-    /// Test that exit strategy is removed from the collection when its order is filled
+    /// Test that exit strategies are added once the rest of a partially filled entry order is
+    /// cancelled
+    #[test]
+    fn update_strategies_entry_order_partially_filled() {
+        let symbol_id = SymbolId::from("instrument");
+        let model: Box<Model> = Box::new(MockModel { symbol: symbol_id.clone(), err: false });
+        let strategy_manager = StrategyManager::new();
+        let mut strategy_collection = StrategyCollection::new();
+        let order_id = OrderId::from("test order");
+        let entry_strategy = model.entry_strategy();
+        strategy_collection.order_strategy.insert(order_id.clone(), *entry_strategy.id());
+        strategy_collection.strategy_types.insert(
+            *entry_strategy.id(),
+            StrategyType::EntryStrategy(*entry_strategy.id(), &model)
+        );
+        strategy_collection.entry_strategies.push(entry_strategy);
+        let execution = Execution::new(symbol_id.clone(), Quantity::new(1.), Price::new(1234.), Utc.ymd(2017, 12, 1).and_hms(12, 0, 0));
+        let mut order = OrderBuilder::unallocated(OrderKind::MarketOrder, symbol_id.clone(), Direction::Long)
+            .set_id(order_id.clone())
+            .set_quantity(Quantity::new(3.))
+            .build().unwrap();
+        order.add_execution(execution.clone());
+
+        strategy_manager.update_strategies(&mut strategy_collection, &vec![(&order, OrderStatus::PartiallyFilled(execution))]);
+        assert_eq!(strategy_collection.exit_strategies.len(), 0);
+        strategy_manager.update_strategies(
+            &mut strategy_collection,
+            &vec![(&order, OrderStatus::Cancelled(CancellationReason::NotFilledImmediately))]
+        );
+        assert_eq!(strategy_collection.exit_strategies.len(), 2);
+    }
+
+    /// Test that exit strategy is ended and removed from the collection when its order is filled
     #[test]
     fn update_strategies_exit_order_filled() {
         let symbol_id = SymbolId::from("instrument");
@@ -269,10 +426,8 @@ mod test {
         let mut strategy_collection = StrategyCollection::new();
         let entry_order_id = OrderId::from("executed entry order");
         let exit_order_id = OrderId::from("exit order");
-        let entry_order = OrderBuilder::unallocated(
-            OrderKind::MarketOrder, symbol_id.clone(), Direction::Long
-        ).set_id(entry_order_id.clone()).build().unwrap();
-        let exit_strategy = model.exit_strategies(&entry_order).remove(0);
+        let ended = Rc::new(Cell::new(false));
+        let exit_strategy = Strategy::new(Box::new(RecordEnd(ended.clone())), Box::new(MarketOrderPolicy::new()));
         strategy_collection.exit_strategies.insert(exit_strategy.id().clone(), exit_strategy);
         strategy_collection.order_strategy.insert(
             exit_order_id.clone(),
@@ -290,18 +445,19 @@ mod test {
             &mut strategy_collection,
             &vec![(
                 &OrderBuilder::unallocated(OrderKind::MarketOrder, symbol_id.clone(), Direction::Long)
-                    .set_id(exit_order_id.clone()).set_quantity(3).build().unwrap(),
+                    .set_id(exit_order_id.clone()).set_quantity(Quantity::new(3.)).build().unwrap(),
                 OrderStatus::Filled(
                     Execution::new(
                         symbol_id.clone(),
-                        3,
-                        1234.,
+                        Quantity::new(3.),
+                        Price::new(1234.),
                         Utc.ymd(2017, 12, 1).and_hms(12, 0, 0)
                     )
                 )
             )]
         );
         assert_eq!(strategy_collection.exit_strategies.len(), 0);
+        assert!(ended.get());
     }
 
     /// Test that exit strategy is removed from the collection when its order is cancelled
@@ -357,11 +513,75 @@ mod test {
             OrderKind::MarketOrder,
             symbol.clone(),
             Direction::Long
-        ).set_id(order_builders[0].id().clone().unwrap());
+        )
+            .set_id(order_builders[0].id().clone().unwrap())
+            .set_strategy_id(Some(*strategy_collection.entry_strategies[0].id()))
+            .set_signal(Some(
+                Signal::new(symbol.clone(), Direction::Long, Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), String::new()).metadata()
+            ));
 
         assert!(order_builders[0] == expected);
     }
 
+    /// Test that the entry detector is told about its filled order and closed position
+    #[test]
+    fn update_strategies_notify_entry_detector() {
+        let symbol_id = SymbolId::from("instrument");
+        let model: Box<Model> = Box::new(MockModel { symbol: symbol_id.clone(), err: false });
+        let strategy_manager = StrategyManager::new();
+        let mut strategy_collection = StrategyCollection::new();
+        let entry_strategy = Strategy::new(
+            Box::new(MaxConcurrency::new(Box::new(SomeSignal { symbol: symbol_id.clone() }), 1)),
+            Box::new(MarketOrderPolicy::new())
+        );
+        strategy_collection.strategy_types.insert(
+            *entry_strategy.id(),
+            StrategyType::EntryStrategy(*entry_strategy.id(), &model)
+        );
+        strategy_collection.entry_strategies.push(entry_strategy);
+        let datetime = Utc.ymd(2016, 1, 3).and_hms(17, 0, 0);
+        let execution = Execution::new(symbol_id.clone(), Quantity::new(1.), Price::new(1.), datetime);
+
+        let entry_order = strategy_manager.run_strategies(&mut strategy_collection, &datetime, &UUIDOrderIdGenerator::new())
+            .unwrap().remove(0).build().unwrap();
+        strategy_manager.update_strategies(&mut strategy_collection, &vec![(&entry_order, OrderStatus::Filled(execution.clone()))]);
+        let order_builders = strategy_manager.run_strategies(&mut strategy_collection, &datetime, &UUIDOrderIdGenerator::new())
+            .unwrap();
+        // only the two exit strategies detect a signal while the entry is open
+        assert_eq!(order_builders.len(), 2);
+
+        let exit_order = order_builders.into_iter().next().unwrap().build().unwrap();
+        strategy_manager.update_strategies(&mut strategy_collection, &vec![(&exit_order, OrderStatus::Filled(execution.clone()))]);
+        let order_builders = strategy_manager.run_strategies(&mut strategy_collection, &datetime, &UUIDOrderIdGenerator::new())
+            .unwrap();
+        assert_eq!(order_builders.len(), 2);
+        assert_eq!(strategy_collection.order_strategy.get(order_builders[0].id().as_ref().unwrap()), Some(strategy_collection.entry_strategies[0].id()));
+    }
+
+    /// Test that each order of a strategy run gets its own id owned by the strategy
+    #[test]
+    fn run_strategies_multiple_orders() {
+        let strategy_manager = StrategyManager::new();
+        let mut strategy_collection = StrategyCollection::new();
+        strategy_collection.entry_strategies.push(Strategy::new(
+            Box::new(PairSignals { first: SymbolId::from("eur/usd"), second: SymbolId::from("gbp/usd") }),
+            Box::new(MarketOrderPolicy::new())
+        ));
+        let order_builders = strategy_manager.run_strategies(
+            &mut strategy_collection,
+            &Utc.ymd(2016, 1, 3).and_hms(17, 0, 0),
+            &UUIDOrderIdGenerator::new()
+        ).unwrap();
+        assert_eq!(order_builders.len(), 2);
+        assert_eq!(order_builders[0].symbol_id(), &SymbolId::from("eur/usd"));
+        assert_eq!(order_builders[1].symbol_id(), &SymbolId::from("gbp/usd"));
+        assert!(order_builders[0].id() != order_builders[1].id());
+        let strategy_id = strategy_collection.entry_strategies[0].id();
+        for order_builder in order_builders.iter() {
+            assert_eq!(strategy_collection.order_strategy.get(order_builder.id().as_ref().unwrap()), Some(strategy_id));
+        }
+    }
+
     #[test]
     fn run_strategies_err() {
         let symbol = SymbolId::from("symbol");
@@ -375,4 +595,17 @@ mod test {
         );
         assert!(orders.is_err());
     }
+
+    #[test]
+    fn order_requests_own_orders() {
+        let strategy_manager = StrategyManager::new();
+        let mut strategy_collection = StrategyCollection::new();
+        let strategy = Strategy::new(Box::new(CancelOrders {}), Box::new(MarketOrderPolicy::new()));
+        let other_strategy = Strategy::new(Box::new(CancelOrders {}), Box::new(MarketOrderPolicy::new()));
+        strategy_collection.order_strategy.insert(OrderId::from("own order"), *strategy.id());
+        strategy_collection.order_strategy.insert(OrderId::from("other order"), *other_strategy.id());
+        strategy_collection.entry_strategies.push(strategy);
+        let requests = strategy_manager.order_requests(&mut strategy_collection, &Utc.ymd(2016, 1, 3).and_hms(17, 0, 0));
+        assert_eq!(requests, vec![OrderRequest::Cancel(OrderId::from("own order"))]);
+    }
 }
@@ -3,7 +3,8 @@ extern crate chrono;
 use self::chrono::prelude::{DateTime, Utc};
 use portfolio::Portfolio;
 use strategy::{StrategyCollection, StrategyType};
-use order::{Order, OrderStatus};
+use order::Order;
+use signal::SignalMetadata;
 
 #[derive(PartialEq, Debug)]
 pub struct OrderPair<'a> {
@@ -11,10 +12,22 @@ pub struct OrderPair<'a> {
     pub exit_order: &'a Order
 }
 
+impl<'a> OrderPair<'a> {
+    /// Metadata of the signal which opened the trade
+    pub fn entry_signal(&self) -> Option<&SignalMetadata> {
+        self.entry_order.signal().as_ref()
+    }
+
+    /// Metadata of the signal which closed the trade
+    pub fn exit_signal(&self) -> Option<&SignalMetadata> {
+        self.exit_order.signal().as_ref()
+    }
+}
+
 fn get_execution_datetime(order: &Order) -> DateTime<Utc> {
-    match *order.status() {
-        OrderStatus::Filled(ref execution) => execution.datetime().clone(),
-        _ => panic!("Order is not executed")
+    match order.average_execution() {
+        Some(execution) => *execution.datetime(),
+        None => panic!("Order is not executed")
     }
 }
 
@@ -29,7 +42,8 @@ pub fn get_order_pairs<'a>(portfolio: &'a Portfolio,
             .expect(format!("Can't find strategy type for strategy #{}", strategy_id).as_str());
 
         if let &StrategyType::ExitStrategy(_strategy_id, _model, ref entry_order_id) = strategy_type {
-            if let &OrderStatus::Filled(_) = order.status() {
+            // exit orders cancelled after a partial fill closed a part of the position
+            if order.average_execution().is_some() {
                 result.push(OrderPair {
                     entry_order: portfolio.closed_orders().get(entry_order_id).unwrap(),
                     exit_order: order
@@ -47,7 +61,7 @@ mod test {
     use super::*;
     extern crate chrono;
     use self::chrono::prelude::{DateTime, Utc, TimeZone};
-    use order::{OrderId, OrderStatus};
+    use order::{OrderId, OrderStatus, CancellationReason};
     use execution::Execution;
     use direction::Direction;
     use symbol::SymbolId;
@@ -56,6 +70,8 @@ mod test {
     use signal::detector::{DetectSignal, DetectSignalError};
     use order::policy::MarketOrderPolicy;
     use signal::Signal;
+    use quantity::Quantity;
+    use price::Price;
 
 
     pub struct AlwaysDetectSignal {
@@ -63,7 +79,7 @@ mod test {
     }
 
     impl DetectSignal for AlwaysDetectSignal {
-        fn detect_signal(&self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
+        fn detect_signal(&mut self, datetime: &DateTime<Utc>) -> Result<Option<Signal>, DetectSignalError> {
             let signal = Signal::new(
                 self.symbol_id.clone(),
                 Direction::Long,
@@ -107,29 +123,29 @@ mod test {
         let symbol_id = SymbolId::from("eur/usd");
         let datetime = Utc.ymd(2017, 1, 1).and_hms(14, 0, 0);
         let model = OrderEveryCandle {symbol_id: symbol_id.clone()};
-        let entry_strategy = model.entry_strategy();
+        let mut entry_strategy = model.entry_strategy();
         let entry_order_id = OrderId::from("entry order");
-        let entry_order = entry_strategy.run(&datetime).unwrap().unwrap().1
+        let entry_order = entry_strategy.run(&datetime).unwrap().remove(0).1
             .set_id(entry_order_id.clone())
             .set_status(
                 OrderStatus::Filled(
                     Execution::new(
                         SymbolId::from("eur/usd"),
-                        0, 1., datetime.clone()
+                        Quantity::zero(), Price::new(1.), datetime
                     )
                 )
             )
             .build()
             .expect("failed to create entry order");
-        let exit_strategy = model.exit_strategies(&entry_order).remove(0);
+        let mut exit_strategy = model.exit_strategies(&entry_order).remove(0);
         let exit_order_id = OrderId::from("exit order");
-        let exit_order = exit_strategy.run(&datetime).unwrap().unwrap().1
+        let exit_order = exit_strategy.run(&datetime).unwrap().remove(0).1
             .set_id(exit_order_id.clone())
             .set_status(
                 OrderStatus::Filled(
                     Execution::new(
                         SymbolId::from("eur/usd"),
-                        0, 1., datetime.clone()
+                        Quantity::zero(), Price::new(1.), datetime
                     )
                 )
             )
@@ -168,5 +184,59 @@ mod test {
                 }
             ]
         );
+        assert_eq!(
+            get_order_pairs(&portfolio, &strategy_collection)[0].entry_signal().map(|signal| signal.label().clone()),
+            Some(String::from("always detect signal"))
+        );
+    }
+
+    #[test]
+    fn test_get_order_pairs_partially_filled() {
+        extern crate csv;
+        use util::write_order_pairs_to_csv;
+
+        let mut portfolio = Portfolio::new();
+        let symbol_id = SymbolId::from("eur/usd");
+        let datetime = Utc.ymd(2017, 1, 1).and_hms(14, 0, 0);
+        let model = OrderEveryCandle {symbol_id: symbol_id.clone()};
+        // both orders are cancelled after a partial fill
+        let partially_filled = |strategy: &mut Strategy, order_id: &OrderId| {
+            let mut order = strategy.run(&datetime).unwrap().remove(0).1
+                .set_id(order_id.clone())
+                .set_quantity(Quantity::new(2.))
+                .set_status(OrderStatus::Cancelled(CancellationReason::NotFilledImmediately))
+                .build()
+                .unwrap();
+            order.add_execution(Execution::new(symbol_id.clone(), Quantity::new(1.), Price::new(1.), datetime));
+            order
+        };
+        let mut entry_strategy = model.entry_strategy();
+        let entry_order_id = OrderId::from("entry order");
+        let entry_order = partially_filled(&mut entry_strategy, &entry_order_id);
+        let mut exit_strategy = model.exit_strategies(&entry_order).remove(0);
+        let exit_order_id = OrderId::from("exit order");
+        let exit_order = partially_filled(&mut exit_strategy, &exit_order_id);
+        portfolio.add_orders(vec![entry_order, exit_order]);
+
+        let mut strategy_collection = StrategyCollection::new();
+        strategy_collection.order_strategy.insert(entry_order_id.clone(), *entry_strategy.id());
+        strategy_collection.strategy_types.insert(
+            *entry_strategy.id(),
+            StrategyType::EntryStrategy(*entry_strategy.id(), &model)
+        );
+        strategy_collection.order_strategy.insert(exit_order_id.clone(), *exit_strategy.id());
+        strategy_collection.strategy_types.insert(
+            *exit_strategy.id(),
+            StrategyType::ExitStrategy(*entry_strategy.id(), &model, entry_order_id.clone())
+        );
+
+        let order_pairs = get_order_pairs(&portfolio, &strategy_collection);
+        assert_eq!(order_pairs.len(), 1);
+        let mut writer = csv::Writer::from_writer(vec![]);
+        write_order_pairs_to_csv(&mut writer, &order_pairs).unwrap();
+        let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        // executed quantities of the partial fills
+        let fields = csv.lines().nth(1).unwrap().split(',').collect::<Vec<&str>>();
+        assert_eq!((fields[9], fields[19]), ("1", "1"));
     }
 }
\ No newline at end of file
@@ -1,6 +1,8 @@
 mod get_order_pairs;
 mod write_order_pairs_to_csv;
+mod write_order_events_to_json_lines;
 
 pub mod record_parser;
 pub use util::get_order_pairs::{OrderPair, get_order_pairs};
 pub use util::write_order_pairs_to_csv::write_order_pairs_to_csv;
+pub use util::write_order_events_to_json_lines::write_order_events_to_json_lines;
@@ -1,10 +1,11 @@
 extern crate chrono;
 extern crate csv;
 use self::chrono::prelude::{DateTime, Utc, TimeZone};
-use std::num::{ParseIntError, ParseFloatError};
+use std::num::ParseFloatError;
 
 use ohlcv::Ohlcv;
 use symbol::SymbolId;
+use quantity::Quantity;
 
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct RecordParser {
@@ -51,15 +52,16 @@ impl RecordParser {
     }
 
     pub fn parse_ohlc_field(&self, field: &str) -> Result<f64, ParseError> {
-        match field.parse() {
+        match field.parse::<f64>() {
+            Ok(value) if !value.is_finite() => Err(ParseError::NonFiniteOhlc(value)),
             Ok(value) => Ok(value),
             Err(e) => Err(ParseError::OhlcError(e))
         }
     }
 
-    pub fn parse_volume_field(&self, field: &str) -> Result<u32, ParseError> {
+    pub fn parse_volume_field(&self, field: &str) -> Result<Quantity, ParseError> {
         match field.parse() {
-            Ok(value) => Ok(value),
+            Ok(value) => Ok(Quantity::new(value)),
             Err(e) => Err(ParseError::VolumeError(e))
         }
     }
@@ -70,7 +72,8 @@ impl RecordParser {
 pub enum ParseError {
     DatetimeError(chrono::ParseError),
     OhlcError(ParseFloatError),
-    VolumeError(ParseIntError),
+    NonFiniteOhlc(f64),
+    VolumeError(ParseFloatError),
     InvalidRecordStructure(csv::Error)
 }
 
@@ -90,7 +93,7 @@ mod tests {
                 Ohlcv::new(
                     SymbolId::from("eur/usd"),
                     Utc.ymd(2017, 1, 1).and_hms(23, 59, 59),
-                    1.325, 1.330, 1.320, 1.328, 8
+                    1.325, 1.330, 1.320, 1.328, Quantity::new(8.)
                 )
             ]
         )
@@ -109,17 +112,26 @@ mod tests {
         parser.parse_ohlc_field("erroneous").unwrap();
     }
 
+    #[test]
+    fn parse_non_finite_ohlc_field() {
+        let parser = RecordParser::new(SymbolId::from("eur/usd"), String::from(""));
+        assert!(parser.parse_ohlc_field("NaN").is_err());
+        assert!(parser.parse_ohlc_field("inf").is_err());
+    }
+
     #[test]
     fn parse_correct_volume_field() {
         let parser = RecordParser::new(SymbolId::from("eur/usd"), String::from(""));
-        assert_eq!(parser.parse_volume_field("123").unwrap(), 123);
+        assert_eq!(parser.parse_volume_field("123").unwrap(), Quantity::from(123u32));
+        assert_eq!(parser.parse_volume_field("0.013").unwrap(), Quantity::new(0.013));
+        assert_eq!(parser.parse_volume_field("8000000000").unwrap(), Quantity::new(8000000000.));
     }
 
     #[test]
     #[should_panic]
     fn parse_incorrect_volume_field() {
         let parser = RecordParser::new(SymbolId::from("eur/usd"), String::from(""));
-        parser.parse_volume_field("1.30").unwrap();
+        parser.parse_volume_field("erroneous").unwrap();
     }
 
     #[test]
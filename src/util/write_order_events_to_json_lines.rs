@@ -0,0 +1,63 @@
+extern crate serde_json;
+use std::io;
+use std::error::Error;
+use order::OrderEvent;
+use portfolio::Portfolio;
+
+/// Write the events of all orders of the portfolio, one JSON object per line, sorted by datetime
+pub fn write_order_events_to_json_lines<W>(writer: &mut W, portfolio: &Portfolio)
+    -> Result<(), Box<Error>> where W: io::Write
+{
+    let mut events: Vec<&OrderEvent> = portfolio.active_orders().values()
+        .chain(portfolio.closed_orders().values())
+        .flat_map(|order| order.events().iter())
+        .collect();
+    // the events of an order keep the order they were recorded in
+    events.sort_by(|a, b| a.datetime().cmp(b.datetime()).then_with(|| a.order_id().cmp(b.order_id())));
+
+    for event in events {
+        serde_json::to_writer(&mut *writer, event)?;
+        writer.write_all(b"\n")?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    extern crate chrono;
+    use super::*;
+    use self::chrono::prelude::{Utc, TimeZone};
+    use direction::Direction;
+    use order::{Order, OrderBuilder, OrderKind, OrderEventKind};
+    use symbol::SymbolId;
+
+    fn order(id: &str) -> Order {
+        OrderBuilder::unallocated(OrderKind::MarketOrder, SymbolId::from("eur/usd"), Direction::Long)
+            .set_id(String::from(id))
+            .build().unwrap()
+    }
+
+    #[test]
+    fn write_events() {
+        let first_order = order("first order");
+        let second_order = order("second order");
+        let mut portfolio = Portfolio::new();
+        portfolio.add_orders(vec![first_order.clone(), second_order.clone()]);
+        let events = vec![
+            OrderEvent::new(Utc.ymd(2016, 1, 3).and_hms(17, 1, 0), &second_order, OrderEventKind::Created),
+            OrderEvent::new(Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), &first_order, OrderEventKind::Created),
+            OrderEvent::new(Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), &first_order, OrderEventKind::Sent)
+        ];
+        portfolio.add_events(events.clone());
+
+        let mut output = vec![];
+        write_order_events_to_json_lines(&mut output, &portfolio).unwrap();
+        let written = String::from_utf8(output).unwrap().lines()
+            .map(|line| serde_json::from_str::<OrderEvent>(line).unwrap())
+            .collect::<Vec<OrderEvent>>();
+        assert_eq!(written, vec![events[1].clone(), events[2].clone(), events[0].clone()]);
+    }
+}
@@ -1,18 +1,35 @@
 extern crate csv;
 extern crate chrono;
+extern crate serde_json;
 use self::chrono::prelude::{DateTime, Utc};
 use std::io;
 use std::error::Error;
 use util::OrderPair;
-use order::{Order, OrderKind, OrderStatus, OcaGroup};
+use order::{Order, OrderKind, OcaGroup, TimeInForce};
 use execution::Execution;
 use direction::Direction;
+use signal::SignalMetadata;
 
 fn order_kind_to_str(kind: &OrderKind) -> String {
     match *kind {
         OrderKind::MarketOrder => String::from("MKT"),
         OrderKind::LimitOrder(_) => String::from("LMT"),
-        OrderKind::StopOrder(_) => String::from("STP")
+        OrderKind::StopOrder(_) => String::from("STP"),
+        OrderKind::StopLimitOrder(_, _) => String::from("STP LMT"),
+        OrderKind::TrailingStopOrder(_) => String::from("TRAIL"),
+        OrderKind::MarketOnOpen => String::from("MOO"),
+        OrderKind::MarketOnClose => String::from("MOC"),
+        OrderKind::LimitOnClose(_) => String::from("LOC")
+    }
+}
+
+fn time_in_force_to_str(time_in_force: &TimeInForce) -> String {
+    match *time_in_force {
+        TimeInForce::Day => String::from("DAY"),
+        TimeInForce::GoodTillCancelled => String::from("GTC"),
+        TimeInForce::ImmediateOrCancel => String::from("IOC"),
+        TimeInForce::FillOrKill => String::from("FOK"),
+        TimeInForce::GoodTillDate(datetime) => format!("GTD {}", datetime)
     }
 }
 
@@ -37,11 +54,31 @@ fn oca_to_str(oca: &Option<OcaGroup>) -> String {
     }
 }
 
-fn get_execution(order: &Order) -> &Execution {
-    match *order.status() {
-        OrderStatus::Filled(ref exec) => exec,
-        _ => {
-            panic!("Order is not filled: {:#?}", order);
+fn signal_strength_to_str(signal: Option<&SignalMetadata>) -> String {
+    match signal.and_then(|signal| signal.strength()) {
+        Some(strength) => strength.to_string(),
+        None => String::from("None")
+    }
+}
+
+/// Tags as a JSON object, so that keys and values can hold any character
+fn signal_tags_to_str(signal: Option<&SignalMetadata>) -> String {
+    match signal {
+        Some(signal) => serde_json::to_string(signal.tags()).unwrap(),
+        None => String::new()
+    }
+}
+
+fn signal_label_to_str(signal: Option<&SignalMetadata>) -> String {
+    signal.map(|signal| signal.label().clone()).unwrap_or_default()
+}
+
+/// Average of the executions of an order, filled or cancelled after a partial fill
+fn get_execution(order: &Order) -> Execution {
+    match order.average_execution() {
+        Some(execution) => execution,
+        None => {
+            panic!("Order is not executed: {:#?}", order);
         }
     }
 }
@@ -70,7 +107,15 @@ pub fn write_order_pairs_to_csv<W>(writer: &mut csv::Writer<W>, order_pairs: &Ve
         "ExitSymbolId",
         "ExitExecutionPrice",
         "ExitExecutionDatetime",
-        "ExitExecutionQuantity"
+        "ExitExecutionQuantity",
+        "EntrySignalLabel",
+        "EntrySignalStrength",
+        "EntrySignalTags",
+        "ExitSignalLabel",
+        "ExitSignalStrength",
+        "ExitSignalTags",
+        "EntryOrderTimeInForce",
+        "ExitOrderTimeInForce"
     ])?;
 
     for order_pair in order_pairs {
@@ -97,7 +142,15 @@ pub fn write_order_pairs_to_csv<W>(writer: &mut csv::Writer<W>, order_pairs: &Ve
             order_pair.exit_order.symbol_id().clone(),
             exit_execution.price().to_string().clone(),
             exit_execution.datetime().to_string().clone(),
-            exit_execution.quantity().to_string().clone()
+            exit_execution.quantity().to_string().clone(),
+            signal_label_to_str(order_pair.entry_signal()),
+            signal_strength_to_str(order_pair.entry_signal()),
+            signal_tags_to_str(order_pair.entry_signal()),
+            signal_label_to_str(order_pair.exit_signal()),
+            signal_strength_to_str(order_pair.exit_signal()),
+            signal_tags_to_str(order_pair.exit_signal()),
+            time_in_force_to_str(order_pair.entry_order.time_in_force()),
+            time_in_force_to_str(order_pair.exit_order.time_in_force())
         ])?;
     }
 
@@ -105,3 +158,21 @@ pub fn write_order_pairs_to_csv<W>(writer: &mut csv::Writer<W>, order_pairs: &Ve
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use self::chrono::prelude::TimeZone;
+    use signal::Signal;
+    use symbol::SymbolId;
+
+    #[test]
+    fn signal_tags() {
+        let signal = Signal::new(SymbolId::from("eur/usd"), Direction::Long, Utc.ymd(2016, 1, 3).and_hms(17, 0, 0), String::new())
+            .set_tag(String::from("reason"), String::from("rsi=30; \"oversold\""))
+            .set_tag(String::from("model"), String::from("a"))
+            .metadata();
+        assert_eq!(signal_tags_to_str(Some(&signal)), r#"{"model":"a","reason":"rsi=30; \"oversold\""}"#);
+        assert_eq!(signal_tags_to_str(None), "");
+    }
+}
@@ -1,19 +1,20 @@
 extern crate backtester;
 extern crate chrono;
-extern crate time;
 extern crate csv;
 extern crate serde_json;
 
+use chrono::Duration;
 use chrono::prelude::{Utc, TimeZone};
 use backtester::ohlcv::source::{OhlcvSource, CsvOhlcvSource};
 use backtester::backtester::Backtester;
 use backtester::model::{Model, ModelId};
 use backtester::strategy::Strategy;
-use backtester::signal::detector::{Once, Always};
+use backtester::signal::detector::Always;
 use backtester::direction::Direction;
-use backtester::order::{Order, OrderStatus, OrderKind};
+use backtester::order::{Order, OrderKind, Bracket, BracketDistance};
 use backtester::order::policy::SimpleOrderPolicy;
 use backtester::symbol::SymbolId;
+use backtester::price::Price;
 use backtester::util::record_parser::RecordParser;
 use backtester::util::{get_order_pairs, write_order_pairs_to_csv};
 
@@ -36,31 +37,11 @@ impl Model for OrderEveryCandle {
     }
 
     fn exit_strategies(&self, entry_order: &Order) -> Vec<Strategy> {
-        let execution = entry_order.execution().expect("Entry order not executed");
-
-        let stop_loss_strategy = Strategy::new(
-            Box::new(Once::new(self.symbol_id.clone(), Direction::Short)),
-            Box::new(SimpleOrderPolicy::new(OrderKind::StopOrder(execution.price() - 0.0005))
-                .set_oca(Some(entry_order.id().clone()))
-            )
-        );
-
-        let timeout_strategy = Strategy::new(
-            Box::new(Once::new(self.symbol_id.clone(), Direction::Short)),
-            Box::new(SimpleOrderPolicy::new(OrderKind::MarketOrder)
-                .set_active_after(Some(execution.datetime().clone() + time::Duration::minutes(120)))
-                .set_oca(Some(entry_order.id().clone()))
-            )
-        );
-
-        let take_profit_strategy = Strategy::new(
-            Box::new(Once::new(self.symbol_id.clone(), Direction::Short)),
-            Box::new(SimpleOrderPolicy::new(OrderKind::LimitOrder(execution.price() + 0.0005))
-                .set_oca(Some(entry_order.id().clone()))
-            )
-        );
-
-        return vec![stop_loss_strategy, timeout_strategy, take_profit_strategy]
+        Bracket::new()
+            .set_stop(Some(BracketDistance::Amount(Price::new(0.0005))))
+            .set_target(Some(BracketDistance::Amount(Price::new(0.0005))))
+            .set_timeout(Some(Duration::minutes(120)))
+            .exit_strategies(entry_order)
     }
 
 }